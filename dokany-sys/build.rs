@@ -1,6 +1,13 @@
 use std::path::PathBuf;
 
 fn main() {
+    // Dokan is only available on Windows.
+    // Other targets use a simulated driver instead.
+    let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap();
+    if target_os != "windows" {
+        return;
+    }
+
     let target_arch = std::env::var("CARGO_CFG_TARGET_ARCH").unwrap();
     let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR")
         .map(PathBuf::from)
@@ -1,87 +1,21 @@
 #![allow(non_snake_case)]
 #![allow(non_camel_case_types)]
 
+#[cfg(not(windows))]
+mod portable;
+#[cfg(not(windows))]
+mod simulated;
+#[cfg(windows)]
+mod win32;
+
+#[cfg(not(windows))]
+pub use self::portable::*;
+#[cfg(not(windows))]
+pub use self::simulated::*;
+#[cfg(windows)]
+pub use self::win32::*;
 pub use std::os::raw::c_int;
 pub use std::os::raw::c_void;
-pub use std::os::windows::raw::HANDLE;
-pub use windows_sys::core::PCWSTR;
-pub use windows_sys::core::PWSTR;
-pub use windows_sys::Win32::Foundation::BOOL;
-pub use windows_sys::Win32::Foundation::BOOLEAN;
-pub use windows_sys::Win32::Foundation::CHAR;
-pub use windows_sys::Win32::Foundation::FALSE;
-pub use windows_sys::Win32::Foundation::FILETIME;
-pub use windows_sys::Win32::Foundation::MAX_PATH;
-pub use windows_sys::Win32::Foundation::NTSTATUS;
-pub use windows_sys::Win32::Foundation::STATUS_INTERNAL_ERROR;
-pub use windows_sys::Win32::Foundation::STATUS_NOT_IMPLEMENTED;
-pub use windows_sys::Win32::Foundation::STATUS_NO_SUCH_FILE;
-pub use windows_sys::Win32::Foundation::STATUS_SUCCESS;
-pub use windows_sys::Win32::Foundation::TRUE;
-pub use windows_sys::Win32::Security::SECURITY_DESCRIPTOR;
-pub use windows_sys::Win32::Storage::FileSystem::BY_HANDLE_FILE_INFORMATION;
-pub use windows_sys::Win32::Storage::FileSystem::DELETE;
-pub use windows_sys::Win32::Storage::FileSystem::FILE_ACCESS_FLAGS;
-pub use windows_sys::Win32::Storage::FileSystem::FILE_ADD_FILE;
-pub use windows_sys::Win32::Storage::FileSystem::FILE_ADD_SUBDIRECTORY;
-pub use windows_sys::Win32::Storage::FileSystem::FILE_ALL_ACCESS;
-pub use windows_sys::Win32::Storage::FileSystem::FILE_APPEND_DATA;
-pub use windows_sys::Win32::Storage::FileSystem::FILE_CREATE_PIPE_INSTANCE;
-pub use windows_sys::Win32::Storage::FileSystem::FILE_DELETE_CHILD;
-pub use windows_sys::Win32::Storage::FileSystem::FILE_EXECUTE;
-pub use windows_sys::Win32::Storage::FileSystem::FILE_LIST_DIRECTORY;
-pub use windows_sys::Win32::Storage::FileSystem::FILE_READ_ATTRIBUTES;
-pub use windows_sys::Win32::Storage::FileSystem::FILE_READ_DATA;
-pub use windows_sys::Win32::Storage::FileSystem::FILE_READ_EA;
-pub use windows_sys::Win32::Storage::FileSystem::FILE_TRAVERSE;
-pub use windows_sys::Win32::Storage::FileSystem::FILE_WRITE_ATTRIBUTES;
-pub use windows_sys::Win32::Storage::FileSystem::FILE_WRITE_DATA;
-pub use windows_sys::Win32::Storage::FileSystem::FILE_WRITE_EA;
-pub use windows_sys::Win32::Storage::FileSystem::READ_CONTROL;
-pub use windows_sys::Win32::Storage::FileSystem::SPECIFIC_RIGHTS_ALL;
-pub use windows_sys::Win32::Storage::FileSystem::STANDARD_RIGHTS_ALL;
-pub use windows_sys::Win32::Storage::FileSystem::STANDARD_RIGHTS_EXECUTE;
-pub use windows_sys::Win32::Storage::FileSystem::STANDARD_RIGHTS_READ;
-pub use windows_sys::Win32::Storage::FileSystem::STANDARD_RIGHTS_REQUIRED;
-pub use windows_sys::Win32::Storage::FileSystem::STANDARD_RIGHTS_WRITE;
-pub use windows_sys::Win32::Storage::FileSystem::SYNCHRONIZE;
-pub use windows_sys::Win32::Storage::FileSystem::WIN32_FIND_DATAW;
-pub use windows_sys::Win32::Storage::FileSystem::WIN32_FIND_STREAM_DATA;
-pub use windows_sys::Win32::Storage::FileSystem::WRITE_DAC;
-pub use windows_sys::Win32::Storage::FileSystem::WRITE_OWNER;
-pub use windows_sys::Win32::System::SystemServices::ACCESS_SYSTEM_SECURITY;
-pub use windows_sys::Win32::System::SystemServices::FILE_CASE_PRESERVED_NAMES;
-pub use windows_sys::Win32::System::SystemServices::FILE_CASE_SENSITIVE_SEARCH;
-pub use windows_sys::Win32::System::SystemServices::FILE_DAX_VOLUME;
-pub use windows_sys::Win32::System::SystemServices::FILE_FILE_COMPRESSION;
-pub use windows_sys::Win32::System::SystemServices::FILE_NAMED_STREAMS;
-pub use windows_sys::Win32::System::SystemServices::FILE_PERSISTENT_ACLS;
-pub use windows_sys::Win32::System::SystemServices::FILE_READ_ONLY_VOLUME;
-pub use windows_sys::Win32::System::SystemServices::FILE_RETURNS_CLEANUP_RESULT_INFO;
-pub use windows_sys::Win32::System::SystemServices::FILE_SEQUENTIAL_WRITE_ONCE;
-pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_BLOCK_REFCOUNTING;
-pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_ENCRYPTION;
-pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_EXTENDED_ATTRIBUTES;
-pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_GHOSTING;
-pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_HARD_LINKS;
-pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_INTEGRITY_STREAMS;
-pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_OBJECT_IDS;
-pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_OPEN_BY_FILE_ID;
-pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_POSIX_UNLINK_RENAME;
-pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_REMOTE_STORAGE;
-pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_REPARSE_POINTS;
-pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_SPARSE_FILES;
-pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_SPARSE_VDL;
-pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_TRANSACTIONS;
-pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_USN_JOURNAL;
-pub use windows_sys::Win32::System::SystemServices::FILE_UNICODE_ON_DISK;
-pub use windows_sys::Win32::System::SystemServices::FILE_VOLUME_IS_COMPRESSED;
-pub use windows_sys::Win32::System::SystemServices::FILE_VOLUME_QUOTAS;
-pub use windows_sys::Win32::System::SystemServices::GENERIC_ALL;
-pub use windows_sys::Win32::System::SystemServices::GENERIC_EXECUTE;
-pub use windows_sys::Win32::System::SystemServices::GENERIC_READ;
-pub use windows_sys::Win32::System::SystemServices::GENERIC_WRITE;
-pub use windows_sys::Win32::System::SystemServices::MAXIMUM_ALLOWED;
 
 // Primitives
 pub type USHORT = u16;
@@ -296,23 +230,74 @@ pub struct DOKAN_FILE_INFO {
 
 pub type PDOKAN_FILE_INFO = *mut DOKAN_FILE_INFO;
 
-pub type PDOKAN_IO_SECURITY_CONTEXT = *mut c_void;
+/// The access state of a create request.
+#[repr(C)]
+pub struct DOKAN_ACCESS_STATE {
+    /// The security descriptor was evaluated.
+    pub SecurityEvaluated: BOOLEAN,
+
+    /// An audit should be generated.
+    pub GenerateAudit: BOOLEAN,
+
+    /// An audit should be generated on close.
+    pub GenerateOnClose: BOOLEAN,
+
+    /// Privileges should be audited.
+    pub AuditPrivileges: BOOLEAN,
+
+    /// Access state flags.
+    pub Flags: ULONG,
+
+    /// The access rights that have not been granted yet.
+    pub RemainingDesiredAccess: ACCESS_MASK,
+
+    /// The access rights that have already been granted.
+    pub PreviouslyGrantedAccess: ACCESS_MASK,
+
+    /// The originally requested access rights.
+    pub OriginalDesiredAccess: ACCESS_MASK,
+
+    /// The security descriptor to assign to a new file.
+    ///
+    /// This is a self-relative security descriptor, and may be null.
+    pub SecurityDescriptor: PSECURITY_DESCRIPTOR,
+
+    /// The name of the object.
+    pub ObjectName: UNICODE_STRING,
+
+    /// The type of the object.
+    pub ObjectType: UNICODE_STRING,
+}
+
+pub type PDOKAN_ACCESS_STATE = *mut DOKAN_ACCESS_STATE;
+
+/// The security context of a create request.
+#[repr(C)]
+pub struct DOKAN_IO_SECURITY_CONTEXT {
+    /// The access state of the request.
+    pub AccessState: DOKAN_ACCESS_STATE,
+
+    /// The requested access rights.
+    pub DesiredAccess: ACCESS_MASK,
+}
+
+pub type PDOKAN_IO_SECURITY_CONTEXT = *mut DOKAN_IO_SECURITY_CONTEXT;
 
 /// FillFindData Used to add an entry in FindFiles operation
 ///
 /// # Return
 /// 1 if buffer is full, otherwise 0 (currently it never returns 1)
 pub type PFillFindData =
-    Option<unsafe extern "stdcall" fn(PWIN32_FIND_DATAW, PDOKAN_FILE_INFO) -> c_int>;
+    Option<unsafe extern "system" fn(PWIN32_FIND_DATAW, PDOKAN_FILE_INFO) -> c_int>;
 
 /// FillFindStreamData Used to add an entry in FindStreams
 ///
 /// # Return
 /// `FALSE` if the buffer is full, otherwise TRUE
 pub type PFillFindStreamData =
-    Option<unsafe extern "stdcall" fn(PWIN32_FIND_STREAM_DATA, PVOID) -> BOOL>;
+    Option<unsafe extern "system" fn(PWIN32_FIND_STREAM_DATA, PVOID) -> BOOL>;
 
-pub type ZwCreateFileCallback = unsafe extern "stdcall" fn(
+pub type ZwCreateFileCallback = unsafe extern "system" fn(
     FileName: LPCWSTR,
     SecurityContext: PDOKAN_IO_SECURITY_CONTEXT,
     DesiredAccess: ACCESS_MASK,
@@ -323,10 +308,10 @@ pub type ZwCreateFileCallback = unsafe extern "stdcall" fn(
     DokanFileInfo: PDOKAN_FILE_INFO,
 ) -> NTSTATUS;
 pub type CleanupCallback =
-    unsafe extern "stdcall" fn(FileName: LPCWSTR, DokanFileInfo: PDOKAN_FILE_INFO);
+    unsafe extern "system" fn(FileName: LPCWSTR, DokanFileInfo: PDOKAN_FILE_INFO);
 pub type CloseFileCallback =
-    unsafe extern "stdcall" fn(FileName: LPCWSTR, DokanFileInfo: PDOKAN_FILE_INFO);
-pub type ReadFileCallback = unsafe extern "stdcall" fn(
+    unsafe extern "system" fn(FileName: LPCWSTR, DokanFileInfo: PDOKAN_FILE_INFO);
+pub type ReadFileCallback = unsafe extern "system" fn(
     FileName: LPCWSTR,
     Buffer: LPVOID,
     BufferLength: DWORD,
@@ -334,7 +319,7 @@ pub type ReadFileCallback = unsafe extern "stdcall" fn(
     Offset: LONGLONG,
     DokanFileInfo: PDOKAN_FILE_INFO,
 ) -> NTSTATUS;
-pub type WriteFileCallback = unsafe extern "stdcall" fn(
+pub type WriteFileCallback = unsafe extern "system" fn(
     FileName: LPCWSTR,
     Buffer: LPCVOID,
     NumberOfBytesToWrite: DWORD,
@@ -343,29 +328,29 @@ pub type WriteFileCallback = unsafe extern "stdcall" fn(
     DokanFileInfo: PDOKAN_FILE_INFO,
 ) -> NTSTATUS;
 pub type FlushFileBuffersCallback =
-    unsafe extern "stdcall" fn(FileName: LPCWSTR, DokanFileInfo: PDOKAN_FILE_INFO) -> NTSTATUS;
-pub type GetFileInformationCallback = unsafe extern "stdcall" fn(
+    unsafe extern "system" fn(FileName: LPCWSTR, DokanFileInfo: PDOKAN_FILE_INFO) -> NTSTATUS;
+pub type GetFileInformationCallback = unsafe extern "system" fn(
     FileName: LPCWSTR,
     Buffer: LPBY_HANDLE_FILE_INFORMATION,
     DokanFileInfo: PDOKAN_FILE_INFO,
-);
-pub type FindFilesCallback = unsafe extern "stdcall" fn(
+) -> NTSTATUS;
+pub type FindFilesCallback = unsafe extern "system" fn(
     FileName: LPCWSTR,
     FillFindData: PFillFindData,
     DokanFileInfo: PDOKAN_FILE_INFO,
 ) -> NTSTATUS;
-pub type FindFilesWithPatternCallback = unsafe extern "stdcall" fn(
+pub type FindFilesWithPatternCallback = unsafe extern "system" fn(
     PathName: LPCWSTR,
     SearchPattern: LPCWSTR,
     FillFindData: PFillFindData,
     DokanFileInfo: PDOKAN_FILE_INFO,
 ) -> NTSTATUS;
-pub type SetFileAttributesCallback = unsafe extern "stdcall" fn(
+pub type SetFileAttributesCallback = unsafe extern "system" fn(
     FileName: LPCWSTR,
     FileAttributes: DWORD,
     DokanFileInfo: PDOKAN_FILE_INFO,
 ) -> NTSTATUS;
-pub type SetFileTimeCallback = unsafe extern "stdcall" fn(
+pub type SetFileTimeCallback = unsafe extern "system" fn(
     FileName: LPCWSTR,
     CreationTime: *const FILETIME,
     LastAccessTime: *const FILETIME,
@@ -373,44 +358,44 @@ pub type SetFileTimeCallback = unsafe extern "stdcall" fn(
     DokanFileInfo: PDOKAN_FILE_INFO,
 ) -> NTSTATUS;
 pub type DeleteFileCallback =
-    unsafe extern "stdcall" fn(FileName: LPCWSTR, DokanFileInfo: PDOKAN_FILE_INFO) -> NTSTATUS;
+    unsafe extern "system" fn(FileName: LPCWSTR, DokanFileInfo: PDOKAN_FILE_INFO) -> NTSTATUS;
 pub type DeleteDirectoryCallback =
-    unsafe extern "stdcall" fn(FileName: LPCWSTR, DokanFileInfo: PDOKAN_FILE_INFO) -> NTSTATUS;
-pub type MoveFileCallback = unsafe extern "stdcall" fn(
+    unsafe extern "system" fn(FileName: LPCWSTR, DokanFileInfo: PDOKAN_FILE_INFO) -> NTSTATUS;
+pub type MoveFileCallback = unsafe extern "system" fn(
     FileName: LPCWSTR,
     NewFileName: LPCWSTR,
     ReplaceIfExisting: BOOL,
     DokanFileInfo: PDOKAN_FILE_INFO,
 ) -> NTSTATUS;
-pub type SetEndOfFileCallback = unsafe extern "stdcall" fn(
+pub type SetEndOfFileCallback = unsafe extern "system" fn(
     FileName: LPCWSTR,
     ByteOffset: LONGLONG,
     DokanFileInfo: PDOKAN_FILE_INFO,
 ) -> NTSTATUS;
-pub type SetAllocationSizeCallback = unsafe extern "stdcall" fn(
+pub type SetAllocationSizeCallback = unsafe extern "system" fn(
     FileName: LPCWSTR,
     AllocSize: LONGLONG,
     DokanFileInfo: PDOKAN_FILE_INFO,
 ) -> NTSTATUS;
-pub type LockFileCallback = unsafe extern "stdcall" fn(
+pub type LockFileCallback = unsafe extern "system" fn(
     FileName: LPCWSTR,
     ByteOffset: LONGLONG,
     Length: LONGLONG,
     DokanFileInfo: PDOKAN_FILE_INFO,
 ) -> NTSTATUS;
-pub type UnlockFileCallback = unsafe extern "stdcall" fn(
+pub type UnlockFileCallback = unsafe extern "system" fn(
     FileName: LPCWSTR,
     ByteOffset: LONGLONG,
     Length: LONGLONG,
     DokanFileInfo: PDOKAN_FILE_INFO,
 ) -> NTSTATUS;
-pub type GetDiskFreeSpaceCallback = unsafe extern "stdcall" fn(
+pub type GetDiskFreeSpaceCallback = unsafe extern "system" fn(
     FreeBytesAvailable: PULONGLONG,
     TotalNumberOfBytes: PULONGLONG,
     TotalNumberOfFreeBytes: PULONGLONG,
     DokanFileInfo: PDOKAN_FILE_INFO,
 ) -> NTSTATUS;
-pub type GetVolumeInformationCallback = unsafe extern "stdcall" fn(
+pub type GetVolumeInformationCallback = unsafe extern "system" fn(
     VolumeNameBuffer: LPWSTR,
     VolumeNameSize: DWORD,
     VolumeSerialNumber: LPDWORD,
@@ -421,9 +406,9 @@ pub type GetVolumeInformationCallback = unsafe extern "stdcall" fn(
     DokanFileInfo: PDOKAN_FILE_INFO,
 ) -> NTSTATUS;
 pub type MountedCallback =
-    unsafe extern "stdcall" fn(MountPoint: LPCWSTR, DokanFileInfo: PDOKAN_FILE_INFO) -> NTSTATUS;
-pub type Unmounted = unsafe extern "stdcall" fn(DokanFileInfo: PDOKAN_FILE_INFO) -> NTSTATUS;
-pub type GetFileSecurityCallback = unsafe extern "stdcall" fn(
+    unsafe extern "system" fn(MountPoint: LPCWSTR, DokanFileInfo: PDOKAN_FILE_INFO) -> NTSTATUS;
+pub type Unmounted = unsafe extern "system" fn(DokanFileInfo: PDOKAN_FILE_INFO) -> NTSTATUS;
+pub type GetFileSecurityCallback = unsafe extern "system" fn(
     FileName: LPCWSTR,
     SecurityInformation: PSECURITY_INFORMATION,
     SecurityDescriptor: PSECURITY_DESCRIPTOR,
@@ -431,14 +416,14 @@ pub type GetFileSecurityCallback = unsafe extern "stdcall" fn(
     LengthNeeded: PULONG,
     DokanFileInfo: PDOKAN_FILE_INFO,
 ) -> NTSTATUS;
-pub type SetFileSecurityCallback = unsafe extern "stdcall" fn(
+pub type SetFileSecurityCallback = unsafe extern "system" fn(
     FileName: LPCWSTR,
     SecurityInformation: PSECURITY_INFORMATION,
     SecurityDescriptor: PSECURITY_DESCRIPTOR,
     BufferLength: ULONG,
     DokanFileInfo: PDOKAN_FILE_INFO,
 ) -> NTSTATUS;
-pub type FindStreamsCallback = unsafe extern "stdcall" fn(
+pub type FindStreamsCallback = unsafe extern "system" fn(
     FileName: LPCWSTR,
     FillFindStreamData: PFillFindStreamData,
     FindStreamContext: PVOID,
@@ -657,7 +642,7 @@ pub struct DOKAN_OPERATIONS {
 
     /// SetFileTime Dokan API callback
     ///
-    /// Set file attributes on a specific file
+    /// Set file times on a specific file
     ///
     /// # Arguments
     /// `FileName`: File path requested by the Kernel on the FileSystem.
@@ -1024,7 +1009,8 @@ pub struct DOKAN_MOUNT_POINT_INFO {
 
 pub type PDOKAN_MOUNT_POINT_INFO = *mut DOKAN_MOUNT_POINT_INFO;
 
-extern "stdcall" {
+#[cfg(windows)]
+extern "system" {
     /// Initialize all required Dokan internal resources.
     ///
     /// This needs to be called only once before trying to use [DokanMain] or [DokanCreateFileSystem] for the first time.
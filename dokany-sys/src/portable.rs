@@ -0,0 +1,279 @@
+//! Pure-Rust definitions of the Win32 items used by Dokan.
+//!
+//! These mirror the definitions in `windows-sys`, which cannot be linked on non-Windows targets.
+
+use crate::c_void;
+
+pub type HANDLE = *mut c_void;
+
+// Core
+pub type PCWSTR = *const u16;
+pub type PWSTR = *mut u16;
+
+// Foundation
+pub type BOOL = i32;
+pub type BOOLEAN = u8;
+pub type CHAR = u8;
+pub const FALSE: BOOL = 0i32;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct FILETIME {
+    pub dwLowDateTime: u32,
+    pub dwHighDateTime: u32,
+}
+
+pub const MAX_PATH: u32 = 260u32;
+pub type NTSTATUS = i32;
+pub const STATUS_ACCESS_DENIED: NTSTATUS = -1073741790i32;
+pub const STATUS_BUFFER_OVERFLOW: NTSTATUS = -2147483643i32;
+pub const STATUS_BUFFER_TOO_SMALL: NTSTATUS = -1073741789i32;
+pub const STATUS_CANCELLED: NTSTATUS = -1073741536i32;
+pub const STATUS_CANNOT_DELETE: NTSTATUS = -1073741535i32;
+pub const STATUS_DATA_ERROR: NTSTATUS = -1073741762i32;
+pub const STATUS_DELETE_PENDING: NTSTATUS = -1073741738i32;
+pub const STATUS_DIRECTORY_NOT_EMPTY: NTSTATUS = -1073741567i32;
+pub const STATUS_DISK_FULL: NTSTATUS = -1073741697i32;
+pub const STATUS_END_OF_FILE: NTSTATUS = -1073741807i32;
+pub const STATUS_FILE_CLOSED: NTSTATUS = -1073741528i32;
+pub const STATUS_FILE_IS_A_DIRECTORY: NTSTATUS = -1073741638i32;
+pub const STATUS_FILE_LOCK_CONFLICT: NTSTATUS = -1073741740i32;
+pub const STATUS_FILE_TOO_LARGE: NTSTATUS = -1073739516i32;
+pub const STATUS_INSUFFICIENT_RESOURCES: NTSTATUS = -1073741670i32;
+pub const STATUS_INTERNAL_ERROR: NTSTATUS = -1073741595i32;
+pub const STATUS_INVALID_DEVICE_REQUEST: NTSTATUS = -1073741808i32;
+pub const STATUS_INVALID_HANDLE: NTSTATUS = -1073741816i32;
+pub const STATUS_INVALID_PARAMETER: NTSTATUS = -1073741811i32;
+pub const STATUS_IO_DEVICE_ERROR: NTSTATUS = -1073741435i32;
+pub const STATUS_IO_TIMEOUT: NTSTATUS = -1073741643i32;
+pub const STATUS_LOCK_NOT_GRANTED: NTSTATUS = -1073741739i32;
+pub const STATUS_MEDIA_WRITE_PROTECTED: NTSTATUS = -1073741662i32;
+pub const STATUS_NAME_TOO_LONG: NTSTATUS = -1073741562i32;
+pub const STATUS_NOT_A_DIRECTORY: NTSTATUS = -1073741565i32;
+pub const STATUS_NOT_IMPLEMENTED: NTSTATUS = -1073741822i32;
+pub const STATUS_NOT_SAME_DEVICE: NTSTATUS = -1073741612i32;
+pub const STATUS_NOT_SUPPORTED: NTSTATUS = -1073741637i32;
+pub const STATUS_NO_MEMORY: NTSTATUS = -1073741801i32;
+pub const STATUS_NO_MORE_FILES: NTSTATUS = -2147483642i32;
+pub const STATUS_NO_SUCH_FILE: NTSTATUS = -1073741809i32;
+pub const STATUS_OBJECT_NAME_COLLISION: NTSTATUS = -1073741771i32;
+pub const STATUS_OBJECT_NAME_INVALID: NTSTATUS = -1073741773i32;
+pub const STATUS_OBJECT_NAME_NOT_FOUND: NTSTATUS = -1073741772i32;
+pub const STATUS_OBJECT_PATH_INVALID: NTSTATUS = -1073741767i32;
+pub const STATUS_OBJECT_PATH_NOT_FOUND: NTSTATUS = -1073741766i32;
+pub const STATUS_RANGE_NOT_LOCKED: NTSTATUS = -1073741698i32;
+pub const STATUS_SHARING_VIOLATION: NTSTATUS = -1073741757i32;
+pub const STATUS_SUCCESS: NTSTATUS = 0i32;
+pub const STATUS_TOO_MANY_LINKS: NTSTATUS = -1073741211i32;
+pub const STATUS_UNSUCCESSFUL: NTSTATUS = -1073741823i32;
+pub const TRUE: BOOL = 1i32;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct UNICODE_STRING {
+    pub Length: u16,
+    pub MaximumLength: u16,
+    pub Buffer: PWSTR,
+}
+pub const WAIT_OBJECT_0: u32 = 0u32;
+pub const WAIT_TIMEOUT: u32 = 258u32;
+
+// Security
+pub const ATTRIBUTE_SECURITY_INFORMATION: OBJECT_SECURITY_INFORMATION = 32u32;
+pub const BACKUP_SECURITY_INFORMATION: OBJECT_SECURITY_INFORMATION = 65536u32;
+pub const DACL_SECURITY_INFORMATION: OBJECT_SECURITY_INFORMATION = 4u32;
+pub const GROUP_SECURITY_INFORMATION: OBJECT_SECURITY_INFORMATION = 2u32;
+pub const LABEL_SECURITY_INFORMATION: OBJECT_SECURITY_INFORMATION = 16u32;
+pub type OBJECT_SECURITY_INFORMATION = u32;
+pub const OWNER_SECURITY_INFORMATION: OBJECT_SECURITY_INFORMATION = 1u32;
+pub const PROTECTED_DACL_SECURITY_INFORMATION: OBJECT_SECURITY_INFORMATION = 2147483648u32;
+pub const PROTECTED_SACL_SECURITY_INFORMATION: OBJECT_SECURITY_INFORMATION = 1073741824u32;
+pub const SACL_SECURITY_INFORMATION: OBJECT_SECURITY_INFORMATION = 8u32;
+pub const SCOPE_SECURITY_INFORMATION: OBJECT_SECURITY_INFORMATION = 64u32;
+pub const UNPROTECTED_DACL_SECURITY_INFORMATION: OBJECT_SECURITY_INFORMATION = 536870912u32;
+pub const UNPROTECTED_SACL_SECURITY_INFORMATION: OBJECT_SECURITY_INFORMATION = 268435456u32;
+pub type PSID = *mut c_void;
+pub type SECURITY_DESCRIPTOR_CONTROL = u16;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct ACL {
+    pub AclRevision: u8,
+    pub Sbz1: u8,
+    pub AclSize: u16,
+    pub AceCount: u16,
+    pub Sbz2: u16,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct SECURITY_DESCRIPTOR {
+    pub Revision: u8,
+    pub Sbz1: u8,
+    pub Control: SECURITY_DESCRIPTOR_CONTROL,
+    pub Owner: PSID,
+    pub Group: PSID,
+    pub Sacl: *mut ACL,
+    pub Dacl: *mut ACL,
+}
+
+// Storage::FileSystem
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct BY_HANDLE_FILE_INFORMATION {
+    pub dwFileAttributes: u32,
+    pub ftCreationTime: FILETIME,
+    pub ftLastAccessTime: FILETIME,
+    pub ftLastWriteTime: FILETIME,
+    pub dwVolumeSerialNumber: u32,
+    pub nFileSizeHigh: u32,
+    pub nFileSizeLow: u32,
+    pub nNumberOfLinks: u32,
+    pub nFileIndexHigh: u32,
+    pub nFileIndexLow: u32,
+}
+
+pub const DELETE: FILE_ACCESS_FLAGS = 65536u32;
+pub type FILE_ACCESS_FLAGS = u32;
+pub const FILE_ADD_FILE: FILE_ACCESS_FLAGS = 2u32;
+pub const FILE_ADD_SUBDIRECTORY: FILE_ACCESS_FLAGS = 4u32;
+pub const FILE_ALL_ACCESS: FILE_ACCESS_FLAGS = 2032127u32;
+pub const FILE_APPEND_DATA: FILE_ACCESS_FLAGS = 4u32;
+pub const FILE_ATTRIBUTE_ARCHIVE: FILE_FLAGS_AND_ATTRIBUTES = 32u32;
+pub const FILE_ATTRIBUTE_COMPRESSED: FILE_FLAGS_AND_ATTRIBUTES = 2048u32;
+pub const FILE_ATTRIBUTE_DEVICE: FILE_FLAGS_AND_ATTRIBUTES = 64u32;
+pub const FILE_ATTRIBUTE_DIRECTORY: FILE_FLAGS_AND_ATTRIBUTES = 16u32;
+pub const FILE_ATTRIBUTE_ENCRYPTED: FILE_FLAGS_AND_ATTRIBUTES = 16384u32;
+pub const FILE_ATTRIBUTE_HIDDEN: FILE_FLAGS_AND_ATTRIBUTES = 2u32;
+pub const FILE_ATTRIBUTE_INTEGRITY_STREAM: FILE_FLAGS_AND_ATTRIBUTES = 32768u32;
+pub const FILE_ATTRIBUTE_NORMAL: FILE_FLAGS_AND_ATTRIBUTES = 128u32;
+pub const FILE_ATTRIBUTE_NOT_CONTENT_INDEXED: FILE_FLAGS_AND_ATTRIBUTES = 8192u32;
+pub const FILE_ATTRIBUTE_NO_SCRUB_DATA: FILE_FLAGS_AND_ATTRIBUTES = 131072u32;
+pub const FILE_ATTRIBUTE_OFFLINE: FILE_FLAGS_AND_ATTRIBUTES = 4096u32;
+pub const FILE_ATTRIBUTE_PINNED: FILE_FLAGS_AND_ATTRIBUTES = 524288u32;
+pub const FILE_ATTRIBUTE_READONLY: FILE_FLAGS_AND_ATTRIBUTES = 1u32;
+pub const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: FILE_FLAGS_AND_ATTRIBUTES = 4194304u32;
+pub const FILE_ATTRIBUTE_RECALL_ON_OPEN: FILE_FLAGS_AND_ATTRIBUTES = 262144u32;
+pub const FILE_ATTRIBUTE_REPARSE_POINT: FILE_FLAGS_AND_ATTRIBUTES = 1024u32;
+pub const FILE_ATTRIBUTE_SPARSE_FILE: FILE_FLAGS_AND_ATTRIBUTES = 512u32;
+pub const FILE_ATTRIBUTE_SYSTEM: FILE_FLAGS_AND_ATTRIBUTES = 4u32;
+pub const FILE_ATTRIBUTE_TEMPORARY: FILE_FLAGS_AND_ATTRIBUTES = 256u32;
+pub const FILE_ATTRIBUTE_UNPINNED: FILE_FLAGS_AND_ATTRIBUTES = 1048576u32;
+pub const FILE_ATTRIBUTE_VIRTUAL: FILE_FLAGS_AND_ATTRIBUTES = 65536u32;
+pub const FILE_CREATE: NT_CREATE_FILE_DISPOSITION = 2u32;
+pub const FILE_CREATE_PIPE_INSTANCE: FILE_ACCESS_FLAGS = 4u32;
+pub const FILE_DELETE_CHILD: FILE_ACCESS_FLAGS = 64u32;
+pub const FILE_EXECUTE: FILE_ACCESS_FLAGS = 32u32;
+pub const FILE_FLAG_BACKUP_SEMANTICS: FILE_FLAGS_AND_ATTRIBUTES = 33554432u32;
+pub type FILE_FLAGS_AND_ATTRIBUTES = u32;
+pub const FILE_LIST_DIRECTORY: FILE_ACCESS_FLAGS = 1u32;
+pub const FILE_OPEN: NT_CREATE_FILE_DISPOSITION = 1u32;
+pub const FILE_OPEN_IF: NT_CREATE_FILE_DISPOSITION = 3u32;
+pub const FILE_OVERWRITE: NT_CREATE_FILE_DISPOSITION = 4u32;
+pub const FILE_OVERWRITE_IF: NT_CREATE_FILE_DISPOSITION = 5u32;
+pub const FILE_READ_ATTRIBUTES: FILE_ACCESS_FLAGS = 128u32;
+pub const FILE_READ_DATA: FILE_ACCESS_FLAGS = 1u32;
+pub const FILE_READ_EA: FILE_ACCESS_FLAGS = 8u32;
+pub const FILE_SHARE_DELETE: FILE_SHARE_MODE = 4u32;
+pub type FILE_SHARE_MODE = u32;
+pub const FILE_SHARE_READ: FILE_SHARE_MODE = 1u32;
+pub const FILE_SHARE_WRITE: FILE_SHARE_MODE = 2u32;
+pub const FILE_SUPERSEDE: NT_CREATE_FILE_DISPOSITION = 0u32;
+pub const FILE_TRAVERSE: FILE_ACCESS_FLAGS = 32u32;
+pub const FILE_WRITE_ATTRIBUTES: FILE_ACCESS_FLAGS = 256u32;
+pub const FILE_WRITE_DATA: FILE_ACCESS_FLAGS = 2u32;
+pub const FILE_WRITE_EA: FILE_ACCESS_FLAGS = 16u32;
+pub type NT_CREATE_FILE_DISPOSITION = u32;
+pub const READ_CONTROL: FILE_ACCESS_FLAGS = 131072u32;
+pub const SPECIFIC_RIGHTS_ALL: FILE_ACCESS_FLAGS = 65535u32;
+pub const STANDARD_RIGHTS_ALL: FILE_ACCESS_FLAGS = 2031616u32;
+pub const STANDARD_RIGHTS_EXECUTE: FILE_ACCESS_FLAGS = 131072u32;
+pub const STANDARD_RIGHTS_READ: FILE_ACCESS_FLAGS = 131072u32;
+pub const STANDARD_RIGHTS_REQUIRED: FILE_ACCESS_FLAGS = 983040u32;
+pub const STANDARD_RIGHTS_WRITE: FILE_ACCESS_FLAGS = 131072u32;
+pub const SYNCHRONIZE: FILE_ACCESS_FLAGS = 1048576u32;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct WIN32_FIND_DATAW {
+    pub dwFileAttributes: u32,
+    pub ftCreationTime: FILETIME,
+    pub ftLastAccessTime: FILETIME,
+    pub ftLastWriteTime: FILETIME,
+    pub nFileSizeHigh: u32,
+    pub nFileSizeLow: u32,
+    pub dwReserved0: u32,
+    pub dwReserved1: u32,
+    pub cFileName: [u16; 260],
+    pub cAlternateFileName: [u16; 14],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct WIN32_FIND_STREAM_DATA {
+    pub StreamSize: i64,
+    pub cStreamName: [u16; 296],
+}
+
+pub const WRITE_DAC: FILE_ACCESS_FLAGS = 262144u32;
+pub const WRITE_OWNER: FILE_ACCESS_FLAGS = 524288u32;
+
+// System::SystemServices
+pub const ACCESS_SYSTEM_SECURITY: u32 = 16777216u32;
+pub const FILE_CASE_PRESERVED_NAMES: u32 = 2u32;
+pub const FILE_CASE_SENSITIVE_SEARCH: u32 = 1u32;
+pub const FILE_DAX_VOLUME: u32 = 536870912u32;
+pub const FILE_FILE_COMPRESSION: u32 = 16u32;
+pub const FILE_NAMED_STREAMS: u32 = 262144u32;
+pub const FILE_PERSISTENT_ACLS: u32 = 8u32;
+pub const FILE_READ_ONLY_VOLUME: u32 = 524288u32;
+pub const FILE_RETURNS_CLEANUP_RESULT_INFO: u32 = 512u32;
+pub const FILE_SEQUENTIAL_WRITE_ONCE: u32 = 1048576u32;
+pub const FILE_SUPPORTS_BLOCK_REFCOUNTING: u32 = 134217728u32;
+pub const FILE_SUPPORTS_ENCRYPTION: u32 = 131072u32;
+pub const FILE_SUPPORTS_EXTENDED_ATTRIBUTES: u32 = 8388608u32;
+pub const FILE_SUPPORTS_GHOSTING: u32 = 1073741824u32;
+pub const FILE_SUPPORTS_HARD_LINKS: u32 = 4194304u32;
+pub const FILE_SUPPORTS_INTEGRITY_STREAMS: u32 = 67108864u32;
+pub const FILE_SUPPORTS_OBJECT_IDS: u32 = 65536u32;
+pub const FILE_SUPPORTS_OPEN_BY_FILE_ID: u32 = 16777216u32;
+pub const FILE_SUPPORTS_POSIX_UNLINK_RENAME: u32 = 1024u32;
+pub const FILE_SUPPORTS_REMOTE_STORAGE: u32 = 256u32;
+pub const FILE_SUPPORTS_REPARSE_POINTS: u32 = 128u32;
+pub const FILE_SUPPORTS_SPARSE_FILES: u32 = 64u32;
+pub const FILE_SUPPORTS_SPARSE_VDL: u32 = 268435456u32;
+pub const FILE_SUPPORTS_TRANSACTIONS: u32 = 2097152u32;
+pub const FILE_SUPPORTS_USN_JOURNAL: u32 = 33554432u32;
+pub const FILE_UNICODE_ON_DISK: u32 = 4u32;
+pub const FILE_VOLUME_IS_COMPRESSED: u32 = 32768u32;
+pub const FILE_VOLUME_QUOTAS: u32 = 32u32;
+pub const GENERIC_ALL: u32 = 268435456u32;
+pub const GENERIC_EXECUTE: u32 = 536870912u32;
+pub const GENERIC_READ: u32 = 2147483648u32;
+pub const GENERIC_WRITE: u32 = 1073741824u32;
+pub const MAXIMUM_ALLOWED: u32 = 33554432u32;
+
+// System::WindowsProgramming
+pub const FILE_COMPLETE_IF_OPLOCKED: u32 = 256u32;
+pub const FILE_CREATE_TREE_CONNECTION: u32 = 128u32;
+pub const FILE_DELETE_ON_CLOSE: u32 = 4096u32;
+pub const FILE_DIRECTORY_FILE: u32 = 1u32;
+pub const FILE_NON_DIRECTORY_FILE: u32 = 64u32;
+pub const FILE_NO_COMPRESSION: u32 = 32768u32;
+pub const FILE_NO_EA_KNOWLEDGE: u32 = 512u32;
+pub const FILE_NO_INTERMEDIATE_BUFFERING: u32 = 8u32;
+pub const FILE_OPEN_BY_FILE_ID: u32 = 8192u32;
+pub const FILE_OPEN_FOR_BACKUP_INTENT: u32 = 16384u32;
+pub const FILE_OPEN_FOR_FREE_SPACE_QUERY: u32 = 8388608u32;
+pub const FILE_OPEN_NO_RECALL: u32 = 4194304u32;
+pub const FILE_OPEN_REMOTE_INSTANCE: u32 = 1024u32;
+pub const FILE_OPEN_REPARSE_POINT: u32 = 2097152u32;
+pub const FILE_OPEN_REQUIRING_OPLOCK: u32 = 65536u32;
+pub const FILE_RANDOM_ACCESS: u32 = 2048u32;
+pub const FILE_RESERVE_OPFILTER: u32 = 1048576u32;
+pub const FILE_SEQUENTIAL_ONLY: u32 = 4u32;
+pub const FILE_SYNCHRONOUS_IO_ALERT: u32 = 16u32;
+pub const FILE_SYNCHRONOUS_IO_NONALERT: u32 = 32u32;
+pub const FILE_WRITE_THROUGH: u32 = 2u32;
+pub const INFINITE: u32 = 4294967295u32;
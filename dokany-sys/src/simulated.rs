@@ -0,0 +1,94 @@
+//! A simulated driver for non-Windows targets.
+//!
+//! Dokan is not available on these targets, so these functions behave as if the driver were not installed.
+//! Mounting always fails with [`DOKAN_DRIVER_INSTALL_ERROR`].
+
+// These mirror the safety requirements of the real Dokan functions.
+#![allow(clippy::missing_safety_doc)]
+
+use crate::*;
+
+pub unsafe extern "system" fn DokanInit() {}
+
+pub unsafe extern "system" fn DokanShutdown() {}
+
+pub unsafe extern "system" fn DokanMain(
+    _DokanOptions: PDOKAN_OPTIONS,
+    _DokanOperations: PDOKAN_OPERATIONS,
+) -> DokanMainResult {
+    DOKAN_DRIVER_INSTALL_ERROR
+}
+
+pub unsafe extern "system" fn DokanCreateFileSystem(
+    _DokanOptions: PDOKAN_OPTIONS,
+    _DokanOperations: PDOKAN_OPERATIONS,
+    DokanInstance: *mut DOKAN_HANDLE,
+) -> DokanMainResult {
+    if !DokanInstance.is_null() {
+        DokanInstance.write(std::ptr::null());
+    }
+
+    DOKAN_DRIVER_INSTALL_ERROR
+}
+
+pub unsafe extern "system" fn DokanIsFileSystemRunning(_DokanInstance: DOKAN_HANDLE) -> BOOL {
+    FALSE
+}
+
+pub unsafe extern "system" fn DokanWaitForFileSystemClosed(
+    _DokanInstance: DOKAN_HANDLE,
+    _dwMilliseconds: DWORD,
+) -> DWORD {
+    // WAIT_OBJECT_0, as no file system can be running.
+    0
+}
+
+pub unsafe extern "system" fn DokanCloseHandle(_DokanInstance: DOKAN_HANDLE) {}
+
+pub unsafe extern "system" fn DokanUnmount(_DriveLetter: WCHAR) -> BOOL {
+    FALSE
+}
+
+pub unsafe extern "system" fn DokanRemoveMountPoint(_MountPoint: LPCWSTR) -> BOOL {
+    FALSE
+}
+
+pub unsafe extern "system" fn DokanIsNameInExpression(
+    _Expression: LPCWSTR,
+    _Name: LPCWSTR,
+    _IgnoreCase: BOOL,
+) -> BOOL {
+    FALSE
+}
+
+pub unsafe extern "system" fn DokanVersion() -> ULONG {
+    0
+}
+
+pub unsafe extern "system" fn DokanDriverVersion() -> ULONG {
+    0
+}
+
+pub unsafe extern "system" fn DokanResetTimeout(
+    _Timeout: ULONG,
+    _DokanFileInfo: PDOKAN_FILE_INFO,
+) -> BOOL {
+    FALSE
+}
+
+pub unsafe extern "system" fn DokanOpenRequestorToken(_DokanFileInfo: PDOKAN_FILE_INFO) -> HANDLE {
+    std::ptr::null_mut()
+}
+
+pub unsafe extern "system" fn DokanGetMountPointList(
+    _uncOnly: BOOL,
+    nbRead: PULONG,
+) -> PDOKAN_MOUNT_POINT_INFO {
+    if !nbRead.is_null() {
+        nbRead.write(0);
+    }
+
+    std::ptr::null_mut()
+}
+
+pub unsafe extern "system" fn DokanReleaseMountPointList(_list: PDOKAN_MOUNT_POINT_INFO) {}
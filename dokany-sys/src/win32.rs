@@ -0,0 +1,193 @@
+//! Win32 items used by Dokan, re-exported from `windows-sys`.
+
+pub use std::os::windows::raw::HANDLE;
+pub use windows_sys::core::PCWSTR;
+pub use windows_sys::core::PWSTR;
+pub use windows_sys::Win32::Foundation::BOOL;
+pub use windows_sys::Win32::Foundation::BOOLEAN;
+pub use windows_sys::Win32::Foundation::CHAR;
+pub use windows_sys::Win32::Foundation::FALSE;
+pub use windows_sys::Win32::Foundation::FILETIME;
+pub use windows_sys::Win32::Foundation::MAX_PATH;
+pub use windows_sys::Win32::Foundation::NTSTATUS;
+pub use windows_sys::Win32::Foundation::STATUS_ACCESS_DENIED;
+pub use windows_sys::Win32::Foundation::STATUS_BUFFER_OVERFLOW;
+pub use windows_sys::Win32::Foundation::STATUS_BUFFER_TOO_SMALL;
+pub use windows_sys::Win32::Foundation::STATUS_CANCELLED;
+pub use windows_sys::Win32::Foundation::STATUS_CANNOT_DELETE;
+pub use windows_sys::Win32::Foundation::STATUS_DATA_ERROR;
+pub use windows_sys::Win32::Foundation::STATUS_DELETE_PENDING;
+pub use windows_sys::Win32::Foundation::STATUS_DIRECTORY_NOT_EMPTY;
+pub use windows_sys::Win32::Foundation::STATUS_DISK_FULL;
+pub use windows_sys::Win32::Foundation::STATUS_END_OF_FILE;
+pub use windows_sys::Win32::Foundation::STATUS_FILE_CLOSED;
+pub use windows_sys::Win32::Foundation::STATUS_FILE_IS_A_DIRECTORY;
+pub use windows_sys::Win32::Foundation::STATUS_FILE_LOCK_CONFLICT;
+pub use windows_sys::Win32::Foundation::STATUS_FILE_TOO_LARGE;
+pub use windows_sys::Win32::Foundation::STATUS_INSUFFICIENT_RESOURCES;
+pub use windows_sys::Win32::Foundation::STATUS_INTERNAL_ERROR;
+pub use windows_sys::Win32::Foundation::STATUS_INVALID_DEVICE_REQUEST;
+pub use windows_sys::Win32::Foundation::STATUS_INVALID_HANDLE;
+pub use windows_sys::Win32::Foundation::STATUS_INVALID_PARAMETER;
+pub use windows_sys::Win32::Foundation::STATUS_IO_DEVICE_ERROR;
+pub use windows_sys::Win32::Foundation::STATUS_IO_TIMEOUT;
+pub use windows_sys::Win32::Foundation::STATUS_LOCK_NOT_GRANTED;
+pub use windows_sys::Win32::Foundation::STATUS_MEDIA_WRITE_PROTECTED;
+pub use windows_sys::Win32::Foundation::STATUS_NAME_TOO_LONG;
+pub use windows_sys::Win32::Foundation::STATUS_NOT_A_DIRECTORY;
+pub use windows_sys::Win32::Foundation::STATUS_NOT_IMPLEMENTED;
+pub use windows_sys::Win32::Foundation::STATUS_NOT_SAME_DEVICE;
+pub use windows_sys::Win32::Foundation::STATUS_NOT_SUPPORTED;
+pub use windows_sys::Win32::Foundation::STATUS_NO_MEMORY;
+pub use windows_sys::Win32::Foundation::STATUS_NO_MORE_FILES;
+pub use windows_sys::Win32::Foundation::STATUS_NO_SUCH_FILE;
+pub use windows_sys::Win32::Foundation::STATUS_OBJECT_NAME_COLLISION;
+pub use windows_sys::Win32::Foundation::STATUS_OBJECT_NAME_INVALID;
+pub use windows_sys::Win32::Foundation::STATUS_OBJECT_NAME_NOT_FOUND;
+pub use windows_sys::Win32::Foundation::STATUS_OBJECT_PATH_INVALID;
+pub use windows_sys::Win32::Foundation::STATUS_OBJECT_PATH_NOT_FOUND;
+pub use windows_sys::Win32::Foundation::STATUS_RANGE_NOT_LOCKED;
+pub use windows_sys::Win32::Foundation::STATUS_SHARING_VIOLATION;
+pub use windows_sys::Win32::Foundation::STATUS_SUCCESS;
+pub use windows_sys::Win32::Foundation::STATUS_TOO_MANY_LINKS;
+pub use windows_sys::Win32::Foundation::STATUS_UNSUCCESSFUL;
+pub use windows_sys::Win32::Foundation::TRUE;
+pub use windows_sys::Win32::Foundation::UNICODE_STRING;
+pub use windows_sys::Win32::Foundation::WAIT_OBJECT_0;
+pub use windows_sys::Win32::Foundation::WAIT_TIMEOUT;
+pub use windows_sys::Win32::Security::GetFileSecurityW;
+pub use windows_sys::Win32::Security::SetFileSecurityW;
+pub use windows_sys::Win32::Security::ATTRIBUTE_SECURITY_INFORMATION;
+pub use windows_sys::Win32::Security::BACKUP_SECURITY_INFORMATION;
+pub use windows_sys::Win32::Security::DACL_SECURITY_INFORMATION;
+pub use windows_sys::Win32::Security::GROUP_SECURITY_INFORMATION;
+pub use windows_sys::Win32::Security::LABEL_SECURITY_INFORMATION;
+pub use windows_sys::Win32::Security::OBJECT_SECURITY_INFORMATION;
+pub use windows_sys::Win32::Security::OWNER_SECURITY_INFORMATION;
+pub use windows_sys::Win32::Security::PROTECTED_DACL_SECURITY_INFORMATION;
+pub use windows_sys::Win32::Security::PROTECTED_SACL_SECURITY_INFORMATION;
+pub use windows_sys::Win32::Security::SACL_SECURITY_INFORMATION;
+pub use windows_sys::Win32::Security::SCOPE_SECURITY_INFORMATION;
+pub use windows_sys::Win32::Security::SECURITY_DESCRIPTOR;
+pub use windows_sys::Win32::Security::UNPROTECTED_DACL_SECURITY_INFORMATION;
+pub use windows_sys::Win32::Security::UNPROTECTED_SACL_SECURITY_INFORMATION;
+pub use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+pub use windows_sys::Win32::Storage::FileSystem::BY_HANDLE_FILE_INFORMATION;
+pub use windows_sys::Win32::Storage::FileSystem::DELETE;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ACCESS_FLAGS;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ADD_FILE;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ADD_SUBDIRECTORY;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ALL_ACCESS;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_APPEND_DATA;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_ARCHIVE;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_COMPRESSED;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_DEVICE;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_DIRECTORY;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_ENCRYPTED;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_HIDDEN;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_INTEGRITY_STREAM;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_NORMAL;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_NOT_CONTENT_INDEXED;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_NO_SCRUB_DATA;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_OFFLINE;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_PINNED;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_READONLY;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_RECALL_ON_OPEN;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_REPARSE_POINT;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_SPARSE_FILE;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_SYSTEM;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_TEMPORARY;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_UNPINNED;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_VIRTUAL;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_CREATE;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_CREATE_PIPE_INSTANCE;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_DELETE_CHILD;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_EXECUTE;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_FLAG_BACKUP_SEMANTICS;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_LIST_DIRECTORY;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_OPEN;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_OPEN_IF;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_OVERWRITE;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_OVERWRITE_IF;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_READ_ATTRIBUTES;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_READ_DATA;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_READ_EA;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_SHARE_DELETE;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_SHARE_MODE;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_SHARE_READ;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_SHARE_WRITE;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_SUPERSEDE;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_TRAVERSE;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_WRITE_ATTRIBUTES;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_WRITE_DATA;
+pub use windows_sys::Win32::Storage::FileSystem::FILE_WRITE_EA;
+pub use windows_sys::Win32::Storage::FileSystem::NT_CREATE_FILE_DISPOSITION;
+pub use windows_sys::Win32::Storage::FileSystem::READ_CONTROL;
+pub use windows_sys::Win32::Storage::FileSystem::SPECIFIC_RIGHTS_ALL;
+pub use windows_sys::Win32::Storage::FileSystem::STANDARD_RIGHTS_ALL;
+pub use windows_sys::Win32::Storage::FileSystem::STANDARD_RIGHTS_EXECUTE;
+pub use windows_sys::Win32::Storage::FileSystem::STANDARD_RIGHTS_READ;
+pub use windows_sys::Win32::Storage::FileSystem::STANDARD_RIGHTS_REQUIRED;
+pub use windows_sys::Win32::Storage::FileSystem::STANDARD_RIGHTS_WRITE;
+pub use windows_sys::Win32::Storage::FileSystem::SYNCHRONIZE;
+pub use windows_sys::Win32::Storage::FileSystem::WIN32_FIND_DATAW;
+pub use windows_sys::Win32::Storage::FileSystem::WIN32_FIND_STREAM_DATA;
+pub use windows_sys::Win32::Storage::FileSystem::WRITE_DAC;
+pub use windows_sys::Win32::Storage::FileSystem::WRITE_OWNER;
+pub use windows_sys::Win32::System::SystemServices::ACCESS_SYSTEM_SECURITY;
+pub use windows_sys::Win32::System::SystemServices::FILE_CASE_PRESERVED_NAMES;
+pub use windows_sys::Win32::System::SystemServices::FILE_CASE_SENSITIVE_SEARCH;
+pub use windows_sys::Win32::System::SystemServices::FILE_DAX_VOLUME;
+pub use windows_sys::Win32::System::SystemServices::FILE_FILE_COMPRESSION;
+pub use windows_sys::Win32::System::SystemServices::FILE_NAMED_STREAMS;
+pub use windows_sys::Win32::System::SystemServices::FILE_PERSISTENT_ACLS;
+pub use windows_sys::Win32::System::SystemServices::FILE_READ_ONLY_VOLUME;
+pub use windows_sys::Win32::System::SystemServices::FILE_RETURNS_CLEANUP_RESULT_INFO;
+pub use windows_sys::Win32::System::SystemServices::FILE_SEQUENTIAL_WRITE_ONCE;
+pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_BLOCK_REFCOUNTING;
+pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_ENCRYPTION;
+pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_EXTENDED_ATTRIBUTES;
+pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_GHOSTING;
+pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_HARD_LINKS;
+pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_INTEGRITY_STREAMS;
+pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_OBJECT_IDS;
+pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_OPEN_BY_FILE_ID;
+pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_POSIX_UNLINK_RENAME;
+pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_REMOTE_STORAGE;
+pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_REPARSE_POINTS;
+pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_SPARSE_FILES;
+pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_SPARSE_VDL;
+pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_TRANSACTIONS;
+pub use windows_sys::Win32::System::SystemServices::FILE_SUPPORTS_USN_JOURNAL;
+pub use windows_sys::Win32::System::SystemServices::FILE_UNICODE_ON_DISK;
+pub use windows_sys::Win32::System::SystemServices::FILE_VOLUME_IS_COMPRESSED;
+pub use windows_sys::Win32::System::SystemServices::FILE_VOLUME_QUOTAS;
+pub use windows_sys::Win32::System::SystemServices::GENERIC_ALL;
+pub use windows_sys::Win32::System::SystemServices::GENERIC_EXECUTE;
+pub use windows_sys::Win32::System::SystemServices::GENERIC_READ;
+pub use windows_sys::Win32::System::SystemServices::GENERIC_WRITE;
+pub use windows_sys::Win32::System::SystemServices::MAXIMUM_ALLOWED;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_COMPLETE_IF_OPLOCKED;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_CREATE_TREE_CONNECTION;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_DELETE_ON_CLOSE;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_DIRECTORY_FILE;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_NON_DIRECTORY_FILE;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_NO_COMPRESSION;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_NO_EA_KNOWLEDGE;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_NO_INTERMEDIATE_BUFFERING;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_OPEN_BY_FILE_ID;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_OPEN_FOR_BACKUP_INTENT;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_OPEN_FOR_FREE_SPACE_QUERY;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_OPEN_NO_RECALL;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_OPEN_REMOTE_INSTANCE;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_OPEN_REPARSE_POINT;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_OPEN_REQUIRING_OPLOCK;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_RANDOM_ACCESS;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_RESERVE_OPFILTER;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_SEQUENTIAL_ONLY;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_SYNCHRONOUS_IO_ALERT;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_SYNCHRONOUS_IO_NONALERT;
+pub use windows_sys::Win32::System::WindowsProgramming::FILE_WRITE_THROUGH;
+pub use windows_sys::Win32::System::WindowsProgramming::INFINITE;
@@ -3,7 +3,7 @@ use crate::sys;
 bitflags::bitflags! {
     /// A value that defines standard, specific, and generic rights.
     #[repr(transparent)]
-    #[derive(Debug)]
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
     pub struct AccessMask: sys::FILE_ACCESS_FLAGS {
         /// For a directory, the right to create a file in the directory.
         const FILE_ADD_FILE = sys::FILE_ADD_FILE;
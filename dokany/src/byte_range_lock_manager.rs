@@ -0,0 +1,218 @@
+use crate::NtStatus;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+
+/// The kind of a byte range lock.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum LockKind {
+    /// Other handles may read and lock the range as shared, but no handle may write to it.
+    Shared,
+    /// Other handles may not read, write or lock the range.
+    Exclusive,
+}
+
+/// A lock held on a range of a file.
+#[derive(Debug, Copy, Clone)]
+struct ByteRangeLock {
+    owner: u64,
+    offset: u64,
+    length: u64,
+    kind: LockKind,
+}
+
+impl ByteRangeLock {
+    /// Returns true if this lock overlaps a range.
+    ///
+    /// Empty ranges never overlap anything.
+    fn overlaps(&self, offset: u64, length: u64) -> bool {
+        let end = u128::from(self.offset) + u128::from(self.length);
+        let other_end = u128::from(offset) + u128::from(length);
+        self.length != 0
+            && length != 0
+            && u128::from(self.offset) < other_end
+            && u128::from(offset) < end
+    }
+}
+
+/// Tracks the byte range locks of a single file, for filesystems that use user-mode locking.
+///
+/// Locks are held by owners, which identify the open handles of the file.
+/// The rules follow `LockFileEx`:
+/// * An exclusive lock cannot overlap any other lock.
+/// * A shared lock cannot overlap an exclusive lock of another owner.
+/// * Other owners cannot read from or write to an exclusive lock.
+/// * No owner can write to a shared lock.
+#[derive(Debug, Default)]
+pub struct ByteRangeLockManager {
+    locks: Mutex<Vec<ByteRangeLock>>,
+}
+
+impl ByteRangeLockManager {
+    /// Make a manager without any locks.
+    pub fn new() -> Self {
+        Self {
+            locks: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn locks(&self) -> MutexGuard<'_, Vec<ByteRangeLock>> {
+        self.locks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Lock a range for an owner.
+    ///
+    /// # Errors
+    /// Returns [`NtStatus::LOCK_NOT_GRANTED`] if the range conflicts with an existing lock.
+    pub fn lock(
+        &self,
+        owner: u64,
+        offset: u64,
+        length: u64,
+        kind: LockKind,
+    ) -> Result<(), NtStatus> {
+        let mut locks = self.locks();
+        let is_conflict = locks.iter().any(|lock| {
+            lock.overlaps(offset, length)
+                && (kind == LockKind::Exclusive
+                    || (lock.kind == LockKind::Exclusive && lock.owner != owner))
+        });
+        if is_conflict {
+            return Err(NtStatus::LOCK_NOT_GRANTED);
+        }
+
+        locks.push(ByteRangeLock {
+            owner,
+            offset,
+            length,
+            kind,
+        });
+        Ok(())
+    }
+
+    /// Unlock a range previously locked by an owner.
+    ///
+    /// The range must match the locked range exactly.
+    ///
+    /// # Errors
+    /// Returns [`NtStatus::RANGE_NOT_LOCKED`] if the owner has no lock on the range.
+    pub fn unlock(&self, owner: u64, offset: u64, length: u64) -> Result<(), NtStatus> {
+        let mut locks = self.locks();
+        let index = locks
+            .iter()
+            .position(|lock| lock.owner == owner && lock.offset == offset && lock.length == length)
+            .ok_or(NtStatus::RANGE_NOT_LOCKED)?;
+
+        locks.remove(index);
+        Ok(())
+    }
+
+    /// Release every lock of an owner.
+    ///
+    /// This should be called when the handle is cleaned up.
+    pub fn unlock_all(&self, owner: u64) {
+        self.locks().retain(|lock| lock.owner != owner);
+    }
+
+    /// Check that an owner may read a range.
+    ///
+    /// # Errors
+    /// Returns [`NtStatus::FILE_LOCK_CONFLICT`] if another owner holds an exclusive lock on the range.
+    pub fn check_read(&self, owner: u64, offset: u64, length: u64) -> Result<(), NtStatus> {
+        let is_conflict = self.locks().iter().any(|lock| {
+            lock.overlaps(offset, length) && lock.kind == LockKind::Exclusive && lock.owner != owner
+        });
+        if is_conflict {
+            return Err(NtStatus::FILE_LOCK_CONFLICT);
+        }
+        Ok(())
+    }
+
+    /// Check that an owner may write to a range.
+    ///
+    /// # Errors
+    /// Returns [`NtStatus::FILE_LOCK_CONFLICT`] if the range is locked as shared,
+    /// or another owner holds an exclusive lock on it.
+    pub fn check_write(&self, owner: u64, offset: u64, length: u64) -> Result<(), NtStatus> {
+        let is_conflict = self.locks().iter().any(|lock| {
+            lock.overlaps(offset, length) && (lock.kind == LockKind::Shared || lock.owner != owner)
+        });
+        if is_conflict {
+            return Err(NtStatus::FILE_LOCK_CONFLICT);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn exclusive() {
+        let manager = ByteRangeLockManager::new();
+
+        manager.lock(1, 10, 10, LockKind::Exclusive).unwrap();
+        assert_eq!(
+            manager.lock(2, 15, 10, LockKind::Shared),
+            Err(NtStatus::LOCK_NOT_GRANTED)
+        );
+        assert_eq!(
+            manager.lock(1, 19, 1, LockKind::Exclusive),
+            Err(NtStatus::LOCK_NOT_GRANTED)
+        );
+        manager.lock(2, 20, 10, LockKind::Exclusive).unwrap();
+        // Empty ranges never conflict.
+        manager.lock(2, 15, 0, LockKind::Exclusive).unwrap();
+
+        manager.check_read(1, 0, 15).unwrap();
+        manager.check_write(1, 10, 10).unwrap();
+        assert_eq!(
+            manager.check_read(2, 0, 15),
+            Err(NtStatus::FILE_LOCK_CONFLICT)
+        );
+        assert_eq!(
+            manager.check_write(1, 19, 2),
+            Err(NtStatus::FILE_LOCK_CONFLICT)
+        );
+    }
+
+    #[test]
+    fn shared() {
+        let manager = ByteRangeLockManager::new();
+
+        manager.lock(1, 0, 100, LockKind::Shared).unwrap();
+        manager.lock(2, 50, 100, LockKind::Shared).unwrap();
+        assert_eq!(
+            manager.lock(2, 0, 1, LockKind::Exclusive),
+            Err(NtStatus::LOCK_NOT_GRANTED)
+        );
+
+        manager.check_read(2, 0, 100).unwrap();
+        assert_eq!(
+            manager.check_write(1, 0, 1),
+            Err(NtStatus::FILE_LOCK_CONFLICT)
+        );
+        manager.check_write(1, 150, 1).unwrap();
+    }
+
+    #[test]
+    fn unlock() {
+        let manager = ByteRangeLockManager::new();
+
+        manager.lock(1, 0, 10, LockKind::Exclusive).unwrap();
+        manager
+            .lock(1, u64::MAX - 1, 10, LockKind::Exclusive)
+            .unwrap();
+        assert_eq!(manager.unlock(1, 0, 5), Err(NtStatus::RANGE_NOT_LOCKED));
+        assert_eq!(manager.unlock(2, 0, 10), Err(NtStatus::RANGE_NOT_LOCKED));
+        manager.unlock(1, 0, 10).unwrap();
+        manager.lock(2, 0, 10, LockKind::Exclusive).unwrap();
+
+        manager.unlock_all(2);
+        manager.lock(3, 0, 10, LockKind::Exclusive).unwrap();
+        assert_eq!(
+            manager.lock(3, u64::MAX, 1, LockKind::Shared),
+            Err(NtStatus::LOCK_NOT_GRANTED)
+        );
+    }
+}
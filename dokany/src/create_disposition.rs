@@ -0,0 +1,84 @@
+use crate::sys;
+
+/// The action to take when a file does or does not exist.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CreateDisposition {
+    /// If the file already exists, replace it with the given file.
+    ///
+    /// If it does not, create the given file.
+    Supersede,
+    /// If the file already exists, open it instead of creating a new file.
+    ///
+    /// If it does not, fail the request and do not create a new file.
+    Open,
+    /// If the file already exists, fail the request and do not create or open the given file.
+    ///
+    /// If it does not, create the given file.
+    Create,
+    /// If the file already exists, open it.
+    ///
+    /// If it does not, create the given file.
+    OpenIf,
+    /// If the file already exists, open it and overwrite it.
+    ///
+    /// If it does not, fail the request.
+    Overwrite,
+    /// If the file already exists, open it and overwrite it.
+    ///
+    /// If it does not, create the given file.
+    OverwriteIf,
+}
+
+impl CreateDisposition {
+    /// Returns true if this disposition may create a new file.
+    pub fn may_create(self) -> bool {
+        matches!(
+            self,
+            Self::Supersede | Self::Create | Self::OpenIf | Self::OverwriteIf
+        )
+    }
+
+    /// Returns true if this disposition may open an existing file.
+    pub fn may_open(self) -> bool {
+        !matches!(self, Self::Create)
+    }
+
+    /// Returns true if this disposition may either open an existing file or create a new one.
+    pub fn may_open_or_create(self) -> bool {
+        self.may_open() && self.may_create()
+    }
+
+    /// Returns true if this disposition truncates or replaces an existing file.
+    pub fn truncates(self) -> bool {
+        matches!(self, Self::Supersede | Self::Overwrite | Self::OverwriteIf)
+    }
+}
+
+impl TryFrom<sys::NT_CREATE_FILE_DISPOSITION> for CreateDisposition {
+    type Error = sys::NT_CREATE_FILE_DISPOSITION;
+
+    fn try_from(value: sys::NT_CREATE_FILE_DISPOSITION) -> Result<Self, Self::Error> {
+        match value {
+            sys::FILE_SUPERSEDE => Ok(Self::Supersede),
+            sys::FILE_OPEN => Ok(Self::Open),
+            sys::FILE_CREATE => Ok(Self::Create),
+            sys::FILE_OPEN_IF => Ok(Self::OpenIf),
+            sys::FILE_OVERWRITE => Ok(Self::Overwrite),
+            sys::FILE_OVERWRITE_IF => Ok(Self::OverwriteIf),
+            value => Err(value),
+        }
+    }
+}
+
+impl From<CreateDisposition> for sys::NT_CREATE_FILE_DISPOSITION {
+    fn from(value: CreateDisposition) -> Self {
+        match value {
+            CreateDisposition::Supersede => sys::FILE_SUPERSEDE,
+            CreateDisposition::Open => sys::FILE_OPEN,
+            CreateDisposition::Create => sys::FILE_CREATE,
+            CreateDisposition::OpenIf => sys::FILE_OPEN_IF,
+            CreateDisposition::Overwrite => sys::FILE_OVERWRITE,
+            CreateDisposition::OverwriteIf => sys::FILE_OVERWRITE_IF,
+        }
+    }
+}
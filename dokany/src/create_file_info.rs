@@ -0,0 +1,147 @@
+use crate::security::SecurityDescriptor;
+use crate::sys;
+use crate::AccessMask;
+use crate::CreateDisposition;
+use crate::CreateOptions;
+use crate::FileAttributes;
+use crate::ShareAccess;
+
+/// The parameters of a request to create or open a file.
+#[derive(Debug, Copy, Clone)]
+pub struct CreateFileInfo<'a> {
+    pub(crate) security_context: Option<SecurityContext<'a>>,
+    pub(crate) desired_access: AccessMask,
+    pub(crate) file_attributes: FileAttributes,
+    pub(crate) share_access: ShareAccess,
+    pub(crate) create_disposition: CreateDisposition,
+    pub(crate) create_options: CreateOptions,
+}
+
+impl<'a> CreateFileInfo<'a> {
+    /// Get the security context of the request, if present.
+    pub fn security_context(&self) -> Option<SecurityContext<'a>> {
+        self.security_context
+    }
+
+    /// Get the requested access rights.
+    pub fn desired_access(&self) -> AccessMask {
+        self.desired_access
+    }
+
+    /// Get the attributes to apply to a newly created file.
+    pub fn file_attributes(&self) -> FileAttributes {
+        self.file_attributes
+    }
+
+    /// Get the type of access other handles may have to the file.
+    pub fn share_access(&self) -> ShareAccess {
+        self.share_access
+    }
+
+    /// Get the action to take if the file does or does not exist.
+    pub fn create_disposition(&self) -> CreateDisposition {
+        self.create_disposition
+    }
+
+    /// Get the options to apply when creating or opening the file.
+    pub fn create_options(&self) -> CreateOptions {
+        self.create_options
+    }
+}
+
+/// The security context of a request to create or open a file.
+#[derive(Copy, Clone)]
+pub struct SecurityContext<'a> {
+    security_context: &'a sys::DOKAN_IO_SECURITY_CONTEXT,
+}
+
+impl<'a> SecurityContext<'a> {
+    /// Wrap a raw security context.
+    ///
+    /// # Safety
+    /// The security descriptor pointer of the context must be null or point to a valid self-relative security descriptor.
+    pub(crate) unsafe fn new(security_context: &'a sys::DOKAN_IO_SECURITY_CONTEXT) -> Self {
+        Self { security_context }
+    }
+
+    /// Get the requested access rights.
+    pub fn desired_access(&self) -> AccessMask {
+        AccessMask::from_bits_retain(self.security_context.DesiredAccess)
+    }
+
+    /// Get the originally requested access rights.
+    pub fn original_desired_access(&self) -> AccessMask {
+        AccessMask::from_bits_retain(self.security_context.AccessState.OriginalDesiredAccess)
+    }
+
+    /// Get the access rights that have not been granted yet.
+    pub fn remaining_desired_access(&self) -> AccessMask {
+        AccessMask::from_bits_retain(self.security_context.AccessState.RemainingDesiredAccess)
+    }
+
+    /// Get the access rights that have already been granted.
+    pub fn previously_granted_access(&self) -> AccessMask {
+        AccessMask::from_bits_retain(self.security_context.AccessState.PreviouslyGrantedAccess)
+    }
+
+    /// Get the self-relative security descriptor to assign to a newly created file, if present.
+    pub fn security_descriptor(&self) -> Option<&'a [u8]> {
+        let ptr = self.security_context.AccessState.SecurityDescriptor;
+        if ptr.is_null() {
+            return None;
+        }
+
+        // The header holds the offsets of the parts, and the part headers hold their sizes.
+        // Each read stops at the end of the parts located by the one before it,
+        // so only the header is read before any offset is known.
+        let ptr = ptr.cast::<u8>();
+        let header_len = SecurityDescriptor::self_relative_len(&[]).ok()?;
+        let header = unsafe { std::slice::from_raw_parts(ptr, header_len) };
+        let parts_len = SecurityDescriptor::self_relative_len(header).ok()?;
+        let parts = unsafe { std::slice::from_raw_parts(ptr, parts_len) };
+        let len = SecurityDescriptor::self_relative_len(parts).ok()?;
+        Some(unsafe { std::slice::from_raw_parts(ptr, len) })
+    }
+
+    /// Get the raw security context.
+    pub fn as_raw(&self) -> &'a sys::DOKAN_IO_SECURITY_CONTEXT {
+        self.security_context
+    }
+}
+
+impl std::fmt::Debug for SecurityContext<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecurityContext")
+            .field("desired_access", &self.desired_access())
+            .field("original_desired_access", &self.original_desired_access())
+            .field("remaining_desired_access", &self.remaining_desired_access())
+            .field(
+                "previously_granted_access",
+                &self.previously_granted_access(),
+            )
+            .field("security_descriptor", &self.security_descriptor())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn security_descriptor() {
+        let descriptor: SecurityDescriptor = "O:BAG:SYD:(A;;FA;;;WD)".parse().unwrap();
+        let mut bytes = descriptor.to_bytes();
+        let len = bytes.len();
+        // Bytes after the descriptor are not part of it.
+        bytes.extend_from_slice(&[0xFF; 8]);
+
+        let mut raw: sys::DOKAN_IO_SECURITY_CONTEXT = unsafe { std::mem::zeroed() };
+        let security_context = unsafe { SecurityContext::new(&raw) };
+        assert_eq!(security_context.security_descriptor(), None);
+
+        raw.AccessState.SecurityDescriptor = bytes.as_mut_ptr().cast();
+        let security_context = unsafe { SecurityContext::new(&raw) };
+        assert_eq!(security_context.security_descriptor(), Some(&bytes[..len]));
+    }
+}
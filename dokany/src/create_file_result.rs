@@ -0,0 +1,30 @@
+/// A handle opened by [`FileSystem::create_file`](crate::FileSystem::create_file).
+#[derive(Debug)]
+pub struct CreateFileResult<C> {
+    /// The per-handle context.
+    pub context: C,
+    /// Whether an existing file was opened, rather than a new one created.
+    ///
+    /// For the dispositions that may either open or create a file,
+    /// Dokan expects an opened existing file to be reported as `OBJECT_NAME_COLLISION`.
+    /// That status is returned for the filesystem if this is set.
+    pub opened_existing: bool,
+}
+
+impl<C> CreateFileResult<C> {
+    /// Make a result for a handle to a newly created file.
+    pub fn created(context: C) -> Self {
+        Self {
+            context,
+            opened_existing: false,
+        }
+    }
+
+    /// Make a result for a handle to an existing file.
+    pub fn opened(context: C) -> Self {
+        Self {
+            context,
+            opened_existing: true,
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use crate::sys;
+
+bitflags::bitflags! {
+    /// Options to apply when creating or opening a file.
+    #[repr(transparent)]
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+    pub struct CreateOptions: sys::ULONG {
+        /// The file being created or opened is a directory file.
+        const DIRECTORY_FILE = sys::FILE_DIRECTORY_FILE;
+        /// System services, file-system drivers, and drivers that write data to the file must actually transfer the data to the file before any requested write operation is considered complete.
+        const WRITE_THROUGH = sys::FILE_WRITE_THROUGH;
+        /// All access to the file will be sequential.
+        const SEQUENTIAL_ONLY = sys::FILE_SEQUENTIAL_ONLY;
+        /// The file cannot be cached or buffered in a driver's internal buffers.
+        const NO_INTERMEDIATE_BUFFERING = sys::FILE_NO_INTERMEDIATE_BUFFERING;
+        /// All operations on the file are performed synchronously.
+        ///
+        /// Any wait on behalf of the caller is subject to premature termination from alerts.
+        const SYNCHRONOUS_IO_ALERT = sys::FILE_SYNCHRONOUS_IO_ALERT;
+        /// All operations on the file are performed synchronously.
+        ///
+        /// Waits in the system to synchronize I/O queuing and completion are not subject to alerts.
+        const SYNCHRONOUS_IO_NONALERT = sys::FILE_SYNCHRONOUS_IO_NONALERT;
+        /// The file being opened must not be a directory file.
+        const NON_DIRECTORY_FILE = sys::FILE_NON_DIRECTORY_FILE;
+        /// Create a tree connection for this file in order to open it over the network.
+        const CREATE_TREE_CONNECTION = sys::FILE_CREATE_TREE_CONNECTION;
+        /// Complete this operation immediately with an alternate success code if the target file is oplocked.
+        const COMPLETE_IF_OPLOCKED = sys::FILE_COMPLETE_IF_OPLOCKED;
+        /// If the extended attributes on an existing file being opened indicate that the caller must understand extended attributes to properly interpret the file, fail this request.
+        const NO_EA_KNOWLEDGE = sys::FILE_NO_EA_KNOWLEDGE;
+        /// The remote instance of the file should be opened.
+        const OPEN_REMOTE_INSTANCE = sys::FILE_OPEN_REMOTE_INSTANCE;
+        /// Accesses to the file can be random, so no sequential read-ahead operations should be performed on the file.
+        const RANDOM_ACCESS = sys::FILE_RANDOM_ACCESS;
+        /// Delete the file when the last handle to it is closed.
+        const DELETE_ON_CLOSE = sys::FILE_DELETE_ON_CLOSE;
+        /// The file name contains the file reference number instead of a name.
+        const OPEN_BY_FILE_ID = sys::FILE_OPEN_BY_FILE_ID;
+        /// The file is being opened for backup intent.
+        const OPEN_FOR_BACKUP_INTENT = sys::FILE_OPEN_FOR_BACKUP_INTENT;
+        /// Suppress compression of the file.
+        const NO_COMPRESSION = sys::FILE_NO_COMPRESSION;
+        /// The file is being opened and an opportunistic lock on the file is being requested as a single atomic operation.
+        const OPEN_REQUIRING_OPLOCK = sys::FILE_OPEN_REQUIRING_OPLOCK;
+        /// Reserve an opportunistic lock filter.
+        const RESERVE_OPFILTER = sys::FILE_RESERVE_OPFILTER;
+        /// Open a file with a reparse point and bypass normal reparse point processing for the file.
+        const OPEN_REPARSE_POINT = sys::FILE_OPEN_REPARSE_POINT;
+        /// Instructs any filters that perform offline storage or virtualization to not recall the contents of the file as a result of this open.
+        const OPEN_NO_RECALL = sys::FILE_OPEN_NO_RECALL;
+        /// The file is being opened to query free space.
+        const OPEN_FOR_FREE_SPACE_QUERY = sys::FILE_OPEN_FOR_FREE_SPACE_QUERY;
+    }
+}
@@ -0,0 +1,62 @@
+/// The capacity of a volume, in bytes.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct DiskSpace {
+    /// The free space available to the user that made the request, which may be limited by quotas.
+    pub free_bytes_available: u64,
+    /// The total size of the volume.
+    pub total_bytes: u64,
+    /// The total free space on the volume.
+    pub total_free_bytes: u64,
+}
+
+impl DiskSpace {
+    /// Make a capacity where all of the free space is available to every user.
+    pub fn new(total_bytes: u64, total_free_bytes: u64) -> Self {
+        Self {
+            free_bytes_available: total_free_bytes,
+            total_bytes,
+            total_free_bytes,
+        }
+    }
+
+    /// Limit the free space to the total size, and the available space to the free space.
+    ///
+    /// Windows does not expect a volume with more free space than its size.
+    pub fn clamped(self) -> Self {
+        let total_free_bytes = self.total_free_bytes.min(self.total_bytes);
+        Self {
+            free_bytes_available: self.free_bytes_available.min(total_free_bytes),
+            total_bytes: self.total_bytes,
+            total_free_bytes,
+        }
+    }
+}
+
+impl Default for DiskSpace {
+    /// The capacity Dokan reports when a filesystem does not provide one: 512 MiB free of 1 GiB.
+    fn default() -> Self {
+        Self::new(1024 * 1024 * 1024, 512 * 1024 * 1024)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn clamped() {
+        let disk_space = DiskSpace {
+            free_bytes_available: 300,
+            total_bytes: 100,
+            total_free_bytes: 200,
+        };
+        assert_eq!(disk_space.clamped(), DiskSpace::new(100, 100));
+
+        let disk_space = DiskSpace {
+            free_bytes_available: 10,
+            total_bytes: 100,
+            total_free_bytes: 50,
+        };
+        assert_eq!(disk_space.clamped(), disk_space);
+    }
+}
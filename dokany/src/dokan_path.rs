@@ -0,0 +1,230 @@
+use crate::wide::os_string_from_wide;
+use std::path::PathBuf;
+use std::string::FromUtf16Error;
+
+/// The separator between path components.
+const SEPARATOR: u16 = b'\\' as u16;
+
+/// The separator between a file name, stream name and stream type.
+const STREAM_SEPARATOR: u16 = b':' as u16;
+
+/// A borrowed file name as passed by Dokan, like `\dir\file.txt:stream:$DATA`.
+///
+/// This does not validate the path. Empty components, like those from repeated separators, are skipped.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct DokanPath<'a> {
+    path: &'a [u16],
+}
+
+impl<'a> DokanPath<'a> {
+    /// Wrap a file name, without the NUL terminator.
+    pub fn new(path: &'a [u16]) -> Self {
+        Self { path }
+    }
+
+    /// Get the wide chars of the whole path.
+    pub fn as_wide(&self) -> &'a [u16] {
+        self.path
+    }
+
+    /// Get the path without its stream name and type.
+    pub fn file_path(&self) -> DokanPath<'a> {
+        Self::new(self.split_stream().0)
+    }
+
+    /// Iterate over the components of the path, without the stream name and type.
+    pub fn components(&self) -> impl DoubleEndedIterator<Item = &'a [u16]> + 'a {
+        self.file_path()
+            .path
+            .split(|&c| c == SEPARATOR)
+            .filter(|component| !component.is_empty())
+    }
+
+    /// Check whether this is the root directory, or a stream of it.
+    pub fn is_root(&self) -> bool {
+        self.components().next().is_none()
+    }
+
+    /// Get the path of the parent directory, or `None` for the root.
+    pub fn parent(&self) -> Option<DokanPath<'a>> {
+        let path = trim_end_separators(self.file_path().path);
+        if path.is_empty() {
+            return None;
+        }
+
+        let end = match path.iter().rposition(|&c| c == SEPARATOR) {
+            // Keep the leading separator of the root.
+            Some(index) => trim_end_separators(&path[..index]).len().max(1),
+            None => 0,
+        };
+        Some(Self::new(&path[..end]))
+    }
+
+    /// Get the last component, without the stream name and type, or `None` for the root.
+    pub fn file_name(&self) -> Option<&'a [u16]> {
+        self.components().next_back()
+    }
+
+    /// Get the stream name, or `None` for the unnamed data stream.
+    pub fn stream_name(&self) -> Option<&'a [u16]> {
+        let stream = self.split_stream().1?;
+        let name = match stream.iter().position(|&c| c == STREAM_SEPARATOR) {
+            Some(index) => &stream[..index],
+            None => stream,
+        };
+        if name.is_empty() {
+            return None;
+        }
+        Some(name)
+    }
+
+    /// Get the stream type, like `$DATA`, or `None` if it is not given.
+    pub fn stream_type(&self) -> Option<&'a [u16]> {
+        let stream = self.split_stream().1?;
+        let index = stream.iter().position(|&c| c == STREAM_SEPARATOR)?;
+        Some(&stream[index + 1..])
+    }
+
+    /// Convert the path to a string, replacing unpaired surrogates with U+FFFD.
+    ///
+    /// Use [`String::try_from`] to reject unpaired surrogates instead.
+    pub fn to_string_lossy(&self) -> String {
+        String::from_utf16_lossy(self.path)
+    }
+
+    /// Convert the path to a host path.
+    ///
+    /// Separators are replaced with the host separator, so `\dir\file.txt` becomes `/dir/file.txt` on Linux.
+    /// Unpaired surrogates are kept on Windows, and on Unix, where they are encoded as WTF-8.
+    pub fn to_path_buf(&self) -> PathBuf {
+        let separator = std::path::MAIN_SEPARATOR as u16;
+        let path: Vec<u16> = self
+            .path
+            .iter()
+            .map(|&c| if c == SEPARATOR { separator } else { c })
+            .collect();
+        os_string_from_wide(&path).into()
+    }
+
+    /// Split the path at the first stream separator.
+    fn split_stream(&self) -> (&'a [u16], Option<&'a [u16]>) {
+        match self.path.iter().position(|&c| c == STREAM_SEPARATOR) {
+            Some(index) => (&self.path[..index], Some(&self.path[index + 1..])),
+            None => (self.path, None),
+        }
+    }
+}
+
+impl<'a> From<&'a [u16]> for DokanPath<'a> {
+    fn from(path: &'a [u16]) -> Self {
+        Self::new(path)
+    }
+}
+
+/// Fails if the path has unpaired surrogates, which a string cannot hold.
+impl TryFrom<DokanPath<'_>> for String {
+    type Error = FromUtf16Error;
+
+    fn try_from(path: DokanPath<'_>) -> Result<Self, Self::Error> {
+        String::from_utf16(path.path)
+    }
+}
+
+impl From<DokanPath<'_>> for PathBuf {
+    fn from(path: DokanPath<'_>) -> Self {
+        path.to_path_buf()
+    }
+}
+
+/// Remove trailing separators from a path.
+fn trim_end_separators(path: &[u16]) -> &[u16] {
+    let len = path
+        .iter()
+        .rposition(|&c| c != SEPARATOR)
+        .map_or(0, |index| index + 1);
+    &path[..len]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn wide(value: &str) -> Vec<u16> {
+        value.encode_utf16().collect()
+    }
+
+    #[test]
+    fn components() {
+        let path = wide("\\dir\\\\file.txt:meta:$DATA");
+        let path = DokanPath::new(&path);
+
+        let components: Vec<String> = path.components().map(String::from_utf16_lossy).collect();
+        assert_eq!(components, ["dir", "file.txt"]);
+        assert_eq!(path.file_name(), Some(wide("file.txt").as_slice()));
+        assert_eq!(path.file_path().as_wide(), wide("\\dir\\\\file.txt"));
+        assert_eq!(path.parent().unwrap().as_wide(), wide("\\dir"));
+        assert_eq!(
+            path.parent().unwrap().parent().unwrap().as_wide(),
+            wide("\\")
+        );
+        assert!(!path.is_root());
+
+        let root = wide("\\");
+        let root = DokanPath::new(&root);
+        assert!(root.is_root());
+        assert_eq!(root.parent(), None);
+        assert_eq!(root.file_name(), None);
+    }
+
+    #[test]
+    fn streams() {
+        let path = wide("\\file.txt:meta:$DATA");
+        let path = DokanPath::new(&path);
+        assert_eq!(path.stream_name(), Some(wide("meta").as_slice()));
+        assert_eq!(path.stream_type(), Some(wide("$DATA").as_slice()));
+
+        let path = wide("\\file.txt:meta");
+        let path = DokanPath::new(&path);
+        assert_eq!(path.stream_name(), Some(wide("meta").as_slice()));
+        assert_eq!(path.stream_type(), None);
+
+        let path = wide("\\file.txt::$DATA");
+        let path = DokanPath::new(&path);
+        assert_eq!(path.stream_name(), None);
+        assert_eq!(path.stream_type(), Some(wide("$DATA").as_slice()));
+
+        // The root directory can have streams too.
+        let path = wide("\\:meta");
+        let path = DokanPath::new(&path);
+        assert!(path.is_root());
+        assert_eq!(path.stream_name(), Some(wide("meta").as_slice()));
+    }
+
+    #[test]
+    fn conversion() {
+        let path = wide("\\dir\\file.txt");
+        let path = DokanPath::new(&path);
+        assert_eq!(String::try_from(path).unwrap(), "\\dir\\file.txt");
+        #[cfg(unix)]
+        assert_eq!(path.to_path_buf(), PathBuf::from("/dir/file.txt"));
+        #[cfg(windows)]
+        assert_eq!(path.to_path_buf(), PathBuf::from("\\dir\\file.txt"));
+
+        // Unpaired surrogates are not replaced, except when asked to.
+        let path = [u16::from(b'\\'), 0xD800];
+        let path = DokanPath::new(&path);
+        assert!(String::try_from(path).is_err());
+        assert_eq!(path.to_string_lossy(), "\\\u{FFFD}");
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            assert_eq!(path.to_path_buf().as_os_str().as_bytes(), b"/\xED\xA0\x80");
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::ffi::OsStrExt;
+            let wide: Vec<u16> = path.to_path_buf().as_os_str().encode_wide().collect();
+            assert_eq!(wide, path.as_wide());
+        }
+    }
+}
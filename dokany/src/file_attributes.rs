@@ -0,0 +1,55 @@
+use crate::sys;
+
+bitflags::bitflags! {
+    /// File attributes.
+    #[repr(transparent)]
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+    pub struct FileAttributes: sys::FILE_FLAGS_AND_ATTRIBUTES {
+        /// A file that is read-only.
+        const READONLY = sys::FILE_ATTRIBUTE_READONLY;
+        /// The file or directory is hidden.
+        const HIDDEN = sys::FILE_ATTRIBUTE_HIDDEN;
+        /// A file or directory that the operating system uses a part of, or uses exclusively.
+        const SYSTEM = sys::FILE_ATTRIBUTE_SYSTEM;
+        /// The handle that identifies a directory.
+        const DIRECTORY = sys::FILE_ATTRIBUTE_DIRECTORY;
+        /// A file or directory that is an archive file or directory.
+        ///
+        /// Applications typically use this attribute to mark files for backup or removal.
+        const ARCHIVE = sys::FILE_ATTRIBUTE_ARCHIVE;
+        /// This value is reserved for system use.
+        const DEVICE = sys::FILE_ATTRIBUTE_DEVICE;
+        /// A file that does not have other attributes set.
+        ///
+        /// This attribute is valid only when used alone.
+        const NORMAL = sys::FILE_ATTRIBUTE_NORMAL;
+        /// A file that is being used for temporary storage.
+        const TEMPORARY = sys::FILE_ATTRIBUTE_TEMPORARY;
+        /// A file that is a sparse file.
+        const SPARSE_FILE = sys::FILE_ATTRIBUTE_SPARSE_FILE;
+        /// A file or directory that has an associated reparse point, or a file that is a symbolic link.
+        const REPARSE_POINT = sys::FILE_ATTRIBUTE_REPARSE_POINT;
+        /// A file or directory that is compressed.
+        const COMPRESSED = sys::FILE_ATTRIBUTE_COMPRESSED;
+        /// The data of a file is not available immediately.
+        const OFFLINE = sys::FILE_ATTRIBUTE_OFFLINE;
+        /// The file or directory is not to be indexed by the content indexing service.
+        const NOT_CONTENT_INDEXED = sys::FILE_ATTRIBUTE_NOT_CONTENT_INDEXED;
+        /// A file or directory that is encrypted.
+        const ENCRYPTED = sys::FILE_ATTRIBUTE_ENCRYPTED;
+        /// The directory or user data stream is configured with integrity.
+        const INTEGRITY_STREAM = sys::FILE_ATTRIBUTE_INTEGRITY_STREAM;
+        /// This value is reserved for system use.
+        const VIRTUAL = sys::FILE_ATTRIBUTE_VIRTUAL;
+        /// The user data stream not to be read by the background data integrity scanner.
+        const NO_SCRUB_DATA = sys::FILE_ATTRIBUTE_NO_SCRUB_DATA;
+        /// The file or directory has no physical representation on the local system; the item is virtual.
+        const RECALL_ON_OPEN = sys::FILE_ATTRIBUTE_RECALL_ON_OPEN;
+        /// The file or directory should be kept fully present locally even when not being actively accessed.
+        const PINNED = sys::FILE_ATTRIBUTE_PINNED;
+        /// The file or directory should not be kept fully present locally except when being actively accessed.
+        const UNPINNED = sys::FILE_ATTRIBUTE_UNPINNED;
+        /// The file or directory is not fully present locally.
+        const RECALL_ON_DATA_ACCESS = sys::FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS;
+    }
+}
@@ -0,0 +1,36 @@
+use crate::sys;
+
+/// Information about the open handle an operation is made on.
+#[derive(Debug, Copy, Clone)]
+pub struct FileInfo {
+    pub(crate) delete_on_close: bool,
+    pub(crate) is_directory: bool,
+    pub(crate) process_id: u32,
+}
+
+impl FileInfo {
+    /// Returns true if the file should be deleted when this handle is cleaned up.
+    pub fn delete_on_close(&self) -> bool {
+        self.delete_on_close
+    }
+
+    /// Returns true if the handle is for a directory.
+    pub fn is_directory(&self) -> bool {
+        self.is_directory
+    }
+
+    /// Get the id of the process that made the request.
+    pub fn process_id(&self) -> u32 {
+        self.process_id
+    }
+}
+
+impl From<&sys::DOKAN_FILE_INFO> for FileInfo {
+    fn from(file_info: &sys::DOKAN_FILE_INFO) -> Self {
+        Self {
+            delete_on_close: file_info.DeleteOnClose != 0,
+            is_directory: file_info.IsDirectory != 0,
+            process_id: file_info.ProcessId,
+        }
+    }
+}
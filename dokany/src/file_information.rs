@@ -0,0 +1,80 @@
+use crate::file_time::file_time_to_system_time;
+use crate::file_time::system_time_to_file_time;
+use crate::sys;
+use crate::FileAttributes;
+use std::time::SystemTime;
+
+/// Information about an open file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FileInformation {
+    /// The file attributes.
+    pub attributes: FileAttributes,
+    /// The time the file was created.
+    pub creation_time: SystemTime,
+    /// The time the file was last read from or written to.
+    pub last_access_time: SystemTime,
+    /// The time the file was last written to.
+    pub last_write_time: SystemTime,
+    /// The serial number of the volume that contains the file.
+    pub volume_serial_number: u32,
+    /// The file size, in bytes.
+    pub size: u64,
+    /// The number of links to the file.
+    pub number_of_links: u32,
+    /// A unique identifier of the file on its volume.
+    pub file_index: u64,
+}
+
+impl FileInformation {
+    /// Make information for a normal, empty file with a single link.
+    pub fn new() -> Self {
+        Self {
+            attributes: FileAttributes::NORMAL,
+            creation_time: SystemTime::UNIX_EPOCH,
+            last_access_time: SystemTime::UNIX_EPOCH,
+            last_write_time: SystemTime::UNIX_EPOCH,
+            volume_serial_number: 0,
+            size: 0,
+            number_of_links: 1,
+            file_index: 0,
+        }
+    }
+}
+
+impl Default for FileInformation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<FileInformation> for sys::BY_HANDLE_FILE_INFORMATION {
+    fn from(info: FileInformation) -> Self {
+        Self {
+            dwFileAttributes: info.attributes.bits(),
+            ftCreationTime: system_time_to_file_time(info.creation_time),
+            ftLastAccessTime: system_time_to_file_time(info.last_access_time),
+            ftLastWriteTime: system_time_to_file_time(info.last_write_time),
+            dwVolumeSerialNumber: info.volume_serial_number,
+            nFileSizeHigh: (info.size >> 32).try_into().unwrap(),
+            nFileSizeLow: (info.size & 0xFFFFFFFF).try_into().unwrap(),
+            nNumberOfLinks: info.number_of_links,
+            nFileIndexHigh: (info.file_index >> 32).try_into().unwrap(),
+            nFileIndexLow: (info.file_index & 0xFFFFFFFF).try_into().unwrap(),
+        }
+    }
+}
+
+impl From<sys::BY_HANDLE_FILE_INFORMATION> for FileInformation {
+    fn from(info: sys::BY_HANDLE_FILE_INFORMATION) -> Self {
+        Self {
+            attributes: FileAttributes::from_bits_retain(info.dwFileAttributes),
+            creation_time: file_time_to_system_time(info.ftCreationTime),
+            last_access_time: file_time_to_system_time(info.ftLastAccessTime),
+            last_write_time: file_time_to_system_time(info.ftLastWriteTime),
+            volume_serial_number: info.dwVolumeSerialNumber,
+            size: (u64::from(info.nFileSizeHigh) << 32) | u64::from(info.nFileSizeLow),
+            number_of_links: info.nNumberOfLinks,
+            file_index: (u64::from(info.nFileIndexHigh) << 32) | u64::from(info.nFileIndexLow),
+        }
+    }
+}
@@ -5,6 +5,7 @@ bitflags::bitflags! {
     ///
     /// FILE_FILE_COMPRESSION and FILE_VOL_IS_COMPRESSED are mutually exclusive
     #[repr(transparent)]
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
     pub struct FileSystemFlags: sys::DWORD {
         /// The specified volume supports case-sensitive file names.
         const CASE_SENSITIVE_SEARCH = sys::FILE_CASE_SENSITIVE_SEARCH;
@@ -0,0 +1,85 @@
+use crate::sys;
+use crate::FileSystem;
+use crate::GlobalContext;
+use crate::Options;
+use std::time::Duration;
+
+/// A handle to a mounted filesystem.
+///
+/// The filesystem is unmounted when this is dropped.
+pub struct FileSystemHandle<FS: FileSystem> {
+    instance: sys::DOKAN_HANDLE,
+
+    /// The options the instance was created with. Dokan keeps a pointer to these.
+    _options: Box<Options>,
+
+    /// The boxed global context. This is only freed after the instance is closed.
+    context: *mut GlobalContext<FS>,
+}
+
+impl<FS: FileSystem> FileSystemHandle<FS> {
+    /// Wrap a running instance.
+    ///
+    /// # Safety
+    /// * `instance` must be a valid instance, created from `options` and `context`.
+    /// * `context` must come from [`Box::into_raw`].
+    pub(crate) unsafe fn new(
+        instance: sys::DOKAN_HANDLE,
+        options: Box<Options>,
+        context: *mut GlobalContext<FS>,
+    ) -> Self {
+        Self {
+            instance,
+            _options: options,
+            context,
+        }
+    }
+
+    /// Get the mounted filesystem.
+    pub fn filesystem(&self) -> &FS {
+        unsafe { &(*self.context).filesystem }
+    }
+
+    /// Returns true if the filesystem is still running.
+    pub fn is_running(&self) -> bool {
+        unsafe { sys::DokanIsFileSystemRunning(self.instance) == sys::TRUE }
+    }
+
+    /// Wait until the filesystem is unmounted, or the timeout expires.
+    ///
+    /// If `timeout` is `None`, this waits forever.
+    ///
+    /// # Returns
+    /// Returns true if the filesystem was unmounted.
+    pub fn wait(&self, timeout: Option<Duration>) -> bool {
+        let timeout = match timeout {
+            Some(timeout) => u32::try_from(timeout.as_millis())
+                .unwrap_or(u32::MAX)
+                .min(sys::INFINITE - 1),
+            None => sys::INFINITE,
+        };
+
+        unsafe { sys::DokanWaitForFileSystemClosed(self.instance, timeout) == sys::WAIT_OBJECT_0 }
+    }
+
+    /// Unmount the filesystem and wait for it to close.
+    ///
+    /// This is the same as dropping the handle.
+    pub fn unmount(self) {
+        drop(self);
+    }
+}
+
+impl<FS: FileSystem> Drop for FileSystemHandle<FS> {
+    fn drop(&mut self) {
+        // This unmounts the filesystem and waits until no more callbacks can run.
+        unsafe { sys::DokanCloseHandle(self.instance) };
+
+        drop(unsafe { Box::from_raw(self.context) });
+    }
+}
+
+// Dokan instances may be used from any thread,
+// and the filesystem is already required to be `Send + Sync`.
+unsafe impl<FS: FileSystem> Send for FileSystemHandle<FS> {}
+unsafe impl<FS: FileSystem> Sync for FileSystemHandle<FS> {}
@@ -0,0 +1,106 @@
+use crate::sys;
+use crate::FileTimeChange;
+use crate::NtStatus;
+use std::time::Duration;
+use std::time::SystemTime;
+
+/// The number of 100 nanosecond intervals between 1601-01-01 and 1970-01-01.
+const UNIX_EPOCH_INTERVALS: u64 = 116_444_736_000_000_000;
+
+/// The number of 100 nanosecond intervals in a second.
+const INTERVALS_PER_SECOND: u64 = 10_000_000;
+
+/// The largest valid file time.
+///
+/// Larger values are negative when interpreted as a `LARGE_INTEGER`, which some apis treat specially.
+const MAX_INTERVALS: u64 = i64::MAX as u64;
+
+/// Convert a [`SystemTime`] into a `FILETIME`.
+///
+/// Times before 1601-01-01 are clamped to zero.
+pub(crate) fn system_time_to_file_time(time: SystemTime) -> sys::FILETIME {
+    let intervals = match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(duration) => UNIX_EPOCH_INTERVALS.saturating_add(duration_to_intervals(duration)),
+        Err(e) => UNIX_EPOCH_INTERVALS.saturating_sub(duration_to_intervals(e.duration())),
+    };
+    let intervals = intervals.min(MAX_INTERVALS);
+
+    sys::FILETIME {
+        dwLowDateTime: (intervals & 0xFFFFFFFF).try_into().unwrap(),
+        dwHighDateTime: (intervals >> 32).try_into().unwrap(),
+    }
+}
+
+/// Convert a `FILETIME` into a [`SystemTime`].
+///
+/// Every valid file time is representable on supported platforms,
+/// but the unix epoch is returned if one is not.
+pub(crate) fn file_time_to_system_time(file_time: sys::FILETIME) -> SystemTime {
+    let intervals = file_time_to_intervals(file_time);
+
+    let time = if intervals >= UNIX_EPOCH_INTERVALS {
+        SystemTime::UNIX_EPOCH.checked_add(intervals_to_duration(intervals - UNIX_EPOCH_INTERVALS))
+    } else {
+        SystemTime::UNIX_EPOCH.checked_sub(intervals_to_duration(UNIX_EPOCH_INTERVALS - intervals))
+    };
+
+    time.unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// The `LARGE_INTEGER` value -1, which asks to stop updating a time.
+const STOP_UPDATING: u64 = -1_i64 as u64;
+
+/// The `LARGE_INTEGER` value -2, which asks to resume updating a time.
+const RESUME_UPDATING: u64 = -2_i64 as u64;
+
+/// Decode an optional `FILETIME` that describes a change into a [`FileTimeChange`].
+///
+/// Null and zero times mean the time should not change, while -1 and -2 stop and resume updating it.
+/// Other times that are negative as a `LARGE_INTEGER` are invalid.
+pub(crate) unsafe fn file_time_ptr_to_change(
+    file_time: *const sys::FILETIME,
+) -> Result<FileTimeChange, NtStatus> {
+    if file_time.is_null() {
+        return Ok(FileTimeChange::Unchanged);
+    }
+
+    let file_time = file_time.read_unaligned();
+    match file_time_to_intervals(file_time) {
+        0 => Ok(FileTimeChange::Unchanged),
+        STOP_UPDATING => Ok(FileTimeChange::StopUpdating),
+        RESUME_UPDATING => Ok(FileTimeChange::ResumeUpdating),
+        intervals if intervals > MAX_INTERVALS => Err(NtStatus::INVALID_PARAMETER),
+        _ => Ok(FileTimeChange::Set(file_time_to_system_time(file_time))),
+    }
+}
+
+/// Encode a [`FileTimeChange`] as an optional `FILETIME`, the inverse of [`file_time_ptr_to_change`].
+pub(crate) fn file_time_change_to_file_time(change: FileTimeChange) -> Option<sys::FILETIME> {
+    let intervals = match change {
+        FileTimeChange::Unchanged => return None,
+        FileTimeChange::Set(time) => return Some(system_time_to_file_time(time)),
+        FileTimeChange::StopUpdating => STOP_UPDATING,
+        FileTimeChange::ResumeUpdating => RESUME_UPDATING,
+    };
+
+    Some(sys::FILETIME {
+        dwLowDateTime: (intervals & 0xFFFFFFFF).try_into().unwrap(),
+        dwHighDateTime: (intervals >> 32).try_into().unwrap(),
+    })
+}
+
+/// Get the raw number of 100 nanosecond intervals in a `FILETIME`.
+pub(crate) fn file_time_to_intervals(file_time: sys::FILETIME) -> u64 {
+    (u64::from(file_time.dwHighDateTime) << 32) | u64::from(file_time.dwLowDateTime)
+}
+
+fn duration_to_intervals(duration: Duration) -> u64 {
+    let intervals = duration.as_nanos() / 100;
+    u64::try_from(intervals).unwrap_or(u64::MAX)
+}
+
+fn intervals_to_duration(intervals: u64) -> Duration {
+    let secs = intervals / INTERVALS_PER_SECOND;
+    let nanos = (intervals % INTERVALS_PER_SECOND) * 100;
+    Duration::new(secs, nanos.try_into().unwrap())
+}
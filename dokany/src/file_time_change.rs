@@ -0,0 +1,20 @@
+use std::time::SystemTime;
+
+/// A requested change to one of the times of a file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FileTimeChange {
+    /// Leave the time as it is.
+    ///
+    /// This is requested with a null or zero time.
+    Unchanged,
+    /// Set the time.
+    Set(SystemTime),
+    /// Stop updating the time as the handle is used, until it is closed or updating is resumed.
+    ///
+    /// This is requested with a time of -1.
+    StopUpdating,
+    /// Resume updating the time as the handle is used, after it was stopped.
+    ///
+    /// This is requested with a time of -2.
+    ResumeUpdating,
+}
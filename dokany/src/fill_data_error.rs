@@ -0,0 +1,28 @@
+use crate::NtStatus;
+
+/// An error from supplying an entry to a listing.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FillDataError {
+    /// The buffer is full, so no more entries can be supplied.
+    ///
+    /// Returning this from a listing ends it successfully with the entries supplied so far.
+    BufferFull,
+}
+
+impl From<FillDataError> for NtStatus {
+    fn from(error: FillDataError) -> Self {
+        match error {
+            FillDataError::BufferFull => Self::BUFFER_OVERFLOW,
+        }
+    }
+}
+
+impl std::fmt::Display for FillDataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BufferFull => write!(f, "the buffer is full"),
+        }
+    }
+}
+
+impl std::error::Error for FillDataError {}
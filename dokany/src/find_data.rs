@@ -0,0 +1,169 @@
+use crate::file_time::file_time_to_system_time;
+use crate::file_time::system_time_to_file_time;
+use crate::sys;
+use crate::AsWide;
+use crate::FileAttributes;
+use std::time::SystemTime;
+
+/// A dir entry
+#[derive(Copy, Clone)]
+pub struct FindData {
+    pub(crate) find_data: sys::WIN32_FIND_DATAW,
+}
+
+impl FindData {
+    /// Create an empty file data
+    pub fn new() -> Self {
+        unsafe { std::mem::zeroed() }
+    }
+
+    /// Set the file attributes
+    pub fn set_file_attributes(&mut self, file_attributes: FileAttributes) -> &mut Self {
+        self.find_data.dwFileAttributes = file_attributes.bits();
+        self
+    }
+
+    /// Get the file attributes
+    pub fn file_attributes(&self) -> FileAttributes {
+        FileAttributes::from_bits_retain(self.find_data.dwFileAttributes)
+    }
+
+    /// Set the creation time
+    pub fn set_creation_time(&mut self, time: SystemTime) -> &mut Self {
+        self.find_data.ftCreationTime = system_time_to_file_time(time);
+        self
+    }
+
+    /// Get the creation time
+    pub fn creation_time(&self) -> SystemTime {
+        file_time_to_system_time(self.find_data.ftCreationTime)
+    }
+
+    /// Set the last access time
+    pub fn set_last_access_time(&mut self, time: SystemTime) -> &mut Self {
+        self.find_data.ftLastAccessTime = system_time_to_file_time(time);
+        self
+    }
+
+    /// Get the last access time
+    pub fn last_access_time(&self) -> SystemTime {
+        file_time_to_system_time(self.find_data.ftLastAccessTime)
+    }
+
+    /// Set the last write time
+    pub fn set_last_write_time(&mut self, time: SystemTime) -> &mut Self {
+        self.find_data.ftLastWriteTime = system_time_to_file_time(time);
+        self
+    }
+
+    /// Get the last write time
+    pub fn last_write_time(&self) -> SystemTime {
+        file_time_to_system_time(self.find_data.ftLastWriteTime)
+    }
+
+    /// Set the file size
+    pub fn set_size(&mut self, file_size: u64) -> &mut Self {
+        let low: u32 = (file_size & 0xFFFFFFFF).try_into().unwrap();
+        let high: u32 = (file_size >> 32).try_into().unwrap();
+
+        self.find_data.nFileSizeHigh = high;
+        self.find_data.nFileSizeLow = low;
+        self
+    }
+
+    /// Get the file size
+    pub fn size(&self) -> u64 {
+        (u64::from(self.find_data.nFileSizeHigh) << 32) | u64::from(self.find_data.nFileSizeLow)
+    }
+
+    /// Set the reparse point tag.
+    ///
+    /// This also sets the [`FileAttributes::REPARSE_POINT`] attribute, as the tag is ignored otherwise.
+    pub fn set_reparse_tag(&mut self, reparse_tag: u32) -> &mut Self {
+        self.find_data.dwReserved0 = reparse_tag;
+        self.find_data.dwFileAttributes |= sys::FILE_ATTRIBUTE_REPARSE_POINT;
+        self
+    }
+
+    /// Get the reparse point tag, if this is a reparse point.
+    pub fn reparse_tag(&self) -> Option<u32> {
+        if self
+            .file_attributes()
+            .contains(FileAttributes::REPARSE_POINT)
+        {
+            Some(self.find_data.dwReserved0)
+        } else {
+            None
+        }
+    }
+
+    /// Set the file name.
+    ///
+    /// If the name is too long, it is truncated.
+    pub fn set_file_name(&mut self, file_name: impl AsWide) -> &mut Self {
+        write_wide_buffer(&mut self.find_data.cFileName, file_name);
+        self
+    }
+
+    /// Get the file name, without the NUL terminator.
+    pub fn file_name(&self) -> &[u16] {
+        wide_buffer_contents(&self.find_data.cFileName)
+    }
+
+    /// Set the alternate 8.3 file name.
+    ///
+    /// If the name is too long, it is truncated.
+    pub fn set_alternate_file_name(&mut self, alternate_file_name: impl AsWide) -> &mut Self {
+        write_wide_buffer(&mut self.find_data.cAlternateFileName, alternate_file_name);
+        self
+    }
+
+    /// Get the alternate 8.3 file name, without the NUL terminator.
+    ///
+    /// This is empty if there is no alternate name.
+    pub fn alternate_file_name(&self) -> &[u16] {
+        wide_buffer_contents(&self.find_data.cAlternateFileName)
+    }
+}
+
+impl Default for FindData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for FindData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FindData")
+            .field("file_name", &String::from_utf16_lossy(self.file_name()))
+            .field(
+                "alternate_file_name",
+                &String::from_utf16_lossy(self.alternate_file_name()),
+            )
+            .field("file_attributes", &self.file_attributes())
+            .field("size", &self.size())
+            .field("creation_time", &self.creation_time())
+            .field("last_access_time", &self.last_access_time())
+            .field("last_write_time", &self.last_write_time())
+            .field("reparse_tag", &self.reparse_tag())
+            .finish()
+    }
+}
+
+/// Write a NUL-terminated wide string into a buffer, truncating it if needed.
+pub(crate) fn write_wide_buffer(buffer: &mut [u16], value: impl AsWide) {
+    // Reserve NUL
+    let max_len = buffer.len() - 1;
+    for (buffer, c) in buffer
+        .iter_mut()
+        .zip(value.as_wide().take(max_len).chain(std::iter::once(0)))
+    {
+        *buffer = c;
+    }
+}
+
+/// Get the contents of a NUL-terminated wide string buffer.
+pub(crate) fn wide_buffer_contents(buffer: &[u16]) -> &[u16] {
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    &buffer[..len]
+}
@@ -0,0 +1,61 @@
+use crate::find_data::wide_buffer_contents;
+use crate::find_data::write_wide_buffer;
+use crate::sys;
+use crate::AsWide;
+
+/// A stream entry
+#[derive(Copy, Clone)]
+pub struct FindStreamData {
+    pub(crate) find_stream_data: sys::WIN32_FIND_STREAM_DATA,
+}
+
+impl FindStreamData {
+    /// Create an empty stream data
+    pub fn new() -> Self {
+        unsafe { std::mem::zeroed() }
+    }
+
+    /// Set the stream name.
+    ///
+    /// This is in the form `:name:$DATA`, or `::$DATA` for the unnamed data stream.
+    /// If the name is too long, it is truncated.
+    pub fn set_stream_name(&mut self, stream_name: impl AsWide) -> &mut Self {
+        write_wide_buffer(&mut self.find_stream_data.cStreamName, stream_name);
+        self
+    }
+
+    /// Get the stream name, without the NUL terminator.
+    pub fn stream_name(&self) -> &[u16] {
+        wide_buffer_contents(&self.find_stream_data.cStreamName)
+    }
+
+    /// Set the stream size
+    ///
+    /// # Panics
+    /// Panics if the size is larger than `i64::MAX`.
+    pub fn set_size(&mut self, stream_size: u64) -> &mut Self {
+        self.find_stream_data.StreamSize =
+            i64::try_from(stream_size).expect("stream size larger than `i64::MAX`");
+        self
+    }
+
+    /// Get the stream size
+    pub fn size(&self) -> u64 {
+        u64::try_from(self.find_stream_data.StreamSize).unwrap_or(0)
+    }
+}
+
+impl Default for FindStreamData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for FindStreamData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FindStreamData")
+            .field("stream_name", &String::from_utf16_lossy(self.stream_name()))
+            .field("size", &self.size())
+            .finish()
+    }
+}
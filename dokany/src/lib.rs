@@ -1,21 +1,79 @@
 mod access_mask;
+mod byte_range_lock_manager;
+mod create_disposition;
+mod create_file_info;
+mod create_file_result;
+mod create_options;
+mod disk_space;
+mod dokan_path;
+mod file_attributes;
+mod file_info;
+mod file_information;
 mod file_system_flags;
+mod file_system_handle;
+mod file_time;
+mod file_time_change;
+mod fill_data_error;
+mod find_data;
+mod find_stream_data;
 mod main_result;
+pub mod memfs;
+pub mod mirror;
+mod name_in_expression;
+mod nt_status;
+mod nul_error;
 mod operations;
 mod option_flags;
 mod options;
+mod options_error;
+pub mod security;
+mod security_information;
+mod share_access;
+pub mod testing;
 mod wide;
+mod wide_c_str;
+mod wide_c_string;
+mod wide_str;
+mod wide_string;
 // mod filesystem;
-// mod wide_string;
 
 pub use self::access_mask::AccessMask;
+pub use self::byte_range_lock_manager::ByteRangeLockManager;
+pub use self::byte_range_lock_manager::LockKind;
+pub use self::create_disposition::CreateDisposition;
+pub use self::create_file_info::CreateFileInfo;
+pub use self::create_file_info::SecurityContext;
+pub use self::create_file_result::CreateFileResult;
+pub use self::create_options::CreateOptions;
+pub use self::disk_space::DiskSpace;
+pub use self::dokan_path::DokanPath;
+pub use self::file_attributes::FileAttributes;
+pub use self::file_info::FileInfo;
+pub use self::file_information::FileInformation;
 pub use self::file_system_flags::FileSystemFlags;
+pub use self::file_system_handle::FileSystemHandle;
+pub use self::file_time_change::FileTimeChange;
+pub use self::fill_data_error::FillDataError;
+pub use self::find_data::FindData;
+pub use self::find_stream_data::FindStreamData;
 pub use self::main_result::MainResult;
-pub(crate) use self::operations::OPERATIONS;
+pub use self::name_in_expression::is_name_in_expression;
+pub use self::nt_status::NtStatus;
+pub use self::nul_error::NulError;
+pub(crate) use self::operations::Operations;
 pub use self::option_flags::OptionFlags;
 pub use self::options::Options;
+pub use self::options_error::OptionsError;
+pub use self::security_information::SecurityInformation;
+pub use self::share_access::ShareAccess;
 pub use self::wide::AsWide;
+pub use self::wide_c_str::WideCStr;
+pub use self::wide_c_string::WideCString;
+pub use self::wide_str::WideStr;
+pub use self::wide_string::WideString;
 pub use dokany_sys as sys;
+use std::cell::Cell;
+use std::marker::PhantomData;
 use std::mem::MaybeUninit;
 use std::sync::Once;
 
@@ -59,74 +117,330 @@ impl<'a> WriteWideCStringCell<'a> {
 pub struct FillFindData<'a> {
     dokan_file_info: &'a mut sys::DOKAN_FILE_INFO,
     func: sys::PFillFindData,
+    /// Set when Dokan reports that its buffer is full.
+    buffer_full: &'a Cell<bool>,
 }
 
 impl FillFindData<'_> {
     /// Fill this with a new FindData entry.
-    pub fn fill(&mut self, find_data: &mut FindData) {
+    ///
+    /// # Errors
+    /// Returns [`FillDataError::BufferFull`] if no more entries can be supplied.
+    /// This may be propagated out of the listing, which then ends successfully.
+    pub fn fill(&mut self, find_data: &FindData) -> Result<(), FillDataError> {
         let func = self.func.unwrap();
 
-        let result = unsafe { (func)(&mut find_data.find_data, self.dokan_file_info) };
+        // Dokan only reads the entry.
+        let find_data = &find_data.find_data as *const sys::WIN32_FIND_DATAW;
+        let result = unsafe { (func)(find_data.cast_mut(), self.dokan_file_info) };
+
+        if result != 0 {
+            self.buffer_full.set(true);
+            return Err(FillDataError::BufferFull);
+        }
 
-        assert!(result == 0);
+        Ok(())
     }
 }
 
-/// A dir entry
-pub struct FindData {
-    find_data: sys::WIN32_FIND_DATAW,
+/// Supply a FindStreamData entry for stream listing.
+pub struct FillFindStreamData<'a> {
+    func: sys::PFillFindStreamData,
+    find_stream_context: sys::PVOID,
+    _marker: PhantomData<&'a mut ()>,
 }
 
-impl FindData {
-    /// Create an empty file data
-    pub fn new() -> Self {
-        unsafe { std::mem::zeroed() }
-    }
-
-    /// Set the file size
-    pub fn set_size(&mut self, file_size: u64) {
-        let low: u32 = (file_size & 0xFFFFFFFF).try_into().unwrap();
-        let high: u32 = (file_size >> 32).try_into().unwrap();
+impl FillFindStreamData<'_> {
+    /// Fill this with a new FindStreamData entry.
+    ///
+    /// # Errors
+    /// Returns [`FillDataError::BufferFull`] if no more entries can be supplied.
+    /// Unlike with directory listings, this should be propagated out of the listing,
+    /// so that the caller learns that the list is incomplete.
+    pub fn fill(&mut self, find_stream_data: &FindStreamData) -> Result<(), FillDataError> {
+        let func = self.func.unwrap();
 
-        self.find_data.nFileSizeHigh = high;
-        self.find_data.nFileSizeLow = low;
-    }
+        // Dokan only reads the entry.
+        let find_stream_data =
+            &find_stream_data.find_stream_data as *const sys::WIN32_FIND_STREAM_DATA;
+        let result = unsafe { (func)(find_stream_data.cast_mut(), self.find_stream_context) };
 
-    /// Set the file name.
-    pub fn set_file_name(&mut self, file_name: impl AsWide) {
-        let max_len = self.find_data.cFileName.len() - 1;
-        for (buffer, c) in self
-            .find_data
-            .cFileName
-            .iter_mut()
-            .zip(file_name.as_wide().take(max_len).chain(std::iter::once(0)))
-        {
-            *buffer = c;
+        if result == sys::FALSE {
+            return Err(FillDataError::BufferFull);
         }
-    }
-}
 
-impl Default for FindData {
-    fn default() -> Self {
-        Self::new()
+        Ok(())
     }
 }
 
 /// The trait a type must implement to serve as a file system
 pub trait FileSystem: Send + Sync + 'static {
+    /// The per-handle context.
+    ///
+    /// This is created by [`FileSystem::create_file`] and passed to every operation on the opened handle.
+    /// It is dropped when the handle is closed.
+    type Context: Send + Sync;
+
     /// Called for opening files and directories
+    ///
+    /// `is_dir` is initially true if the file is known to be a directory.
+    /// It should be set to true if the opened file is a directory.
+    ///
+    /// # Returns
+    /// Returns the context for the opened handle, and whether an existing file was opened.
+    ///
+    /// Errors must be error statuses. A success status or, for a disposition that may open or create a file,
+    /// `OBJECT_NAME_COLLISION` would leave Dokan with an open handle and no context,
+    /// so these are reported as an internal error instead.
     fn create_file(
         &self,
         _file_name: &[u16],
-        _desired_access: AccessMask,
+        _create_file_info: &CreateFileInfo<'_>,
         _is_dir: &mut bool,
-    ) -> sys::NTSTATUS {
-        sys::STATUS_NOT_IMPLEMENTED
+    ) -> Result<CreateFileResult<Self::Context>, NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
+    }
+
+    /// Called when the last user handle to a file is closed.
+    ///
+    /// If [`FileInfo::delete_on_close`] is set, the file should be deleted here.
+    /// Other operations may still be made on the handle until [`FileSystem::close_file`] is called.
+    fn cleanup(&self, _file_name: &[u16], _context: &Self::Context, _file_info: &FileInfo) {}
+
+    /// Called when the handle is closed, after every other operation on it.
+    ///
+    /// The context is dropped when this returns.
+    fn close_file(&self, _file_name: &[u16], _context: Self::Context) {}
+
+    /// Called to read data from a file.
+    ///
+    /// `buffer` should be filled with data starting at `offset`.
+    ///
+    /// # Returns
+    /// Returns the number of bytes read.
+    fn read_file(
+        &self,
+        _file_name: &[u16],
+        _context: &Self::Context,
+        _buffer: &mut [u8],
+        _offset: u64,
+    ) -> Result<usize, NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
+    }
+
+    /// Called to write data to a file.
+    ///
+    /// If `write_to_eof` is true, `data` should be appended to the end of the file and `offset` should be ignored.
+    ///
+    /// # Returns
+    /// Returns the number of bytes written.
+    fn write_file(
+        &self,
+        _file_name: &[u16],
+        _context: &Self::Context,
+        _data: &[u8],
+        _offset: u64,
+        _write_to_eof: bool,
+    ) -> Result<usize, NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
+    }
+
+    /// Called to set the size of a file, truncating or zero-extending it.
+    fn set_end_of_file(
+        &self,
+        _file_name: &[u16],
+        _context: &Self::Context,
+        _length: u64,
+    ) -> Result<(), NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
+    }
+
+    /// Called to set the space allocated for a file.
+    ///
+    /// If `length` is less than the size of the file, the file should be truncated to it.
+    /// Otherwise, the size of the file does not change.
+    fn set_allocation_size(
+        &self,
+        _file_name: &[u16],
+        _context: &Self::Context,
+        _length: u64,
+    ) -> Result<(), NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
+    }
+
+    /// Called to lock a range of a file.
+    ///
+    /// This is only called if [`OptionFlags::FILELOCK_USER_MODE`] is set.
+    /// A [`ByteRangeLockManager`] can be used to track the locks.
+    fn lock_file(
+        &self,
+        _file_name: &[u16],
+        _context: &Self::Context,
+        _offset: u64,
+        _length: u64,
+    ) -> Result<(), NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
+    }
+
+    /// Called to unlock a range of a file previously locked with [`FileSystem::lock_file`].
+    fn unlock_file(
+        &self,
+        _file_name: &[u16],
+        _context: &Self::Context,
+        _offset: u64,
+        _length: u64,
+    ) -> Result<(), NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
+    }
+
+    /// Called to get information about an open file.
+    fn get_file_information(
+        &self,
+        _file_name: &[u16],
+        _context: &Self::Context,
+    ) -> Result<FileInformation, NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
     }
 
     /// Called to get a function that returns entries in a directory
-    fn find_files(&self, _file_name: &[u16], _fill_find_data: FillFindData<'_>) -> sys::NTSTATUS {
-        sys::STATUS_NOT_IMPLEMENTED
+    fn find_files(
+        &self,
+        _file_name: &[u16],
+        _context: &Self::Context,
+        _fill_find_data: FillFindData<'_>,
+    ) -> Result<(), NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
+    }
+
+    /// Called to list the entries in a directory that match a search pattern.
+    ///
+    /// `search_pattern` is a DOS wildcard expression, see [`is_name_in_expression`].
+    /// If this returns [`NtStatus::NOT_IMPLEMENTED`],
+    /// [`FileSystem::find_files`] is called instead and Dokan filters the entries itself.
+    fn find_files_with_pattern(
+        &self,
+        _file_name: &[u16],
+        _context: &Self::Context,
+        _search_pattern: &[u16],
+        _fill_find_data: FillFindData<'_>,
+    ) -> Result<(), NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
+    }
+
+    /// Called to list the alternate data streams of a file.
+    ///
+    /// Each entry is named like `:name:$DATA`, and files should also list their unnamed stream as `::$DATA`.
+    /// This is only called if [`OptionFlags::ALT_STREAM`] is set.
+    fn find_streams(
+        &self,
+        _file_name: &[u16],
+        _context: &Self::Context,
+        _fill_find_stream_data: FillFindStreamData<'_>,
+    ) -> Result<(), NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
+    }
+
+    /// Called to set the attributes of a file.
+    ///
+    /// Empty attributes mean the attributes should not change.
+    fn set_file_attributes(
+        &self,
+        _file_name: &[u16],
+        _context: &Self::Context,
+        _file_attributes: FileAttributes,
+    ) -> Result<(), NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
+    }
+
+    /// Called to set the times of a file.
+    ///
+    /// Each time may also be left unchanged, or stop or resume being updated as this handle is used.
+    fn set_file_time(
+        &self,
+        _file_name: &[u16],
+        _context: &Self::Context,
+        _creation_time: FileTimeChange,
+        _last_access_time: FileTimeChange,
+        _last_write_time: FileTimeChange,
+    ) -> Result<(), NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
+    }
+
+    /// Called to check whether a file can be deleted.
+    ///
+    /// The file must not be deleted here, but in [`FileSystem::cleanup`] if [`FileInfo::delete_on_close`] is set.
+    /// This is also called with [`FileInfo::delete_on_close`] unset when the deletion is cancelled.
+    fn delete_file(
+        &self,
+        _file_name: &[u16],
+        _context: &Self::Context,
+        _file_info: &FileInfo,
+    ) -> Result<(), NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
+    }
+
+    /// Called to check whether a directory can be deleted.
+    ///
+    /// This should return [`NtStatus::DIRECTORY_NOT_EMPTY`] if the directory has any entries.
+    /// Otherwise, this works like [`FileSystem::delete_file`].
+    fn delete_directory(
+        &self,
+        _file_name: &[u16],
+        _context: &Self::Context,
+        _file_info: &FileInfo,
+    ) -> Result<(), NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
+    }
+
+    /// Called to move or rename a file or directory.
+    ///
+    /// If `new_file_name` exists and `replace_if_existing` is false,
+    /// this should return [`NtStatus::OBJECT_NAME_COLLISION`].
+    fn move_file(
+        &self,
+        _file_name: &[u16],
+        _context: &Self::Context,
+        _new_file_name: &[u16],
+        _replace_if_existing: bool,
+    ) -> Result<(), NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
+    }
+
+    /// Called to get the security of a file or directory.
+    ///
+    /// This should return a self-relative security descriptor with the parts selected by `security_information`.
+    /// Buffering is handled by the caller, so the descriptor may be of any length.
+    ///
+    /// If this is not implemented, Dokan builds a descriptor that grants access to the user running the filesystem.
+    fn get_file_security(
+        &self,
+        _file_name: &[u16],
+        _context: &Self::Context,
+        _security_information: SecurityInformation,
+    ) -> Result<Vec<u8>, NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
+    }
+
+    /// Called to change the security of a file or directory.
+    ///
+    /// `security_descriptor` is a self-relative security descriptor.
+    /// Only the parts selected by `security_information` should be changed.
+    fn set_file_security(
+        &self,
+        _file_name: &[u16],
+        _context: &Self::Context,
+        _security_information: SecurityInformation,
+        _security_descriptor: &[u8],
+    ) -> Result<(), NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
+    }
+
+    /// Called for calls to GetDiskFreeSpace, to get the capacity of the volume.
+    ///
+    /// If this is not implemented, [`DiskSpace::default`] is reported.
+    /// The result is [clamped](DiskSpace::clamped) before it is passed to Windows.
+    fn get_disk_free_space(&self) -> Result<DiskSpace, NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
     }
 
     /// Called for calls to GetVolumeInformation
@@ -137,18 +451,18 @@ pub trait FileSystem: Send + Sync + 'static {
         _maximum_component_length: &mut u32,
         _file_system_flags: &mut FileSystemFlags,
         _file_system_name: WriteWideCStringCell<'_>,
-    ) -> sys::NTSTATUS {
-        sys::STATUS_NOT_IMPLEMENTED
+    ) -> Result<(), NtStatus> {
+        Err(NtStatus::NOT_IMPLEMENTED)
     }
 
     /// Called when the filesystem is mounted
-    fn mounted(&self, _mount_point: &[u16]) -> sys::NTSTATUS {
-        sys::STATUS_SUCCESS
+    fn mounted(&self, _mount_point: &[u16]) -> Result<(), NtStatus> {
+        Ok(())
     }
 
     /// Called when the filesystem is unmounted
-    fn unmounted(&self) -> sys::NTSTATUS {
-        sys::STATUS_SUCCESS
+    fn unmounted(&self) -> Result<(), NtStatus> {
+        Ok(())
     }
 }
 
@@ -176,21 +490,21 @@ pub fn driver_version() -> u32 {
     unsafe { sys::DokanDriverVersion() }
 }
 
-pub(crate) struct GlobalContext {
-    pub filesystem: Box<dyn FileSystem>,
+pub(crate) struct GlobalContext<FS> {
+    pub filesystem: FS,
 }
 
 /// Mount and run a filesystem from the given options an mount object.
-pub fn main(mut options: Options, filesystem: impl FileSystem) -> Result<(), MainResult> {
+pub fn main<FS: FileSystem>(mut options: Options, filesystem: FS) -> Result<(), MainResult> {
     // Inject the filesystem as context.
-    let context = Box::new(GlobalContext {
-        filesystem: Box::new(filesystem),
-    });
+    let context = Box::new(GlobalContext { filesystem });
     let context_ptr = Box::into_raw(context);
     options.options.GlobalContext = context_ptr as u64;
 
-    // Official docs also use a global static, so this is probably safe.
-    let operations = &OPERATIONS as *const sys::DOKAN_OPERATIONS as *mut sys::DOKAN_OPERATIONS;
+    // Official docs also use a global static.
+    // This is a promoted constant, so it is also static and this is probably safe.
+    let operations =
+        Operations::<FS>::OPERATIONS as *const sys::DOKAN_OPERATIONS as *mut sys::DOKAN_OPERATIONS;
 
     // Mount, run, unmount.
     let result = MainResult(unsafe { sys::DokanMain(&mut options.options, operations) });
@@ -202,6 +516,43 @@ pub fn main(mut options: Options, filesystem: impl FileSystem) -> Result<(), Mai
     result.into()
 }
 
+/// Mount a filesystem from the given options, without blocking.
+///
+/// # Returns
+/// Returns a handle that unmounts the filesystem when dropped.
+pub fn mount<FS: FileSystem>(
+    options: Options,
+    filesystem: FS,
+) -> Result<FileSystemHandle<FS>, MainResult> {
+    init();
+
+    // Dokan keeps a pointer to the options, so they need a stable address.
+    let mut options = Box::new(options);
+
+    // Inject the filesystem as context.
+    let context = Box::new(GlobalContext { filesystem });
+    let context_ptr = Box::into_raw(context);
+    options.options.GlobalContext = context_ptr as u64;
+
+    let operations =
+        Operations::<FS>::OPERATIONS as *const sys::DOKAN_OPERATIONS as *mut sys::DOKAN_OPERATIONS;
+
+    let mut instance = std::ptr::null();
+    let result = MainResult(unsafe {
+        sys::DokanCreateFileSystem(&mut options.options, operations, &mut instance)
+    });
+
+    if !result.is_success() {
+        // Free context
+        let context = unsafe { Box::from_raw(context_ptr) };
+        drop(context);
+
+        return Err(result);
+    }
+
+    Ok(unsafe { FileSystemHandle::new(instance, options, context_ptr) })
+}
+
 /// Unmount the drive from the given drive letter.
 ///
 /// # Panics
@@ -226,7 +577,10 @@ pub fn unmount(drive_letter: char) -> bool {
 pub fn remove_mount_point(path: impl AsWide) -> bool {
     init();
 
-    let path: Vec<u16> = path.as_wide().chain(std::iter::once(0)).collect();
+    // A path with a NUL cannot be a mount point.
+    let Ok(path) = WideCString::new(path) else {
+        return false;
+    };
 
     unsafe { sys::DokanRemoveMountPoint(path.as_ptr()) == sys::TRUE }
 }
@@ -243,47 +597,49 @@ pub unsafe fn shutdown() {
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::ffi::OsString;
-    use std::os::windows::ffi::OsStringExt;
     use std::path::Path;
     use std::path::PathBuf;
 
     struct SimpleFileSystem;
 
     impl FileSystem for SimpleFileSystem {
+        type Context = ();
+
         fn create_file(
             &self,
             file_name: &[u16],
-            desired_access: AccessMask,
+            create_file_info: &CreateFileInfo<'_>,
             is_dir: &mut bool,
-        ) -> sys::NTSTATUS {
-            let file_name = PathBuf::from(OsString::from_wide(file_name));
+        ) -> Result<CreateFileResult<Self::Context>, NtStatus> {
+            let file_name = PathBuf::from(String::from_utf16_lossy(file_name));
             println!(
-                "CreateFile(file_name=\"{}\", desired_access={:?})",
+                "CreateFile(file_name=\"{}\", desired_access={:?}, create_disposition={:?})",
                 file_name.display(),
-                desired_access
+                create_file_info.desired_access(),
+                create_file_info.create_disposition(),
             );
 
             if file_name.starts_with("\\System Volume Information") {
-                return sys::STATUS_NO_SUCH_FILE;
+                return Err(NtStatus::NO_SUCH_FILE);
             }
 
             if file_name == Path::new("/") {
                 *is_dir = true;
             }
 
-            sys::STATUS_SUCCESS
+            Ok(CreateFileResult::opened(()))
         }
 
         fn find_files(
             &self,
             file_name: &[u16],
+            _context: &Self::Context,
             _fill_find_data: FillFindData<'_>,
-        ) -> sys::NTSTATUS {
-            let file_name = PathBuf::from(OsString::from_wide(file_name));
+        ) -> Result<(), NtStatus> {
+            let file_name = PathBuf::from(String::from_utf16_lossy(file_name));
             println!("FindFiles(file_name=\"{}\")", file_name.display());
 
-            sys::STATUS_SUCCESS
+            Ok(())
         }
 
         fn get_volume_information(
@@ -293,7 +649,7 @@ mod test {
             maximum_component_length: &mut u32,
             file_system_flags: &mut FileSystemFlags,
             mut file_system_name: WriteWideCStringCell<'_>,
-        ) -> sys::NTSTATUS {
+        ) -> Result<(), NtStatus> {
             volume_name.write("Simple Filesystem");
 
             *volume_serial_number = 1337;
@@ -306,18 +662,18 @@ mod test {
 
             println!("GetVolumeInformation");
 
-            sys::STATUS_SUCCESS
+            Ok(())
         }
 
-        fn mounted(&self, mounted: &[u16]) -> sys::NTSTATUS {
-            let mounted = PathBuf::from(OsString::from_wide(mounted));
+        fn mounted(&self, mounted: &[u16]) -> Result<(), NtStatus> {
+            let mounted = PathBuf::from(String::from_utf16_lossy(mounted));
             println!("Mounted at \"{}\"", mounted.display());
-            sys::STATUS_SUCCESS
+            Ok(())
         }
 
-        fn unmounted(&self) -> sys::NTSTATUS {
+        fn unmounted(&self) -> Result<(), NtStatus> {
             println!("Unmounted");
-            sys::STATUS_SUCCESS
+            Ok(())
         }
     }
 
@@ -347,7 +703,7 @@ mod test {
 
         let mut options = Options::new();
         options.set_version(209);
-        options.set_mount_point("Z");
+        options.set_mount_point("Z").unwrap();
         options.set_option_flags(OptionFlags::MOUNT_MANAGER);
 
         let simple_filesystem = SimpleFileSystem;
@@ -363,4 +719,214 @@ mod test {
 
         unsafe { shutdown() }
     }
+
+    #[test]
+    fn find_data() {
+        let time = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+
+        let mut find_data = FindData::new();
+        find_data
+            .set_file_name("a long file name.txt")
+            .set_alternate_file_name("ALONGF~1.TXT")
+            .set_file_attributes(FileAttributes::ARCHIVE | FileAttributes::HIDDEN)
+            .set_creation_time(time)
+            .set_last_access_time(time)
+            .set_last_write_time(time)
+            .set_size(0x1_0000_0001);
+
+        assert_eq!(
+            String::from_utf16_lossy(find_data.file_name()),
+            "a long file name.txt"
+        );
+        assert_eq!(
+            String::from_utf16_lossy(find_data.alternate_file_name()),
+            "ALONGF~1.TXT"
+        );
+        assert_eq!(
+            find_data.file_attributes(),
+            FileAttributes::ARCHIVE | FileAttributes::HIDDEN
+        );
+        assert_eq!(find_data.creation_time(), time);
+        assert_eq!(find_data.last_access_time(), time);
+        assert_eq!(find_data.last_write_time(), time);
+        assert_eq!(find_data.size(), 0x1_0000_0001);
+        assert_eq!(find_data.reparse_tag(), None);
+
+        find_data.set_reparse_tag(0xA000000C);
+        assert_eq!(find_data.reparse_tag(), Some(0xA000000C));
+        assert!(find_data
+            .file_attributes()
+            .contains(FileAttributes::REPARSE_POINT));
+
+        // Names that are too long are truncated, leaving room for the NUL terminator.
+        find_data.set_alternate_file_name("TOOLONGNAME.TXT");
+        assert_eq!(
+            String::from_utf16_lossy(find_data.alternate_file_name()),
+            "TOOLONGNAME.T"
+        );
+
+        let debug = format!("{find_data:?}");
+        assert!(debug.contains("a long file name.txt"), "{debug}");
+    }
+
+    #[test]
+    fn file_time_changes() {
+        use crate::file_time::file_time_change_to_file_time;
+        use crate::file_time::file_time_ptr_to_change;
+        use std::time::SystemTime;
+
+        let file_time = |intervals: u64| sys::FILETIME {
+            dwLowDateTime: intervals as u32,
+            dwHighDateTime: (intervals >> 32) as u32,
+        };
+
+        unsafe {
+            assert_eq!(
+                file_time_ptr_to_change(std::ptr::null()),
+                Ok(FileTimeChange::Unchanged)
+            );
+            assert_eq!(
+                file_time_ptr_to_change(&file_time(0)),
+                Ok(FileTimeChange::Unchanged)
+            );
+            assert_eq!(
+                file_time_ptr_to_change(&file_time(u64::MAX)),
+                Ok(FileTimeChange::StopUpdating)
+            );
+            assert_eq!(
+                file_time_ptr_to_change(&file_time(u64::MAX - 1)),
+                Ok(FileTimeChange::ResumeUpdating)
+            );
+            assert_eq!(
+                file_time_ptr_to_change(&file_time(u64::MAX - 2)),
+                Err(NtStatus::INVALID_PARAMETER)
+            );
+            assert_eq!(
+                file_time_ptr_to_change(&file_time(116_444_736_000_000_000)),
+                Ok(FileTimeChange::Set(SystemTime::UNIX_EPOCH))
+            );
+        }
+
+        // Every change survives a round trip, except for zero which is the same as null.
+        for change in [
+            FileTimeChange::Unchanged,
+            FileTimeChange::Set(SystemTime::UNIX_EPOCH),
+            FileTimeChange::StopUpdating,
+            FileTimeChange::ResumeUpdating,
+        ] {
+            let file_time = file_time_change_to_file_time(change);
+            let file_time_ptr = file_time
+                .as_ref()
+                .map_or(std::ptr::null(), |file_time| file_time as *const _);
+            assert_eq!(
+                unsafe { file_time_ptr_to_change(file_time_ptr) },
+                Ok(change)
+            );
+        }
+    }
+
+    #[test]
+    fn options() {
+        let mut options = Options::new();
+        options
+            .set_version(209)
+            .set_single_thread(true)
+            .set_mount_point("Z")
+            .unwrap()
+            .set_unc_name("\\\\dokan\\share")
+            .unwrap()
+            .set_timeout(std::time::Duration::from_secs(30))
+            .set_sector_size(4096)
+            .unwrap()
+            .set_allocation_unit_size(4096)
+            .unwrap()
+            .set_volume_security_descriptor(&[1, 0, 4, 128])
+            .unwrap();
+
+        assert_eq!(options.version(), 209);
+        assert!(options.single_thread());
+        assert_eq!(options.mount_point(), Some(&[u16::from(b'Z')][..]));
+        assert_eq!(
+            options.unc_name().map(String::from_utf16_lossy).as_deref(),
+            Some("\\\\dokan\\share")
+        );
+        assert_eq!(options.timeout(), std::time::Duration::from_secs(30));
+        assert_eq!(options.sector_size(), 4096);
+        assert_eq!(options.allocation_unit_size(), 4096);
+        assert_eq!(
+            options.volume_security_descriptor(),
+            Some(&[1, 0, 4, 128][..])
+        );
+
+        assert_eq!(
+            options.set_sector_size(1000).err(),
+            Some(OptionsError::InvalidSectorSize(1000))
+        );
+        assert_eq!(
+            options.set_allocation_unit_size(256).err(),
+            Some(OptionsError::InvalidAllocationUnitSize(256))
+        );
+        let too_large = vec![0; sys::VOLUME_SECURITY_DESCRIPTOR_MAX_SIZE + 1];
+        assert_eq!(
+            options.set_volume_security_descriptor(&too_large).err(),
+            Some(OptionsError::SecurityDescriptorTooLarge(too_large.len()))
+        );
+        assert_eq!(options.sector_size(), 4096);
+
+        options.set_volume_security_descriptor(&[]).unwrap();
+        assert_eq!(options.volume_security_descriptor(), None);
+        assert_eq!(Options::new().mount_point(), None);
+
+        assert_eq!(
+            options.set_mount_point("Z\0").err(),
+            Some(OptionsError::InvalidMountPoint(NulError::Interior(1)))
+        );
+        assert_eq!(options.mount_point(), Some(&[u16::from(b'Z')][..]));
+    }
+
+    #[test]
+    #[ignore]
+    fn mount_handle() {
+        let mut options = Options::new();
+        options.set_version(209);
+        options.set_mount_point("Y").unwrap();
+        options.set_option_flags(OptionFlags::MOUNT_MANAGER);
+
+        let handle = match mount(options, SimpleFileSystem) {
+            Ok(handle) => handle,
+            Err(e) => {
+                panic!("{e}");
+            }
+        };
+        assert!(handle.is_running());
+        assert!(!handle.wait(Some(std::time::Duration::from_millis(100))));
+
+        handle.unmount();
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn mount_without_driver() {
+        use std::sync::atomic::AtomicBool;
+        use std::sync::atomic::Ordering;
+        use std::sync::Arc;
+
+        struct DropFileSystem(Arc<AtomicBool>);
+
+        impl FileSystem for DropFileSystem {
+            type Context = ();
+        }
+
+        impl Drop for DropFileSystem {
+            fn drop(&mut self) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let dropped = Arc::new(AtomicBool::new(false));
+        let result = mount(Options::new(), DropFileSystem(dropped.clone()));
+
+        assert_eq!(result.err(), Some(MainResult::DRIVER_INSTALL_ERROR));
+        assert!(dropped.load(Ordering::SeqCst));
+    }
 }
@@ -0,0 +1,1670 @@
+//! A filesystem that keeps everything in memory.
+//!
+//! [`MemFs`] is both an example of a complete [`FileSystem`] and a baseline to test other
+//! implementations against.
+
+use crate::is_name_in_expression;
+use crate::security::SecurityDescriptor;
+use crate::AccessMask;
+use crate::ByteRangeLockManager;
+use crate::CreateDisposition;
+use crate::CreateFileInfo;
+use crate::CreateFileResult;
+use crate::CreateOptions;
+use crate::DokanPath;
+use crate::FileAttributes;
+use crate::FileInfo;
+use crate::FileInformation;
+use crate::FileSystem;
+use crate::FileSystemFlags;
+use crate::FileTimeChange;
+use crate::FillFindData;
+use crate::FillFindStreamData;
+use crate::FindData;
+use crate::FindStreamData;
+use crate::LockKind;
+use crate::NtStatus;
+use crate::SecurityInformation;
+use crate::WriteWideCStringCell;
+use std::collections::BTreeMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::RwLock;
+use std::sync::RwLockReadGuard;
+use std::sync::RwLockWriteGuard;
+use std::sync::Weak;
+use std::time::SystemTime;
+
+/// The maximum length of a file name component, in chars.
+const MAX_COMPONENT_LEN: usize = 255;
+
+/// The serial number of every [`MemFs`] volume.
+const VOLUME_SERIAL_NUMBER: u32 = 0x19831116;
+
+/// Access rights that allow modifying the data of a file.
+const WRITE_ACCESS: AccessMask = AccessMask::FILE_WRITE_DATA
+    .union(AccessMask::FILE_APPEND_DATA)
+    .union(AccessMask::GENERIC_WRITE)
+    .union(AccessMask::GENERIC_ALL);
+
+/// An in-memory filesystem.
+pub struct MemFs {
+    root: Arc<Node>,
+    volume_name: String,
+    /// The file index of the next created node.
+    next_index: AtomicU64,
+    /// The id of the next opened handle, which owns its byte range locks.
+    next_handle_id: AtomicU64,
+    /// Held while directory entries are added, removed or moved.
+    ///
+    /// Only the holder may lock more than one node at a time,
+    /// so those nodes can be locked in any order without deadlocking.
+    namespace: Mutex<()>,
+}
+
+impl MemFs {
+    /// Make a new, empty filesystem.
+    pub fn new() -> Self {
+        Self::with_volume_name("MemFs")
+    }
+
+    /// Make a new, empty filesystem with the given volume name.
+    pub fn with_volume_name(volume_name: impl Into<String>) -> Self {
+        Self {
+            root: Arc::new(Node::new(1, true, FileAttributes::DIRECTORY, Vec::new())),
+            volume_name: volume_name.into(),
+            next_index: AtomicU64::new(2),
+            next_handle_id: AtomicU64::new(1),
+            namespace: Mutex::new(()),
+        }
+    }
+
+    /// Lock the namespace, to add, remove or move directory entries.
+    fn namespace(&self) -> MutexGuard<'_, ()> {
+        self.namespace.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Look up a node from its path components.
+    fn lookup(&self, components: &[String]) -> Option<Arc<Node>> {
+        let mut node = Arc::clone(&self.root);
+        for component in components {
+            let next = Arc::clone(&node.read().children.get(&key(component))?.node);
+            node = next;
+        }
+        Some(node)
+    }
+}
+
+impl Default for MemFs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A file or directory.
+struct Node {
+    /// The unique file index.
+    index: u64,
+    is_directory: bool,
+    state: RwLock<NodeState>,
+    /// The byte range locks of each stream, keyed like [`MemFsContext::stream`].
+    locks: Mutex<BTreeMap<Option<String>, Arc<ByteRangeLockManager>>>,
+    /// The parent directory and entry key, or `None` for the root.
+    ///
+    /// This is shared by every handle, and only changed while holding the namespace lock.
+    /// It is kept after the node is deleted.
+    parent: Mutex<Option<(Weak<Node>, String)>>,
+}
+
+impl Node {
+    fn new(
+        index: u64,
+        is_directory: bool,
+        attributes: FileAttributes,
+        security_descriptor: Vec<u8>,
+    ) -> Self {
+        let now = SystemTime::now();
+        Self {
+            index,
+            is_directory,
+            state: RwLock::new(NodeState {
+                attributes,
+                creation_time: now,
+                last_access_time: now,
+                last_write_time: now,
+                security_descriptor,
+                delete_pending: false,
+                data: Vec::new(),
+                streams: BTreeMap::new(),
+                children: BTreeMap::new(),
+            }),
+            locks: Mutex::new(BTreeMap::new()),
+            parent: Mutex::new(None),
+        }
+    }
+
+    /// Get the parent directory and entry key, or `None` for the root.
+    fn parent(&self) -> Option<(Arc<Node>, String)> {
+        let parent = self.parent.lock().unwrap_or_else(|e| e.into_inner());
+        let (parent, key) = parent.as_ref()?;
+        Some((parent.upgrade()?, key.clone()))
+    }
+
+    /// Link this node to a new parent directory, while holding the namespace lock.
+    fn set_parent(&self, _namespace: &MutexGuard<'_, ()>, parent: &Arc<Node>, key: String) {
+        *self.parent.lock().unwrap_or_else(|e| e.into_inner()) =
+            Some((Arc::downgrade(parent), key));
+    }
+
+    /// Returns true if this is the root directory.
+    fn is_root(&self) -> bool {
+        self.parent
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_none()
+    }
+
+    /// Get the byte range locks of the main stream or a named stream.
+    fn locks(&self, stream: Option<&str>) -> Arc<ByteRangeLockManager> {
+        let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(locks.entry(stream.map(str::to_owned)).or_default())
+    }
+
+    /// Make a directory entry for this node.
+    fn find_data(&self, name: &str) -> FindData {
+        let state = self.read();
+
+        let mut find_data = FindData::new();
+        find_data
+            .set_file_name(name)
+            .set_file_attributes(state.attributes)
+            .set_creation_time(state.creation_time)
+            .set_last_access_time(state.last_access_time)
+            .set_last_write_time(state.last_write_time)
+            .set_size(state.data.len() as u64);
+        find_data
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, NodeState> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, NodeState> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The mutable state of a node.
+struct NodeState {
+    attributes: FileAttributes,
+    creation_time: SystemTime,
+    last_access_time: SystemTime,
+    last_write_time: SystemTime,
+    /// A self-relative security descriptor, or empty if none was assigned.
+    security_descriptor: Vec<u8>,
+    /// The node will be deleted when its last handle is closed.
+    delete_pending: bool,
+    /// The unnamed data stream.
+    data: Vec<u8>,
+    /// Named alternate data streams, keyed by upper-cased name.
+    streams: BTreeMap<String, Stream>,
+    /// Directory entries, keyed by upper-cased name.
+    children: BTreeMap<String, Entry>,
+}
+
+impl NodeState {
+    /// Get the data of the main stream or a named stream.
+    fn data(&self, stream: Option<&str>) -> Result<&Vec<u8>, NtStatus> {
+        match stream {
+            None => Ok(&self.data),
+            Some(stream) => self
+                .streams
+                .get(stream)
+                .map(|stream| &stream.data)
+                .ok_or(NtStatus::OBJECT_NAME_NOT_FOUND),
+        }
+    }
+
+    /// Get the mutable data of the main stream or a named stream.
+    fn data_mut(&mut self, stream: Option<&str>) -> Result<&mut Vec<u8>, NtStatus> {
+        match stream {
+            None => Ok(&mut self.data),
+            Some(stream) => self
+                .streams
+                .get_mut(stream)
+                .map(|stream| &mut stream.data)
+                .ok_or(NtStatus::OBJECT_NAME_NOT_FOUND),
+        }
+    }
+}
+
+/// A named alternate data stream.
+struct Stream {
+    /// The name, with its original case.
+    name: String,
+    data: Vec<u8>,
+}
+
+/// A directory entry.
+struct Entry {
+    /// The name, with its original case.
+    name: String,
+    node: Arc<Node>,
+}
+
+/// The per-handle context of a [`MemFs`].
+pub struct MemFsContext {
+    node: Arc<Node>,
+    /// The upper-cased stream name, or `None` for the main stream.
+    stream: Option<String>,
+    /// The byte range locks of the stream.
+    locks: Arc<ByteRangeLockManager>,
+    /// The owner id of byte range locks taken through this handle.
+    handle_id: u64,
+    /// Whether using this handle stopped updating the last access time.
+    keep_last_access_time: AtomicBool,
+    /// Whether using this handle stopped updating the last write time.
+    keep_last_write_time: AtomicBool,
+}
+
+impl MemFsContext {
+    /// Update the last access time after a read, unless this handle stopped updating it.
+    fn accessed(&self, state: &mut NodeState) {
+        if !self.keep_last_access_time.load(Ordering::Relaxed) {
+            state.last_access_time = SystemTime::now();
+        }
+    }
+
+    /// Update the last write time after a change to the data, unless this handle stopped updating it.
+    fn modified(&self, state: &mut NodeState) {
+        state.attributes |= FileAttributes::ARCHIVE;
+        if !self.keep_last_write_time.load(Ordering::Relaxed) {
+            state.last_write_time = SystemTime::now();
+        }
+    }
+
+    /// Check that the file or stream of this handle can be deleted.
+    fn check_delete(&self) -> Result<(), NtStatus> {
+        if self.stream.is_some() {
+            return Ok(());
+        }
+        if self.node.is_root() {
+            return Err(NtStatus::CANNOT_DELETE);
+        }
+
+        let state = self.node.read();
+        if state.delete_pending {
+            return Err(NtStatus::DELETE_PENDING);
+        }
+        if state.attributes.contains(FileAttributes::READONLY) {
+            return Err(NtStatus::CANNOT_DELETE);
+        }
+        if !state.children.is_empty() {
+            return Err(NtStatus::DIRECTORY_NOT_EMPTY);
+        }
+        Ok(())
+    }
+
+    /// Delete the file or stream of this handle, while holding the namespace lock.
+    fn delete(&self, _namespace: &MutexGuard<'_, ()>) {
+        match (&self.stream, self.node.parent()) {
+            (Some(stream), _) => {
+                self.node.write().streams.remove(stream);
+                // A new stream with the same name starts without locks.
+                self.node
+                    .locks
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .remove(&Some(stream.clone()));
+            }
+            (None, Some((parent, key))) => {
+                let mut parent_state = parent.write();
+                let is_same_node = parent_state
+                    .children
+                    .get(&key)
+                    .is_some_and(|entry| Arc::ptr_eq(&entry.node, &self.node));
+                let mut state = self.node.write();
+                if is_same_node && state.children.is_empty() {
+                    parent_state.children.remove(&key);
+                    parent_state.last_write_time = SystemTime::now();
+                    // Handles that are still open can no longer be used to create entries.
+                    state.delete_pending = true;
+                }
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+/// A parsed file name.
+struct FilePath {
+    components: Vec<String>,
+    stream: Option<String>,
+}
+
+impl FilePath {
+    /// Parse a file name like `\dir\file.txt:stream:$DATA`.
+    fn parse(file_name: &[u16]) -> Result<Self, NtStatus> {
+        let path = DokanPath::new(file_name);
+        let to_name = |name: &[u16]| -> Result<String, NtStatus> {
+            let name = String::from_utf16(name).map_err(|_| NtStatus::OBJECT_NAME_INVALID)?;
+            validate_component(&name)?;
+            Ok(name)
+        };
+
+        let components = path
+            .components()
+            .map(to_name)
+            .collect::<Result<Vec<_>, NtStatus>>()?;
+
+        if let Some(stream_type) = path.stream_type() {
+            let stream_type = String::from_utf16_lossy(stream_type);
+            if !stream_type.eq_ignore_ascii_case("$DATA") {
+                return Err(NtStatus::OBJECT_NAME_INVALID);
+            }
+        }
+        let stream = path.stream_name().map(to_name).transpose()?;
+
+        Ok(Self { components, stream })
+    }
+}
+
+/// Check that a name can be used as a file or stream name.
+fn validate_component(component: &str) -> Result<(), NtStatus> {
+    if component.chars().count() > MAX_COMPONENT_LEN {
+        return Err(NtStatus::NAME_TOO_LONG);
+    }
+    let is_invalid =
+        |c: char| c.is_control() || matches!(c, '<' | '>' | ':' | '"' | '/' | '|' | '?' | '*');
+    if component.chars().any(is_invalid) {
+        return Err(NtStatus::OBJECT_NAME_INVALID);
+    }
+    Ok(())
+}
+
+/// Get the key of a name for case-insensitive lookups.
+fn key(name: &str) -> String {
+    name.to_uppercase()
+}
+
+/// Normalize the attributes of a file.
+///
+/// `NORMAL` is only valid on its own, and the `DIRECTORY` attribute is managed by the filesystem.
+fn normalize_attributes(attributes: FileAttributes, is_directory: bool) -> FileAttributes {
+    let mut attributes = attributes - FileAttributes::NORMAL - FileAttributes::DIRECTORY;
+    if is_directory {
+        attributes |= FileAttributes::DIRECTORY;
+    } else {
+        attributes |= FileAttributes::ARCHIVE;
+    }
+    attributes
+}
+
+/// Apply a change to a time that is updated as a handle is used.
+fn apply_time_change(change: FileTimeChange, time: &mut SystemTime, keep_time: &AtomicBool) {
+    match change {
+        FileTimeChange::Unchanged => {}
+        FileTimeChange::Set(new_time) => *time = new_time,
+        FileTimeChange::StopUpdating => keep_time.store(true, Ordering::Relaxed),
+        FileTimeChange::ResumeUpdating => keep_time.store(false, Ordering::Relaxed),
+    }
+}
+
+/// List the entries of an open directory, optionally only those that match a search pattern.
+fn list_dir(
+    context: &MemFsContext,
+    search_pattern: Option<&[u16]>,
+    mut fill_find_data: FillFindData<'_>,
+) -> Result<(), NtStatus> {
+    if !context.node.is_directory || context.stream.is_some() {
+        return Err(NtStatus::NOT_A_DIRECTORY);
+    }
+
+    let mut entries = Vec::new();
+    if let Some((parent, _key)) = context.node.parent() {
+        entries.push(context.node.find_data("."));
+        entries.push(parent.find_data(".."));
+    }
+
+    // Only the namespace lock holder may lock a child while its directory is locked.
+    let children: Vec<(String, Arc<Node>)> = context
+        .node
+        .read()
+        .children
+        .values()
+        .map(|entry| (entry.name.clone(), Arc::clone(&entry.node)))
+        .collect();
+    for (name, node) in children {
+        entries.push(node.find_data(&name));
+    }
+
+    let is_match = |find_data: &FindData| {
+        search_pattern.is_none_or(|search_pattern| {
+            is_name_in_expression(search_pattern, find_data.file_name(), true)
+        })
+    };
+    for find_data in entries.iter().filter(|find_data| is_match(find_data)) {
+        fill_find_data.fill(find_data)?;
+    }
+
+    Ok(())
+}
+
+impl FileSystem for MemFs {
+    type Context = MemFsContext;
+
+    fn create_file(
+        &self,
+        file_name: &[u16],
+        create_file_info: &CreateFileInfo<'_>,
+        is_dir: &mut bool,
+    ) -> Result<CreateFileResult<Self::Context>, NtStatus> {
+        let path = FilePath::parse(file_name)?;
+        let create_disposition = create_file_info.create_disposition();
+        let create_options = create_file_info.create_options();
+        let delete_on_close = create_options.contains(CreateOptions::DELETE_ON_CLOSE);
+        if create_options
+            .contains(CreateOptions::DIRECTORY_FILE | CreateOptions::NON_DIRECTORY_FILE)
+        {
+            return Err(NtStatus::INVALID_PARAMETER);
+        }
+
+        let (node, created) = match path.components.split_last() {
+            None => (Arc::clone(&self.root), false),
+            Some((name, parent_components)) => {
+                let parent = self
+                    .lookup(parent_components)
+                    .filter(|parent| parent.is_directory)
+                    .ok_or(NtStatus::OBJECT_PATH_NOT_FOUND)?;
+                let key = key(name);
+
+                let namespace = self.namespace();
+                let mut parent_state = parent.write();
+                let (node, created) = match parent_state.children.get(&key) {
+                    Some(entry) => (Arc::clone(&entry.node), false),
+                    None => {
+                        if !create_disposition.may_create() {
+                            return Err(NtStatus::OBJECT_NAME_NOT_FOUND);
+                        }
+                        if parent_state.delete_pending {
+                            return Err(NtStatus::DELETE_PENDING);
+                        }
+
+                        let is_directory = create_options.contains(CreateOptions::DIRECTORY_FILE)
+                            && path.stream.is_none();
+                        let attributes =
+                            normalize_attributes(create_file_info.file_attributes(), is_directory);
+                        // The entry is visible once inserted, so the checks below must not fail for it.
+                        if delete_on_close && attributes.contains(FileAttributes::READONLY) {
+                            return Err(NtStatus::CANNOT_DELETE);
+                        }
+                        let security_descriptor = create_file_info
+                            .security_context()
+                            .and_then(|security_context| security_context.security_descriptor())
+                            .map(|security_descriptor| security_descriptor.to_vec())
+                            // Without an explicit descriptor, inherit the one of the parent.
+                            .unwrap_or_else(|| parent_state.security_descriptor.clone());
+                        let node = Arc::new(Node::new(
+                            self.next_index.fetch_add(1, Ordering::Relaxed),
+                            is_directory,
+                            attributes,
+                            security_descriptor,
+                        ));
+                        node.set_parent(&namespace, &parent, key.clone());
+
+                        parent_state.children.insert(
+                            key.clone(),
+                            Entry {
+                                name: name.clone(),
+                                node: Arc::clone(&node),
+                            },
+                        );
+                        parent_state.last_write_time = SystemTime::now();
+
+                        (node, true)
+                    }
+                };
+                drop(parent_state);
+                drop(namespace);
+
+                (node, created)
+            }
+        };
+
+        let mut state = node.write();
+        if state.delete_pending {
+            return Err(NtStatus::DELETE_PENDING);
+        }
+
+        let stream = path.stream.as_deref().map(key);
+        let exists = match &stream {
+            Some(stream) => state.streams.contains_key(stream),
+            None => !created,
+        };
+
+        if exists && create_disposition == CreateDisposition::Create {
+            return Err(NtStatus::OBJECT_NAME_COLLISION);
+        }
+        if !exists && !create_disposition.may_create() {
+            return Err(NtStatus::OBJECT_NAME_NOT_FOUND);
+        }
+        if stream.is_none() {
+            if node.is_directory && create_options.contains(CreateOptions::NON_DIRECTORY_FILE) {
+                return Err(NtStatus::FILE_IS_A_DIRECTORY);
+            }
+            if !node.is_directory && create_options.contains(CreateOptions::DIRECTORY_FILE) {
+                return Err(NtStatus::NOT_A_DIRECTORY);
+            }
+        }
+
+        if state.attributes.contains(FileAttributes::READONLY) {
+            if delete_on_close {
+                return Err(NtStatus::CANNOT_DELETE);
+            }
+            if exists
+                && (create_file_info.desired_access().intersects(WRITE_ACCESS)
+                    || create_disposition.truncates())
+            {
+                return Err(NtStatus::ACCESS_DENIED);
+            }
+        }
+        if delete_on_close && node.is_root() && stream.is_none() {
+            return Err(NtStatus::CANNOT_DELETE);
+        }
+
+        if let (Some(stream), Some(name)) = (&stream, &path.stream) {
+            if !exists {
+                state.streams.insert(
+                    stream.clone(),
+                    Stream {
+                        name: name.clone(),
+                        data: Vec::new(),
+                    },
+                );
+            }
+        }
+
+        if exists && create_disposition.truncates() {
+            if node.is_directory && stream.is_none() {
+                return Err(NtStatus::INVALID_PARAMETER);
+            }
+
+            let now = SystemTime::now();
+            state.data_mut(stream.as_deref())?.clear();
+            state.attributes |= normalize_attributes(create_file_info.file_attributes(), false);
+            state.last_write_time = now;
+            // A superseded file is replaced by a new one.
+            if create_disposition == CreateDisposition::Supersede && stream.is_none() {
+                state.creation_time = now;
+            }
+        }
+        drop(state);
+
+        *is_dir = node.is_directory && stream.is_none();
+
+        let context = MemFsContext {
+            locks: node.locks(stream.as_deref()),
+            node,
+            stream,
+            handle_id: self.next_handle_id.fetch_add(1, Ordering::Relaxed),
+            keep_last_access_time: AtomicBool::new(false),
+            keep_last_write_time: AtomicBool::new(false),
+        };
+        Ok(CreateFileResult {
+            context,
+            opened_existing: exists,
+        })
+    }
+
+    fn cleanup(&self, _file_name: &[u16], context: &Self::Context, file_info: &FileInfo) {
+        context.locks.unlock_all(context.handle_id);
+
+        if file_info.delete_on_close() {
+            context.delete(&self.namespace());
+        }
+    }
+
+    fn read_file(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        buffer: &mut [u8],
+        offset: u64,
+    ) -> Result<usize, NtStatus> {
+        if context.node.is_directory && context.stream.is_none() {
+            return Err(NtStatus::INVALID_DEVICE_REQUEST);
+        }
+
+        let length = u64::try_from(buffer.len()).unwrap();
+        context
+            .locks
+            .check_read(context.handle_id, offset, length)?;
+
+        let mut state = context.node.write();
+        let data = state.data(context.stream.as_deref())?;
+
+        let len = match usize::try_from(offset)
+            .ok()
+            .and_then(|offset| data.get(offset..))
+        {
+            Some(data) => {
+                let len = data.len().min(buffer.len());
+                buffer[..len].copy_from_slice(&data[..len]);
+                len
+            }
+            None => 0,
+        };
+        context.accessed(&mut state);
+
+        Ok(len)
+    }
+
+    fn write_file(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        data: &[u8],
+        offset: u64,
+        write_to_eof: bool,
+    ) -> Result<usize, NtStatus> {
+        if context.node.is_directory && context.stream.is_none() {
+            return Err(NtStatus::INVALID_DEVICE_REQUEST);
+        }
+
+        let mut state = context.node.write();
+        let file_data = state.data_mut(context.stream.as_deref())?;
+
+        let offset = if write_to_eof {
+            file_data.len()
+        } else {
+            usize::try_from(offset).map_err(|_| NtStatus::DISK_FULL)?
+        };
+        context.locks.check_write(
+            context.handle_id,
+            u64::try_from(offset).unwrap(),
+            u64::try_from(data.len()).unwrap(),
+        )?;
+        let end = offset.checked_add(data.len()).ok_or(NtStatus::DISK_FULL)?;
+        if let Some(additional) = end.checked_sub(file_data.len()) {
+            file_data
+                .try_reserve_exact(additional)
+                .map_err(|_| NtStatus::DISK_FULL)?;
+            file_data.resize(end, 0);
+        }
+        file_data[offset..end].copy_from_slice(data);
+
+        context.modified(&mut state);
+
+        Ok(data.len())
+    }
+
+    fn set_end_of_file(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        length: u64,
+    ) -> Result<(), NtStatus> {
+        if context.node.is_directory && context.stream.is_none() {
+            return Err(NtStatus::INVALID_DEVICE_REQUEST);
+        }
+        let length = usize::try_from(length).map_err(|_| NtStatus::DISK_FULL)?;
+
+        let mut state = context.node.write();
+        let file_data = state.data_mut(context.stream.as_deref())?;
+        if let Some(additional) = length.checked_sub(file_data.len()) {
+            file_data
+                .try_reserve_exact(additional)
+                .map_err(|_| NtStatus::DISK_FULL)?;
+        }
+        file_data.resize(length, 0);
+
+        context.modified(&mut state);
+
+        Ok(())
+    }
+
+    fn set_allocation_size(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        length: u64,
+    ) -> Result<(), NtStatus> {
+        if context.node.is_directory && context.stream.is_none() {
+            return Err(NtStatus::INVALID_DEVICE_REQUEST);
+        }
+        let length = usize::try_from(length).map_err(|_| NtStatus::DISK_FULL)?;
+
+        let mut state = context.node.write();
+        let file_data = state.data_mut(context.stream.as_deref())?;
+        match length.checked_sub(file_data.len()) {
+            // Reserve the space, so later writes up to it do not run out of memory.
+            Some(additional) => file_data
+                .try_reserve_exact(additional)
+                .map_err(|_| NtStatus::DISK_FULL)?,
+            None => {
+                file_data.truncate(length);
+                context.modified(&mut state);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn lock_file(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        offset: u64,
+        length: u64,
+    ) -> Result<(), NtStatus> {
+        // Dokan only requests exclusive locks.
+        context
+            .locks
+            .lock(context.handle_id, offset, length, LockKind::Exclusive)
+    }
+
+    fn unlock_file(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        offset: u64,
+        length: u64,
+    ) -> Result<(), NtStatus> {
+        context.locks.unlock(context.handle_id, offset, length)
+    }
+
+    fn get_file_information(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+    ) -> Result<FileInformation, NtStatus> {
+        let state = context.node.read();
+
+        Ok(FileInformation {
+            attributes: state.attributes,
+            creation_time: state.creation_time,
+            last_access_time: state.last_access_time,
+            last_write_time: state.last_write_time,
+            volume_serial_number: VOLUME_SERIAL_NUMBER,
+            size: state.data(context.stream.as_deref())?.len() as u64,
+            number_of_links: 1,
+            file_index: context.node.index,
+        })
+    }
+
+    fn find_files(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        fill_find_data: FillFindData<'_>,
+    ) -> Result<(), NtStatus> {
+        list_dir(context, None, fill_find_data)
+    }
+
+    fn find_files_with_pattern(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        search_pattern: &[u16],
+        fill_find_data: FillFindData<'_>,
+    ) -> Result<(), NtStatus> {
+        list_dir(context, Some(search_pattern), fill_find_data)
+    }
+
+    fn find_streams(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        mut fill_find_stream_data: FillFindStreamData<'_>,
+    ) -> Result<(), NtStatus> {
+        let state = context.node.read();
+        let mut find_stream_data = FindStreamData::new();
+
+        // Directories have no unnamed data stream.
+        if !context.node.is_directory {
+            find_stream_data
+                .set_stream_name("::$DATA")
+                .set_size(state.data.len() as u64);
+            fill_find_stream_data.fill(&find_stream_data)?;
+        }
+        for stream in state.streams.values() {
+            find_stream_data
+                .set_stream_name(format!(":{}:$DATA", stream.name).as_str())
+                .set_size(stream.data.len() as u64);
+            fill_find_stream_data.fill(&find_stream_data)?;
+        }
+
+        Ok(())
+    }
+
+    fn set_file_attributes(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        file_attributes: FileAttributes,
+    ) -> Result<(), NtStatus> {
+        if file_attributes.is_empty() {
+            return Ok(());
+        }
+
+        let mut attributes = file_attributes - FileAttributes::NORMAL - FileAttributes::DIRECTORY;
+        if context.node.is_directory {
+            attributes |= FileAttributes::DIRECTORY;
+        }
+        context.node.write().attributes = attributes;
+
+        Ok(())
+    }
+
+    fn set_file_time(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        creation_time: FileTimeChange,
+        last_access_time: FileTimeChange,
+        last_write_time: FileTimeChange,
+    ) -> Result<(), NtStatus> {
+        let mut state = context.node.write();
+        // The creation time is never updated as a handle is used, so only setting it has an effect.
+        if let FileTimeChange::Set(creation_time) = creation_time {
+            state.creation_time = creation_time;
+        }
+        apply_time_change(
+            last_access_time,
+            &mut state.last_access_time,
+            &context.keep_last_access_time,
+        );
+        apply_time_change(
+            last_write_time,
+            &mut state.last_write_time,
+            &context.keep_last_write_time,
+        );
+
+        Ok(())
+    }
+
+    fn delete_file(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        file_info: &FileInfo,
+    ) -> Result<(), NtStatus> {
+        if !file_info.delete_on_close() {
+            return Ok(());
+        }
+        context.check_delete()
+    }
+
+    fn delete_directory(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        file_info: &FileInfo,
+    ) -> Result<(), NtStatus> {
+        if !file_info.delete_on_close() {
+            return Ok(());
+        }
+        context.check_delete()
+    }
+
+    fn move_file(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        new_file_name: &[u16],
+        replace_if_existing: bool,
+    ) -> Result<(), NtStatus> {
+        let new_path = FilePath::parse(new_file_name)?;
+        if context.stream.is_some() || new_path.stream.is_some() {
+            return Err(NtStatus::NOT_SUPPORTED);
+        }
+        let (new_name, new_parent_components) = new_path
+            .components
+            .split_last()
+            .ok_or(NtStatus::ACCESS_DENIED)?;
+
+        let namespace = self.namespace();
+        let (old_parent, old_key) = context.node.parent().ok_or(NtStatus::ACCESS_DENIED)?;
+
+        // A directory cannot be moved into itself.
+        let mut new_parent = Arc::clone(&self.root);
+        for component in new_parent_components {
+            let next = Arc::clone(
+                &new_parent
+                    .read()
+                    .children
+                    .get(&key(component))
+                    .ok_or(NtStatus::OBJECT_PATH_NOT_FOUND)?
+                    .node,
+            );
+            if Arc::ptr_eq(&next, &context.node) {
+                return Err(NtStatus::INVALID_PARAMETER);
+            }
+            new_parent = next;
+        }
+        if !new_parent.is_directory {
+            return Err(NtStatus::OBJECT_PATH_NOT_FOUND);
+        }
+        let new_key = key(new_name);
+
+        // The namespace lock is held, so both directories can be locked in any order.
+        let mut old_state = old_parent.write();
+        let mut new_state = if Arc::ptr_eq(&old_parent, &new_parent) {
+            None
+        } else {
+            Some(new_parent.write())
+        };
+
+        let is_same_node = old_state
+            .children
+            .get(&old_key)
+            .is_some_and(|entry| Arc::ptr_eq(&entry.node, &context.node));
+        if !is_same_node {
+            return Err(NtStatus::OBJECT_NAME_NOT_FOUND);
+        }
+
+        let target = new_state.as_deref_mut().unwrap_or(&mut old_state);
+        if target.delete_pending {
+            return Err(NtStatus::DELETE_PENDING);
+        }
+        if let Some(existing) = target.children.get(&new_key) {
+            // Renaming a file to a different case of its own name is allowed.
+            if !Arc::ptr_eq(&existing.node, &context.node) {
+                if !replace_if_existing {
+                    return Err(NtStatus::OBJECT_NAME_COLLISION);
+                }
+
+                let mut existing_state = existing.node.write();
+                if existing.node.is_directory
+                    || existing_state.attributes.contains(FileAttributes::READONLY)
+                {
+                    return Err(NtStatus::ACCESS_DENIED);
+                }
+                existing_state.delete_pending = true;
+                drop(existing_state);
+
+                target.children.remove(&new_key);
+            }
+        }
+
+        let now = SystemTime::now();
+        let entry = old_state.children.remove(&old_key).unwrap();
+        old_state.last_write_time = now;
+
+        let target = new_state.as_deref_mut().unwrap_or(&mut old_state);
+        target.children.insert(
+            new_key.clone(),
+            Entry {
+                name: new_name.clone(),
+                node: entry.node,
+            },
+        );
+        target.last_write_time = now;
+        drop(old_state);
+        drop(new_state);
+
+        context.node.set_parent(&namespace, &new_parent, new_key);
+        Ok(())
+    }
+
+    fn get_file_security(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        security_information: SecurityInformation,
+    ) -> Result<Vec<u8>, NtStatus> {
+        let state = context.node.read();
+        if state.security_descriptor.is_empty() {
+            // Let Dokan build a default descriptor.
+            return Err(NtStatus::NOT_IMPLEMENTED);
+        }
+        match SecurityDescriptor::from_bytes(&state.security_descriptor) {
+            Ok(descriptor) => Ok(descriptor.filtered(security_information).to_bytes()),
+            // Descriptors with entries we cannot parse are returned whole.
+            Err(_) => Ok(state.security_descriptor.clone()),
+        }
+    }
+
+    fn set_file_security(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        security_information: SecurityInformation,
+        security_descriptor: &[u8],
+    ) -> Result<(), NtStatus> {
+        let mut state = context.node.write();
+        let current = if state.security_descriptor.is_empty() {
+            Ok(SecurityDescriptor::new())
+        } else {
+            SecurityDescriptor::from_bytes(&state.security_descriptor)
+        };
+        match (current, SecurityDescriptor::from_bytes(security_descriptor)) {
+            (Ok(mut current), Ok(new)) => {
+                current.merge(&new, security_information);
+                state.security_descriptor = current.to_bytes();
+            }
+            // Fall back to storing the descriptor as a whole.
+            _ => state.security_descriptor = security_descriptor.to_vec(),
+        }
+        Ok(())
+    }
+
+    fn get_volume_information(
+        &self,
+        mut volume_name: WriteWideCStringCell<'_>,
+        volume_serial_number: &mut u32,
+        maximum_component_length: &mut u32,
+        file_system_flags: &mut FileSystemFlags,
+        mut file_system_name: WriteWideCStringCell<'_>,
+    ) -> Result<(), NtStatus> {
+        volume_name.write(self.volume_name.as_str());
+        *volume_serial_number = VOLUME_SERIAL_NUMBER;
+        *maximum_component_length = MAX_COMPONENT_LEN as u32;
+        *file_system_flags = FileSystemFlags::CASE_PRESERVED_NAMES
+            | FileSystemFlags::UNICODE_ON_DISK
+            | FileSystemFlags::PERSISTENT_ACLS
+            | FileSystemFlags::NAMED_STREAMS;
+        file_system_name.write("NTFS");
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::CreateRequest;
+    use crate::testing::Handle;
+    use crate::testing::Harness;
+    use std::time::Duration;
+
+    fn file_names(entries: &[FindData]) -> Vec<String> {
+        entries
+            .iter()
+            .map(|entry| String::from_utf16_lossy(entry.file_name()))
+            .collect()
+    }
+
+    #[test]
+    fn write_read() {
+        let harness = Harness::new(MemFs::new());
+
+        let mut file = harness.create("\\file.txt").unwrap();
+        assert!(!file.is_directory());
+        assert_eq!(file.write_at(b"Hello", 0).unwrap(), 5);
+        assert_eq!(file.append(b" World").unwrap(), 6);
+        assert_eq!(file.write_at(b"!", 12).unwrap(), 1);
+        drop(file);
+
+        assert_eq!(harness.read("\\FILE.TXT").unwrap(), b"Hello World\0!");
+        assert_eq!(
+            harness.create("\\file.txt").err(),
+            Some(NtStatus::OBJECT_NAME_COLLISION)
+        );
+
+        let request = CreateRequest {
+            create_disposition: CreateDisposition::OverwriteIf,
+            ..CreateRequest::new()
+        };
+        drop(harness.create_file("\\file.txt", &request).unwrap());
+        assert_eq!(harness.read("\\file.txt").unwrap(), b"");
+    }
+
+    #[test]
+    fn open_or_create() {
+        let harness = Harness::new(MemFs::new());
+
+        for create_disposition in [
+            CreateDisposition::OpenIf,
+            CreateDisposition::OverwriteIf,
+            CreateDisposition::Supersede,
+        ] {
+            let request = CreateRequest {
+                create_disposition,
+                ..CreateRequest::new()
+            };
+
+            let file = harness.create_file("\\file.txt", &request).unwrap();
+            assert!(!file.opened_existing(), "{create_disposition:?}");
+            drop(file);
+
+            // Opening the existing file is reported as a collision, but still opens it.
+            let file = harness.create_file("\\file.txt", &request).unwrap();
+            assert!(file.opened_existing(), "{create_disposition:?}");
+            drop(file);
+
+            harness.delete("\\file.txt").unwrap();
+        }
+
+        assert!(harness.open("\\").unwrap().opened_existing());
+    }
+
+    #[test]
+    fn file_information() {
+        let harness = Harness::new(MemFs::new());
+
+        let mut file = harness.create("\\file.txt").unwrap();
+        file.write_at(b"data", 0).unwrap();
+        let info = file.file_information().unwrap();
+        assert_eq!(info.size, 4);
+        assert_eq!(info.attributes, FileAttributes::ARCHIVE);
+        assert_eq!(info.volume_serial_number, VOLUME_SERIAL_NUMBER);
+        assert!(info.creation_time <= info.last_write_time);
+
+        let mut dir = harness.create_dir("\\dir").unwrap();
+        let dir_info = dir.file_information().unwrap();
+        assert_eq!(dir_info.attributes, FileAttributes::DIRECTORY);
+        assert_ne!(dir_info.file_index, info.file_index);
+    }
+
+    #[test]
+    fn directories() {
+        let harness = Harness::new(MemFs::new());
+
+        let dir = harness.create_dir("\\Dir").unwrap();
+        assert!(dir.is_directory());
+        drop(harness.create("\\Dir\\b.txt").unwrap());
+        drop(harness.create("\\Dir\\a.txt").unwrap());
+
+        let entries = harness.list_dir("\\").unwrap();
+        assert_eq!(file_names(&entries), ["Dir"]);
+        assert_eq!(entries[0].file_attributes(), FileAttributes::DIRECTORY);
+        assert_eq!(
+            file_names(&harness.list_dir("\\dir").unwrap()),
+            [".", "..", "a.txt", "b.txt"]
+        );
+        let mut dir = harness.open("\\Dir").unwrap();
+        assert_eq!(
+            file_names(&dir.list_dir_with_capacity(3).unwrap()),
+            [".", "..", "a.txt"]
+        );
+        assert_eq!(
+            file_names(&dir.list_dir_with_pattern("B.*").unwrap()),
+            ["b.txt"]
+        );
+        assert_eq!(
+            file_names(&dir.list_dir_with_pattern("<.TXT").unwrap()),
+            ["a.txt", "b.txt"]
+        );
+
+        assert_eq!(
+            harness.create("\\Missing\\a.txt").err(),
+            Some(NtStatus::OBJECT_PATH_NOT_FOUND)
+        );
+        assert_eq!(
+            harness.list_dir("\\Dir\\a.txt").err(),
+            Some(NtStatus::NOT_A_DIRECTORY)
+        );
+        assert_eq!(
+            harness.read("\\Dir").err(),
+            Some(NtStatus::INVALID_DEVICE_REQUEST)
+        );
+    }
+
+    #[test]
+    fn streams() {
+        let harness = Harness::new(MemFs::new());
+
+        let mut file = harness.create("\\file.txt").unwrap();
+        file.write_at(b"main", 0).unwrap();
+        drop(file);
+
+        let mut stream = harness.create("\\file.txt:extra").unwrap();
+        stream.write_at(b"stream", 0).unwrap();
+        drop(stream);
+
+        assert_eq!(harness.read("\\file.txt").unwrap(), b"main");
+        assert_eq!(harness.read("\\file.txt::$DATA").unwrap(), b"main");
+        assert_eq!(harness.read("\\file.txt:EXTRA:$DATA").unwrap(), b"stream");
+        assert_eq!(
+            harness.read("\\file.txt:missing").err(),
+            Some(NtStatus::OBJECT_NAME_NOT_FOUND)
+        );
+
+        // Streams are listed with the case they were created with.
+        let streams = harness.list_streams("\\file.txt").unwrap();
+        let streams: Vec<(String, u64)> = streams
+            .iter()
+            .map(|stream| {
+                (
+                    String::from_utf16_lossy(stream.stream_name()),
+                    stream.size(),
+                )
+            })
+            .collect();
+        assert_eq!(streams, [("::$DATA".into(), 4), (":extra:$DATA".into(), 6)]);
+        assert!(harness.list_streams("\\").unwrap().is_empty());
+    }
+
+    #[test]
+    fn failed_create() {
+        let harness = Harness::new(MemFs::new());
+
+        // A read-only file cannot be deleted on close, so it is not created either.
+        let request = CreateRequest {
+            file_attributes: FileAttributes::READONLY,
+            create_disposition: CreateDisposition::Create,
+            create_options: CreateOptions::DELETE_ON_CLOSE,
+            ..CreateRequest::new()
+        };
+        assert_eq!(
+            harness.create_file("\\a.txt", &request).err(),
+            Some(NtStatus::CANNOT_DELETE)
+        );
+        assert_eq!(
+            harness.create_file("\\a.txt:stream", &request).err(),
+            Some(NtStatus::CANNOT_DELETE)
+        );
+
+        let request = CreateRequest {
+            create_disposition: CreateDisposition::Create,
+            create_options: CreateOptions::DIRECTORY_FILE | CreateOptions::NON_DIRECTORY_FILE,
+            ..CreateRequest::new()
+        };
+        assert_eq!(
+            harness.create_file("\\b", &request).err(),
+            Some(NtStatus::INVALID_PARAMETER)
+        );
+
+        assert!(harness.list_dir("\\").unwrap().is_empty());
+    }
+
+    #[test]
+    fn delete_on_close() {
+        let harness = Harness::new(MemFs::new());
+
+        let request = CreateRequest {
+            create_disposition: CreateDisposition::Create,
+            create_options: CreateOptions::DELETE_ON_CLOSE,
+            ..CreateRequest::new()
+        };
+        let file = harness.create_file("\\temp.txt", &request).unwrap();
+        assert_eq!(file_names(&harness.list_dir("\\").unwrap()), ["temp.txt"]);
+        drop(file);
+
+        assert!(harness.list_dir("\\").unwrap().is_empty());
+
+        // A directory is unlinked at cleanup, even while other handles to it are open.
+        let request = CreateRequest {
+            create_disposition: CreateDisposition::Create,
+            create_options: CreateOptions::DIRECTORY_FILE | CreateOptions::DELETE_ON_CLOSE,
+            ..CreateRequest::new()
+        };
+        let dir = harness.create_file("\\Dir", &request).unwrap();
+        let mut other = harness.open("\\Dir").unwrap();
+        drop(dir);
+        assert!(harness.list_dir("\\").unwrap().is_empty());
+        assert_eq!(other.list_dir().unwrap().len(), 2);
+        assert_eq!(
+            harness.create("\\Dir\\a.txt").err(),
+            Some(NtStatus::OBJECT_PATH_NOT_FOUND)
+        );
+    }
+
+    #[test]
+    fn set_size() {
+        let harness = Harness::new(MemFs::new());
+
+        let mut file = harness.create("\\a.txt").unwrap();
+        file.write_at(b"Hello World!", 0).unwrap();
+        file.set_end_of_file(5).unwrap();
+        assert_eq!(file.read_to_end().unwrap(), b"Hello");
+        file.set_end_of_file(7).unwrap();
+        assert_eq!(file.read_to_end().unwrap(), b"Hello\0\0");
+
+        // Growing the allocation keeps the size, but shrinking it below the size truncates.
+        file.set_allocation_size(4096).unwrap();
+        assert_eq!(file.file_information().unwrap().size, 7);
+        file.set_allocation_size(2).unwrap();
+        assert_eq!(file.read_to_end().unwrap(), b"He");
+
+        // Writes far beyond the end that cannot be allocated fail, without changing the file.
+        assert_eq!(
+            file.write_at(b"data", 1 << 62).err(),
+            Some(NtStatus::DISK_FULL)
+        );
+        assert_eq!(file.read_to_end().unwrap(), b"He");
+
+        let mut dir = harness.create_dir("\\Dir").unwrap();
+        assert_eq!(
+            dir.set_end_of_file(0).err(),
+            Some(NtStatus::INVALID_DEVICE_REQUEST)
+        );
+    }
+
+    #[test]
+    fn lock() {
+        let harness = Harness::new(MemFs::new());
+
+        let mut file = harness.create("\\a.txt").unwrap();
+        file.write_at(b"Hello World!", 0).unwrap();
+        file.lock(0, 5).unwrap();
+
+        let mut other = harness.open("\\a.txt").unwrap();
+        assert_eq!(other.lock(4, 2).err(), Some(NtStatus::LOCK_NOT_GRANTED));
+        let mut buffer = [0; 5];
+        assert_eq!(
+            other.read_at(&mut buffer, 2).err(),
+            Some(NtStatus::FILE_LOCK_CONFLICT)
+        );
+        assert_eq!(
+            other.write_at(b"J", 0).err(),
+            Some(NtStatus::FILE_LOCK_CONFLICT)
+        );
+        assert_eq!(other.read_at(&mut buffer, 5).unwrap(), 5);
+        assert_eq!(other.unlock(0, 5).err(), Some(NtStatus::RANGE_NOT_LOCKED));
+
+        // Each stream has its own locks.
+        let mut stream = harness.create("\\a.txt:s").unwrap();
+        stream.write_at(b"Hello", 0).unwrap();
+        stream.lock(0, 5).unwrap();
+        drop(stream);
+
+        // The owner can still use the range, and its locks are released on cleanup.
+        file.write_at(b"J", 0).unwrap();
+        drop(file);
+        other.lock(0, 5).unwrap();
+        other.unlock(0, 5).unwrap();
+        assert_eq!(other.read_to_end().unwrap(), b"Jello World!");
+    }
+
+    #[test]
+    fn security() {
+        let harness = Harness::new(MemFs::new());
+        let owner_and_dacl = SecurityInformation::OWNER | SecurityInformation::DACL;
+        let descriptor: SecurityDescriptor = "O:WDD:(A;;FA;;;WD)".parse().unwrap();
+
+        let mut root = harness.open("\\").unwrap();
+        assert_eq!(
+            root.file_security(SecurityInformation::OWNER).err(),
+            Some(NtStatus::NOT_IMPLEMENTED)
+        );
+        root.set_file_security(owner_and_dacl, &descriptor.to_bytes())
+            .unwrap();
+        let read = |handle: &mut Handle<'_, MemFs>, info| {
+            SecurityDescriptor::from_bytes(&handle.file_security(info).unwrap())
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(read(&mut root, owner_and_dacl), "O:WDD:(A;;FA;;;WD)");
+        assert_eq!(read(&mut root, SecurityInformation::OWNER), "O:WD");
+
+        // Only the requested parts are replaced.
+        let update: SecurityDescriptor = "O:SYD:(A;;FR;;;WD)".parse().unwrap();
+        root.set_file_security(SecurityInformation::DACL, &update.to_bytes())
+            .unwrap();
+        assert_eq!(read(&mut root, owner_and_dacl), "O:WDD:(A;;FR;;;WD)");
+
+        // New files inherit the descriptor of their parent.
+        let mut file = harness.create("\\a.txt").unwrap();
+        assert_eq!(read(&mut file, owner_and_dacl), "O:WDD:(A;;FR;;;WD)");
+    }
+
+    #[test]
+    fn set_attributes_and_times() {
+        let harness = Harness::new(MemFs::new());
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+        let mut file = harness.create("\\a.txt").unwrap();
+        file.set_file_attributes(FileAttributes::READONLY | FileAttributes::HIDDEN)
+            .unwrap();
+        file.set_file_attributes(FileAttributes::empty()).unwrap();
+        file.set_file_time(
+            FileTimeChange::Unchanged,
+            FileTimeChange::Unchanged,
+            FileTimeChange::Set(time),
+        )
+        .unwrap();
+
+        let information = file.file_information().unwrap();
+        assert_eq!(
+            information.attributes,
+            FileAttributes::READONLY | FileAttributes::HIDDEN
+        );
+        assert_eq!(information.last_write_time, time);
+        assert_ne!(information.creation_time, time);
+        drop(file);
+
+        assert_eq!(
+            harness.delete("\\a.txt").err(),
+            Some(NtStatus::CANNOT_DELETE)
+        );
+
+        let mut dir = harness.create_dir("\\Dir").unwrap();
+        dir.set_file_attributes(FileAttributes::NORMAL).unwrap();
+        assert_eq!(
+            dir.file_information().unwrap().attributes,
+            FileAttributes::DIRECTORY
+        );
+    }
+
+    #[test]
+    fn stop_updating_times() {
+        let harness = Harness::new(MemFs::new());
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+        let mut file = harness.create("\\a.txt").unwrap();
+        file.set_file_time(
+            FileTimeChange::Unchanged,
+            FileTimeChange::Set(time),
+            FileTimeChange::Set(time),
+        )
+        .unwrap();
+        file.set_file_time(
+            FileTimeChange::StopUpdating,
+            FileTimeChange::StopUpdating,
+            FileTimeChange::StopUpdating,
+        )
+        .unwrap();
+
+        // Using the handle leaves the times alone.
+        file.write_at(b"data", 0).unwrap();
+        file.read_at(&mut [0; 4], 0).unwrap();
+        let information = file.file_information().unwrap();
+        assert_eq!(information.last_access_time, time);
+        assert_eq!(information.last_write_time, time);
+
+        // Other handles still update them.
+        let mut other = harness.open("\\a.txt").unwrap();
+        other.write_at(b"more", 4).unwrap();
+        assert_ne!(file.file_information().unwrap().last_write_time, time);
+
+        file.set_file_time(
+            FileTimeChange::Unchanged,
+            FileTimeChange::Set(time),
+            FileTimeChange::Set(time),
+        )
+        .unwrap();
+        file.set_file_time(
+            FileTimeChange::ResumeUpdating,
+            FileTimeChange::Unchanged,
+            FileTimeChange::ResumeUpdating,
+        )
+        .unwrap();
+        file.write_at(b"data", 0).unwrap();
+        file.read_at(&mut [0; 4], 0).unwrap();
+        let information = file.file_information().unwrap();
+        assert_eq!(information.last_access_time, time);
+        assert_ne!(information.last_write_time, time);
+    }
+
+    #[test]
+    fn delete() {
+        let harness = Harness::new(MemFs::new());
+
+        drop(harness.create_dir("\\Dir").unwrap());
+        drop(harness.create("\\Dir\\a.txt").unwrap());
+        assert_eq!(
+            harness.delete("\\Dir").err(),
+            Some(NtStatus::DIRECTORY_NOT_EMPTY)
+        );
+        assert_eq!(harness.delete("\\").err(), Some(NtStatus::CANNOT_DELETE));
+
+        // Cancelling a deletion keeps the file.
+        let mut file = harness.open("\\Dir\\a.txt").unwrap();
+        file.set_delete_on_close(true).unwrap();
+        file.set_delete_on_close(false).unwrap();
+        drop(file);
+        assert_eq!(file_names(&harness.list_dir("\\Dir").unwrap()).len(), 3);
+
+        harness.delete("\\Dir\\a.txt").unwrap();
+        harness.delete("\\Dir").unwrap();
+        assert!(harness.list_dir("\\").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rename() {
+        let harness = Harness::new(MemFs::new());
+
+        drop(harness.create_dir("\\Dir").unwrap());
+        let mut file = harness.create("\\a.txt").unwrap();
+        file.write_at(b"data", 0).unwrap();
+        file.move_to("\\Dir\\b.txt", false).unwrap();
+        // The handle follows the file.
+        file.set_delete_on_close(true).unwrap();
+        drop(file);
+        assert_eq!(file_names(&harness.list_dir("\\").unwrap()), ["Dir"]);
+        assert_eq!(file_names(&harness.list_dir("\\Dir").unwrap()), [".", ".."]);
+
+        drop(harness.create("\\a.txt").unwrap());
+        drop(harness.create("\\b.txt").unwrap());
+        assert_eq!(
+            harness.rename("\\a.txt", "\\B.TXT", false).err(),
+            Some(NtStatus::OBJECT_NAME_COLLISION)
+        );
+        harness.rename("\\a.txt", "\\B.TXT", true).unwrap();
+        harness.rename("\\b.txt", "\\b.txt", false).unwrap();
+        assert_eq!(
+            file_names(&harness.list_dir("\\").unwrap()),
+            ["b.txt", "Dir"]
+        );
+
+        assert_eq!(
+            harness.rename("\\Dir", "\\Dir\\Sub", false).err(),
+            Some(NtStatus::INVALID_PARAMETER)
+        );
+        assert_eq!(
+            harness.rename("\\b.txt", "\\Missing\\b.txt", false).err(),
+            Some(NtStatus::OBJECT_PATH_NOT_FOUND)
+        );
+    }
+
+    #[test]
+    fn rename_with_other_handles() {
+        let harness = Harness::new(MemFs::new());
+
+        drop(harness.create_dir("\\Dir").unwrap());
+        let mut file = harness.create("\\a.txt").unwrap();
+        let mut other = harness.open("\\a.txt").unwrap();
+        file.move_to("\\Dir\\b.txt", false).unwrap();
+
+        // Other handles follow the file too.
+        other.move_to("\\Dir\\c.txt", false).unwrap();
+        assert_eq!(
+            file_names(&harness.list_dir("\\Dir").unwrap()),
+            [".", "..", "c.txt"]
+        );
+        other.set_delete_on_close(true).unwrap();
+        drop(other);
+        assert_eq!(file_names(&harness.list_dir("\\Dir").unwrap()), [".", ".."]);
+        drop(file);
+
+        // The parent entry and later renames use the new parent of a moved directory.
+        drop(harness.create_dir("\\Dir\\Sub").unwrap());
+        let mut sub = harness.open("\\Dir\\Sub").unwrap();
+        harness.rename("\\Dir\\Sub", "\\Sub", false).unwrap();
+        harness
+            .open("\\Dir")
+            .unwrap()
+            .set_file_attributes(FileAttributes::HIDDEN)
+            .unwrap();
+        let entries = sub.list_dir().unwrap();
+        assert!(!entries[1]
+            .file_attributes()
+            .contains(FileAttributes::HIDDEN));
+        sub.move_to("\\Renamed", false).unwrap();
+        assert_eq!(
+            file_names(&harness.list_dir("\\").unwrap()),
+            ["Dir", "Renamed"]
+        );
+    }
+
+    #[test]
+    fn rename_into_older_directory() {
+        let harness = Harness::new(MemFs::new());
+
+        // Make a subdirectory with a lower index than its parent.
+        drop(harness.create_dir("\\Inner").unwrap());
+        drop(harness.create_dir("\\Outer").unwrap());
+        harness.rename("\\Inner", "\\Outer\\Inner", false).unwrap();
+        let inner_index = harness
+            .open("\\Outer\\Inner")
+            .unwrap()
+            .file_information()
+            .unwrap()
+            .file_index;
+        let outer_index = harness
+            .open("\\Outer")
+            .unwrap()
+            .file_information()
+            .unwrap()
+            .file_index;
+        assert!(inner_index < outer_index);
+
+        let mut file = harness.create("\\Outer\\a.txt").unwrap();
+        file.move_to("\\Outer\\Inner\\a.txt", false).unwrap();
+        assert_eq!(
+            file_names(&harness.list_dir("\\Outer\\Inner").unwrap()),
+            [".", "..", "a.txt"]
+        );
+        file.move_to("\\Outer\\a.txt", false).unwrap();
+        assert_eq!(
+            file_names(&harness.list_dir("\\Outer").unwrap()),
+            [".", "..", "a.txt", "Inner"]
+        );
+        drop(file);
+
+        // The emptied subdirectory can be deleted from under its parent.
+        harness.delete("\\Outer\\Inner").unwrap();
+        assert_eq!(
+            file_names(&harness.list_dir("\\Outer").unwrap()),
+            [".", "..", "a.txt"]
+        );
+    }
+
+    #[test]
+    fn invalid_names() {
+        let harness = Harness::new(MemFs::new());
+
+        assert_eq!(
+            harness.create("\\a*b").err(),
+            Some(NtStatus::OBJECT_NAME_INVALID)
+        );
+        assert_eq!(
+            harness.create("\\file.txt:stream:$INVALID").err(),
+            Some(NtStatus::OBJECT_NAME_INVALID)
+        );
+        assert_eq!(
+            harness
+                .create("x".repeat(MAX_COMPONENT_LEN + 1).as_str())
+                .err(),
+            Some(NtStatus::NAME_TOO_LONG)
+        );
+    }
+
+    #[test]
+    fn volume_info() {
+        let harness = Harness::new(MemFs::with_volume_name("Test"));
+
+        let volume_info = harness.volume_info().unwrap();
+        assert_eq!(volume_info.volume_name, "Test");
+        assert_eq!(volume_info.file_system_name, "NTFS");
+        assert!(volume_info
+            .file_system_flags
+            .contains(FileSystemFlags::NAMED_STREAMS));
+    }
+}
@@ -0,0 +1,1045 @@
+//! A filesystem that mirrors a directory on the host.
+//!
+//! [`MirrorFs`] forwards every operation to [`std::fs`], so it can serve as a base for filesystems
+//! that expose local files with transformations on top.
+
+use crate::AccessMask;
+use crate::CreateDisposition;
+use crate::CreateFileInfo;
+use crate::CreateFileResult;
+use crate::CreateOptions;
+use crate::DiskSpace;
+use crate::FileAttributes;
+use crate::FileInfo;
+use crate::FileInformation;
+use crate::FileSystem;
+use crate::FileSystemFlags;
+use crate::FileTimeChange;
+use crate::FillFindData;
+use crate::FindData;
+use crate::NtStatus;
+use crate::SecurityInformation;
+use crate::WriteWideCStringCell;
+use std::ffi::OsStr;
+use std::fs::File;
+use std::fs::FileTimes;
+use std::fs::Metadata;
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Access rights that require the file to be opened for reading.
+const READ_ACCESS: AccessMask = AccessMask::FILE_READ_DATA
+    .union(AccessMask::FILE_EXECUTE)
+    .union(AccessMask::GENERIC_READ)
+    .union(AccessMask::GENERIC_EXECUTE)
+    .union(AccessMask::GENERIC_ALL)
+    .union(AccessMask::MAXIMUM_ALLOWED);
+
+/// Access rights that require the file to be opened for writing.
+const WRITE_ACCESS: AccessMask = AccessMask::FILE_WRITE_DATA
+    .union(AccessMask::FILE_APPEND_DATA)
+    .union(AccessMask::GENERIC_WRITE)
+    .union(AccessMask::GENERIC_ALL);
+
+/// A filesystem that forwards to a root directory on the host.
+///
+/// File names are resolved against the root, and names that would escape it are rejected.
+/// Symbolic links inside the root are followed as usual.
+pub struct MirrorFs {
+    root: PathBuf,
+}
+
+impl MirrorFs {
+    /// Make a new filesystem that mirrors the given directory.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Get the mirrored directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Resolve a file name like `\dir\file.txt` against the root.
+    fn resolve(&self, file_name: &[u16]) -> Result<PathBuf, NtStatus> {
+        let file_name = String::from_utf16(file_name).map_err(|_| NtStatus::OBJECT_NAME_INVALID)?;
+
+        let mut path = self.root.clone();
+        for component in file_name.split('\\') {
+            match component {
+                "" | "." => {}
+                ".." => return Err(NtStatus::OBJECT_NAME_INVALID),
+                component => {
+                    // Reject anything the host could interpret as a separator, prefix or stream.
+                    let is_invalid = |c: char| {
+                        c.is_control() || matches!(c, '/' | ':' | '<' | '>' | '"' | '|' | '?' | '*')
+                    };
+                    if component.chars().any(is_invalid) {
+                        return Err(NtStatus::OBJECT_NAME_INVALID);
+                    }
+                    path.push(component);
+                }
+            }
+        }
+
+        Ok(path)
+    }
+}
+
+/// The per-handle context of a [`MirrorFs`].
+///
+/// This holds no path, as Dokan passes the current name of the file on every call,
+/// including after another handle renamed it.
+pub struct MirrorFsContext {
+    /// The open file, or `None` for directories.
+    file: Option<File>,
+}
+
+impl MirrorFsContext {
+    /// Get the open file.
+    fn file(&self) -> Result<&File, NtStatus> {
+        self.file.as_ref().ok_or(NtStatus::INVALID_DEVICE_REQUEST)
+    }
+}
+
+/// Returns true if two paths on a case-insensitive host name the same file.
+#[cfg(windows)]
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    a.as_os_str().eq_ignore_ascii_case(b.as_os_str())
+}
+
+/// Returns true if two existing paths name the same file.
+#[cfg(unix)]
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (std::fs::symlink_metadata(a), std::fs::symlink_metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+/// Get the attributes of a file from host metadata.
+#[cfg(windows)]
+fn attributes_from_metadata(metadata: &Metadata) -> FileAttributes {
+    use std::os::windows::fs::MetadataExt;
+
+    FileAttributes::from_bits_retain(metadata.file_attributes())
+}
+
+/// Get the attributes of a file from host metadata.
+#[cfg(not(windows))]
+fn attributes_from_metadata(metadata: &Metadata) -> FileAttributes {
+    let mut attributes = if metadata.is_dir() {
+        FileAttributes::DIRECTORY
+    } else {
+        FileAttributes::ARCHIVE
+    };
+    if metadata.permissions().readonly() {
+        attributes |= FileAttributes::READONLY;
+    }
+    attributes
+}
+
+/// Make a FileInformation from host metadata.
+fn file_information_from_metadata(metadata: &Metadata) -> FileInformation {
+    let last_write_time = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+
+    let mut file_information = FileInformation {
+        attributes: attributes_from_metadata(metadata),
+        creation_time: metadata.created().unwrap_or(last_write_time),
+        last_access_time: metadata.accessed().unwrap_or(last_write_time),
+        last_write_time,
+        size: if metadata.is_dir() { 0 } else { metadata.len() },
+        ..FileInformation::new()
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        // Only the low bits of the device fit, which is enough to tell local volumes apart.
+        file_information.volume_serial_number = metadata.dev() as u32;
+        file_information.number_of_links = metadata.nlink().try_into().unwrap_or(u32::MAX);
+        file_information.file_index = metadata.ino();
+    }
+
+    file_information
+}
+
+/// Make a FindData from host metadata.
+fn find_data_from_metadata(file_name: &OsStr, metadata: &Metadata) -> FindData {
+    let file_information = file_information_from_metadata(metadata);
+
+    let mut find_data = FindData::new();
+    find_data
+        .set_file_name(file_name)
+        .set_file_attributes(file_information.attributes)
+        .set_creation_time(file_information.creation_time)
+        .set_last_access_time(file_information.last_access_time)
+        .set_last_write_time(file_information.last_write_time)
+        .set_size(file_information.size);
+    find_data
+}
+
+/// Open a file or directory to change its times.
+#[cfg(unix)]
+fn open_for_times(path: &Path) -> io::Result<File> {
+    File::open(path)
+}
+
+/// Open a file or directory to change its times.
+#[cfg(windows)]
+fn open_for_times(path: &Path) -> io::Result<File> {
+    use crate::sys;
+    use std::os::windows::fs::OpenOptionsExt;
+
+    // Directories can only be opened with backup semantics.
+    OpenOptions::new()
+        .access_mode(sys::FILE_WRITE_ATTRIBUTES)
+        .custom_flags(sys::FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)
+}
+
+/// Convert a path to a NUL-terminated wide string.
+#[cfg(windows)]
+fn wide_path(path: &Path) -> Vec<u16> {
+    use std::os::windows::ffi::OsStrExt;
+
+    path.as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect()
+}
+
+/// Get the capacity of the volume that contains a path.
+#[cfg(windows)]
+fn disk_space(path: &Path) -> Result<DiskSpace, NtStatus> {
+    use crate::sys;
+
+    let path = wide_path(path);
+    let mut disk_space = DiskSpace::new(0, 0);
+    let ok = unsafe {
+        sys::GetDiskFreeSpaceExW(
+            path.as_ptr(),
+            &mut disk_space.free_bytes_available,
+            &mut disk_space.total_bytes,
+            &mut disk_space.total_free_bytes,
+        )
+    };
+    if ok == 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    Ok(disk_space)
+}
+
+/// Get the capacity of the volume that contains a path.
+///
+/// `std` cannot query this on other hosts, so the Dokan defaults are used.
+#[cfg(not(windows))]
+fn disk_space(_path: &Path) -> Result<DiskSpace, NtStatus> {
+    Err(NtStatus::NOT_IMPLEMENTED)
+}
+
+/// Get the parts of the security descriptor of a file selected by `security_information`.
+#[cfg(windows)]
+fn file_security(
+    path: &Path,
+    security_information: SecurityInformation,
+) -> Result<Vec<u8>, NtStatus> {
+    use crate::sys;
+
+    const ERROR_INSUFFICIENT_BUFFER: i32 = 122;
+
+    let path = wide_path(path);
+    let mut buffer: Vec<u8> = Vec::new();
+    loop {
+        let mut length_needed = 0;
+        let ok = unsafe {
+            sys::GetFileSecurityW(
+                path.as_ptr(),
+                security_information.bits(),
+                buffer.as_mut_ptr().cast(),
+                buffer.len().try_into().unwrap(),
+                &mut length_needed,
+            )
+        };
+        let length_needed = usize::try_from(length_needed).unwrap();
+        if ok != 0 {
+            buffer.truncate(length_needed);
+            return Ok(buffer);
+        }
+
+        let error = io::Error::last_os_error();
+        if error.raw_os_error() != Some(ERROR_INSUFFICIENT_BUFFER) || length_needed <= buffer.len()
+        {
+            return Err(error.into());
+        }
+        buffer.resize(length_needed, 0);
+    }
+}
+
+/// Change the parts of the security descriptor of a file selected by `security_information`.
+#[cfg(windows)]
+fn set_file_security(
+    path: &Path,
+    security_information: SecurityInformation,
+    security_descriptor: &[u8],
+) -> Result<(), NtStatus> {
+    use crate::sys;
+
+    let path = wide_path(path);
+    let ok = unsafe {
+        sys::SetFileSecurityW(
+            path.as_ptr(),
+            security_information.bits(),
+            security_descriptor.as_ptr().cast_mut().cast(),
+        )
+    };
+    if ok == 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+/// Get the security descriptor of a file.
+///
+/// Other hosts have no security descriptors, so Dokan builds a default one.
+#[cfg(not(windows))]
+fn file_security(
+    _path: &Path,
+    _security_information: SecurityInformation,
+) -> Result<Vec<u8>, NtStatus> {
+    Err(NtStatus::NOT_IMPLEMENTED)
+}
+
+/// Change the security descriptor of a file.
+#[cfg(not(windows))]
+fn set_file_security(
+    _path: &Path,
+    _security_information: SecurityInformation,
+    _security_descriptor: &[u8],
+) -> Result<(), NtStatus> {
+    Err(NtStatus::NOT_IMPLEMENTED)
+}
+
+#[cfg(unix)]
+fn read_at(file: &File, buffer: &mut [u8], offset: u64) -> io::Result<usize> {
+    std::os::unix::fs::FileExt::read_at(file, buffer, offset)
+}
+
+#[cfg(windows)]
+fn read_at(file: &File, buffer: &mut [u8], offset: u64) -> io::Result<usize> {
+    std::os::windows::fs::FileExt::seek_read(file, buffer, offset)
+}
+
+#[cfg(unix)]
+fn write_at(file: &File, data: &[u8], offset: u64) -> io::Result<usize> {
+    std::os::unix::fs::FileExt::write_at(file, data, offset)
+}
+
+#[cfg(windows)]
+fn write_at(file: &File, data: &[u8], offset: u64) -> io::Result<usize> {
+    std::os::windows::fs::FileExt::seek_write(file, data, offset)
+}
+
+impl FileSystem for MirrorFs {
+    type Context = MirrorFsContext;
+
+    fn create_file(
+        &self,
+        file_name: &[u16],
+        create_file_info: &CreateFileInfo<'_>,
+        is_dir: &mut bool,
+    ) -> Result<CreateFileResult<Self::Context>, NtStatus> {
+        let path = self.resolve(file_name)?;
+        let create_disposition = create_file_info.create_disposition();
+        let create_options = create_file_info.create_options();
+        let desired_access = create_file_info.desired_access();
+        let delete_on_close = create_options.contains(CreateOptions::DELETE_ON_CLOSE);
+
+        if let Some(parent) = path.parent().filter(|_| path != self.root) {
+            if !parent.is_dir() {
+                return Err(NtStatus::OBJECT_PATH_NOT_FOUND);
+            }
+        }
+
+        let metadata = match std::fs::metadata(&path) {
+            Ok(metadata) => Some(metadata),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        let is_directory = match &metadata {
+            Some(metadata) => metadata.is_dir(),
+            None => create_options.contains(CreateOptions::DIRECTORY_FILE),
+        };
+
+        if is_directory {
+            if create_options.contains(CreateOptions::NON_DIRECTORY_FILE) {
+                return Err(NtStatus::FILE_IS_A_DIRECTORY);
+            }
+            if delete_on_close && path == self.root {
+                return Err(NtStatus::CANNOT_DELETE);
+            }
+
+            let exists = metadata.is_some();
+            match metadata {
+                Some(_) if create_disposition == CreateDisposition::Create => {
+                    return Err(NtStatus::OBJECT_NAME_COLLISION);
+                }
+                Some(_) if create_disposition.truncates() => {
+                    return Err(NtStatus::INVALID_PARAMETER);
+                }
+                Some(_) => {}
+                None if create_disposition.may_create() => std::fs::create_dir(&path)?,
+                None => return Err(NtStatus::OBJECT_NAME_NOT_FOUND),
+            }
+
+            *is_dir = true;
+
+            return Ok(CreateFileResult {
+                context: MirrorFsContext { file: None },
+                opened_existing: exists,
+            });
+        }
+
+        if create_options.contains(CreateOptions::DIRECTORY_FILE) {
+            return Err(NtStatus::NOT_A_DIRECTORY);
+        }
+
+        // Only ask the host for write access when it is needed,
+        // so read-only files can still be opened.
+        let exists = metadata.is_some();
+        if exists && create_disposition == CreateDisposition::Create {
+            return Err(NtStatus::OBJECT_NAME_COLLISION);
+        }
+        if !exists && !create_disposition.may_create() {
+            return Err(NtStatus::OBJECT_NAME_NOT_FOUND);
+        }
+        let create = !exists && create_disposition.may_create();
+        let truncate = exists && create_disposition.truncates();
+        let write = desired_access.intersects(WRITE_ACCESS) || create || truncate;
+        let read = desired_access.intersects(READ_ACCESS) || !write;
+
+        let file = OpenOptions::new()
+            .read(read)
+            .write(write)
+            .create_new(create && create_disposition == CreateDisposition::Create)
+            .create(create)
+            .truncate(truncate)
+            .open(&path)?;
+
+        *is_dir = false;
+
+        Ok(CreateFileResult {
+            context: MirrorFsContext { file: Some(file) },
+            opened_existing: exists,
+        })
+    }
+
+    fn cleanup(&self, file_name: &[u16], context: &Self::Context, file_info: &FileInfo) {
+        if !file_info.delete_on_close() {
+            return;
+        }
+
+        // Files are opened with delete sharing, so they can be removed while still open.
+        let Ok(path) = self.resolve(file_name) else {
+            return;
+        };
+        let _result = if context.file.is_none() {
+            std::fs::remove_dir(path)
+        } else {
+            std::fs::remove_file(path)
+        };
+    }
+
+    fn read_file(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        buffer: &mut [u8],
+        offset: u64,
+    ) -> Result<usize, NtStatus> {
+        let file = context.file()?;
+
+        let mut len = 0;
+        while len < buffer.len() {
+            match read_at(file, &mut buffer[len..], offset + len as u64) {
+                Ok(0) => break,
+                Ok(n) => len += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(len)
+    }
+
+    fn write_file(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        data: &[u8],
+        offset: u64,
+        write_to_eof: bool,
+    ) -> Result<usize, NtStatus> {
+        let file = context.file()?;
+        let offset = if write_to_eof {
+            file.metadata()?.len()
+        } else {
+            offset
+        };
+
+        let mut len = 0;
+        while len < data.len() {
+            match write_at(file, &data[len..], offset + len as u64) {
+                Ok(0) => return Err(NtStatus::DISK_FULL),
+                Ok(n) => len += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(len)
+    }
+
+    fn set_end_of_file(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        length: u64,
+    ) -> Result<(), NtStatus> {
+        context.file()?.set_len(length)?;
+        Ok(())
+    }
+
+    fn set_allocation_size(
+        &self,
+        _file_name: &[u16],
+        context: &Self::Context,
+        length: u64,
+    ) -> Result<(), NtStatus> {
+        // The host allocates space as needed, so only shrinking has an effect.
+        let file = context.file()?;
+        if length < file.metadata()?.len() {
+            file.set_len(length)?;
+        }
+        Ok(())
+    }
+
+    fn get_file_information(
+        &self,
+        file_name: &[u16],
+        context: &Self::Context,
+    ) -> Result<FileInformation, NtStatus> {
+        let metadata = match &context.file {
+            Some(file) => file.metadata()?,
+            None => std::fs::metadata(self.resolve(file_name)?)?,
+        };
+
+        Ok(file_information_from_metadata(&metadata))
+    }
+
+    fn find_files(
+        &self,
+        file_name: &[u16],
+        context: &Self::Context,
+        mut fill_find_data: FillFindData<'_>,
+    ) -> Result<(), NtStatus> {
+        if context.file.is_some() {
+            return Err(NtStatus::NOT_A_DIRECTORY);
+        }
+
+        let path = self.resolve(file_name)?;
+        if let Some(parent) = path.parent().filter(|_| path != self.root) {
+            let metadata = std::fs::metadata(&path)?;
+            fill_find_data.fill(&find_data_from_metadata(OsStr::new("."), &metadata))?;
+            let metadata = std::fs::metadata(parent)?;
+            fill_find_data.fill(&find_data_from_metadata(OsStr::new(".."), &metadata))?;
+        }
+
+        for entry in std::fs::read_dir(&path)? {
+            let entry = entry?;
+            // Skip entries that vanish or cannot be inspected while listing.
+            let metadata = match std::fs::metadata(entry.path()) {
+                Ok(metadata) => metadata,
+                Err(_e) => continue,
+            };
+            let find_data = find_data_from_metadata(&entry.file_name(), &metadata);
+            fill_find_data.fill(&find_data)?;
+        }
+
+        Ok(())
+    }
+
+    fn set_file_attributes(
+        &self,
+        file_name: &[u16],
+        _context: &Self::Context,
+        file_attributes: FileAttributes,
+    ) -> Result<(), NtStatus> {
+        if file_attributes.is_empty() {
+            return Ok(());
+        }
+
+        // Only the read-only attribute can be mirrored on every host.
+        let path = self.resolve(file_name)?;
+        let mut permissions = std::fs::metadata(&path)?.permissions();
+        let readonly = file_attributes.contains(FileAttributes::READONLY);
+        if permissions.readonly() != readonly {
+            permissions.set_readonly(readonly);
+            std::fs::set_permissions(&path, permissions)?;
+        }
+        Ok(())
+    }
+
+    fn set_file_time(
+        &self,
+        file_name: &[u16],
+        _context: &Self::Context,
+        creation_time: FileTimeChange,
+        last_access_time: FileTimeChange,
+        last_write_time: FileTimeChange,
+    ) -> Result<(), NtStatus> {
+        // The standard library cannot stop or resume updating host times, so only set times are mirrored.
+        let mut times = FileTimes::new();
+        if let FileTimeChange::Set(last_access_time) = last_access_time {
+            times = times.set_accessed(last_access_time);
+        }
+        if let FileTimeChange::Set(last_write_time) = last_write_time {
+            times = times.set_modified(last_write_time);
+        }
+        // Only some hosts can change the creation time.
+        #[cfg(windows)]
+        if let FileTimeChange::Set(creation_time) = creation_time {
+            use std::os::windows::fs::FileTimesExt;
+
+            times = times.set_created(creation_time);
+        }
+        #[cfg(not(windows))]
+        let _ = creation_time;
+
+        open_for_times(&self.resolve(file_name)?)?.set_times(times)?;
+        Ok(())
+    }
+
+    fn delete_file(
+        &self,
+        file_name: &[u16],
+        _context: &Self::Context,
+        file_info: &FileInfo,
+    ) -> Result<(), NtStatus> {
+        if !file_info.delete_on_close() {
+            return Ok(());
+        }
+
+        let metadata = std::fs::metadata(self.resolve(file_name)?)?;
+        if metadata.permissions().readonly() {
+            return Err(NtStatus::CANNOT_DELETE);
+        }
+        Ok(())
+    }
+
+    fn delete_directory(
+        &self,
+        file_name: &[u16],
+        _context: &Self::Context,
+        file_info: &FileInfo,
+    ) -> Result<(), NtStatus> {
+        if !file_info.delete_on_close() {
+            return Ok(());
+        }
+
+        let path = self.resolve(file_name)?;
+        if path == self.root {
+            return Err(NtStatus::CANNOT_DELETE);
+        }
+        if std::fs::read_dir(path)?.next().is_some() {
+            return Err(NtStatus::DIRECTORY_NOT_EMPTY);
+        }
+        Ok(())
+    }
+
+    fn move_file(
+        &self,
+        file_name: &[u16],
+        _context: &Self::Context,
+        new_file_name: &[u16],
+        replace_if_existing: bool,
+    ) -> Result<(), NtStatus> {
+        let path = self.resolve(file_name)?;
+        let new_path = self.resolve(new_file_name)?;
+        if path == self.root || new_path == self.root {
+            return Err(NtStatus::ACCESS_DENIED);
+        }
+
+        // The host replaces existing files, so check first.
+        // A file may be renamed to a different case of its own name.
+        if let Ok(metadata) = std::fs::symlink_metadata(&new_path) {
+            if !is_same_file(&path, &new_path) {
+                if !replace_if_existing {
+                    return Err(NtStatus::OBJECT_NAME_COLLISION);
+                }
+                if metadata.is_dir() {
+                    return Err(NtStatus::ACCESS_DENIED);
+                }
+            }
+        }
+        // A directory cannot be moved into itself.
+        if new_path != path && new_path.starts_with(&path) {
+            return Err(NtStatus::INVALID_PARAMETER);
+        }
+
+        std::fs::rename(&path, &new_path)?;
+        Ok(())
+    }
+
+    fn get_file_security(
+        &self,
+        file_name: &[u16],
+        _context: &Self::Context,
+        security_information: SecurityInformation,
+    ) -> Result<Vec<u8>, NtStatus> {
+        file_security(&self.resolve(file_name)?, security_information)
+    }
+
+    fn set_file_security(
+        &self,
+        file_name: &[u16],
+        _context: &Self::Context,
+        security_information: SecurityInformation,
+        security_descriptor: &[u8],
+    ) -> Result<(), NtStatus> {
+        set_file_security(
+            &self.resolve(file_name)?,
+            security_information,
+            security_descriptor,
+        )
+    }
+
+    fn get_disk_free_space(&self) -> Result<DiskSpace, NtStatus> {
+        disk_space(&self.root)
+    }
+
+    fn get_volume_information(
+        &self,
+        mut volume_name: WriteWideCStringCell<'_>,
+        _volume_serial_number: &mut u32,
+        _maximum_component_length: &mut u32,
+        file_system_flags: &mut FileSystemFlags,
+        mut file_system_name: WriteWideCStringCell<'_>,
+    ) -> Result<(), NtStatus> {
+        volume_name.write("MirrorFs");
+        *file_system_flags =
+            FileSystemFlags::CASE_PRESERVED_NAMES | FileSystemFlags::UNICODE_ON_DISK;
+        file_system_name.write("NTFS");
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::CreateRequest;
+    use crate::testing::Harness;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    /// A directory that is removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+            let path = std::env::temp_dir().join(format!(
+                "dokany-mirror-{}-{}",
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            std::fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _result = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn file_names(entries: &[FindData]) -> Vec<String> {
+        let mut file_names: Vec<_> = entries
+            .iter()
+            .map(|entry| String::from_utf16_lossy(entry.file_name()))
+            .collect();
+        file_names.sort();
+        file_names
+    }
+
+    #[test]
+    fn write_read() {
+        let root = TempDir::new();
+        let harness = Harness::new(MirrorFs::new(&root.0));
+
+        let mut file = harness.create("\\file.txt").unwrap();
+        assert_eq!(file.write_at(b"Hello", 0).unwrap(), 5);
+        assert_eq!(file.append(b" World").unwrap(), 6);
+        drop(file);
+
+        assert_eq!(
+            std::fs::read(root.0.join("file.txt")).unwrap(),
+            b"Hello World"
+        );
+        assert_eq!(harness.read("\\file.txt").unwrap(), b"Hello World");
+        assert_eq!(
+            harness.create("\\file.txt").err(),
+            Some(NtStatus::OBJECT_NAME_COLLISION)
+        );
+        assert_eq!(
+            harness.open("\\missing.txt").err(),
+            Some(NtStatus::OBJECT_NAME_NOT_FOUND)
+        );
+
+        let request = CreateRequest {
+            create_disposition: CreateDisposition::OpenIf,
+            ..CreateRequest::new()
+        };
+        assert!(harness
+            .create_file("\\file.txt", &request)
+            .unwrap()
+            .opened_existing());
+        assert!(!harness
+            .create_file("\\new.txt", &request)
+            .unwrap()
+            .opened_existing());
+    }
+
+    #[test]
+    fn file_information() {
+        let root = TempDir::new();
+        std::fs::write(root.0.join("a.txt"), b"abc").unwrap();
+        let harness = Harness::new(MirrorFs::new(&root.0));
+
+        let info = harness.open("\\a.txt").unwrap().file_information().unwrap();
+        let metadata = std::fs::metadata(root.0.join("a.txt")).unwrap();
+        assert_eq!(info.size, 3);
+        assert!(!info.attributes.contains(FileAttributes::DIRECTORY));
+        // File times only have a precision of 100ns.
+        let difference = metadata
+            .modified()
+            .unwrap()
+            .duration_since(info.last_write_time)
+            .unwrap();
+        assert!(difference.as_nanos() < 100);
+
+        let info = harness.open("\\").unwrap().file_information().unwrap();
+        assert!(info.attributes.contains(FileAttributes::DIRECTORY));
+    }
+
+    #[test]
+    fn directories() {
+        let root = TempDir::new();
+        std::fs::write(root.0.join("a.txt"), b"abc").unwrap();
+        let harness = Harness::new(MirrorFs::new(&root.0));
+
+        drop(harness.create_dir("\\dir").unwrap());
+        assert!(root.0.join("dir").is_dir());
+        drop(harness.create("\\dir\\b.txt").unwrap());
+
+        let entries = harness.list_dir("\\").unwrap();
+        assert_eq!(file_names(&entries), ["a.txt", "dir"]);
+        assert_eq!(
+            file_names(&harness.list_dir("\\dir").unwrap()),
+            [".", "..", "b.txt"]
+        );
+
+        // The dot entries describe the directory and its parent.
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        open_for_times(&root.0.join("dir"))
+            .unwrap()
+            .set_times(FileTimes::new().set_modified(time))
+            .unwrap();
+        let entries = harness.list_dir("\\dir").unwrap();
+        assert_eq!(entries[0].last_write_time(), time);
+        assert_ne!(entries[1].last_write_time(), time);
+
+        assert_eq!(
+            harness.create("\\missing\\c.txt").err(),
+            Some(NtStatus::OBJECT_PATH_NOT_FOUND)
+        );
+        assert_eq!(
+            harness.list_dir("\\a.txt").err(),
+            Some(NtStatus::NOT_A_DIRECTORY)
+        );
+    }
+
+    #[test]
+    fn reject_escapes() {
+        let root = TempDir::new();
+        let harness = Harness::new(MirrorFs::new(root.0.join("inner")));
+        std::fs::create_dir(root.0.join("inner")).unwrap();
+        std::fs::write(root.0.join("secret.txt"), b"secret").unwrap();
+
+        for file_name in [
+            "\\..\\secret.txt",
+            "\\dir\\..\\..\\secret.txt",
+            "\\../secret.txt",
+        ] {
+            assert_eq!(
+                harness.open(file_name).err(),
+                Some(NtStatus::OBJECT_NAME_INVALID),
+                "{file_name}"
+            );
+        }
+    }
+
+    #[test]
+    fn delete_on_close() {
+        let root = TempDir::new();
+        let harness = Harness::new(MirrorFs::new(&root.0));
+
+        let request = CreateRequest {
+            create_disposition: CreateDisposition::Create,
+            create_options: CreateOptions::DELETE_ON_CLOSE,
+            ..CreateRequest::new()
+        };
+        let file = harness.create_file("\\temp.txt", &request).unwrap();
+        assert!(root.0.join("temp.txt").exists());
+        drop(file);
+
+        assert!(!root.0.join("temp.txt").exists());
+    }
+
+    #[test]
+    fn set_size() {
+        let root = TempDir::new();
+        let harness = Harness::new(MirrorFs::new(&root.0));
+
+        let mut file = harness.create("\\a.txt").unwrap();
+        file.write_at(b"Hello World!", 0).unwrap();
+        file.set_end_of_file(5).unwrap();
+        assert_eq!(std::fs::read(root.0.join("a.txt")).unwrap(), b"Hello");
+        file.set_allocation_size(4096).unwrap();
+        assert_eq!(file.file_information().unwrap().size, 5);
+        file.set_allocation_size(2).unwrap();
+        assert_eq!(std::fs::read(root.0.join("a.txt")).unwrap(), b"He");
+    }
+
+    #[test]
+    fn set_attributes_and_times() {
+        let root = TempDir::new();
+        let harness = Harness::new(MirrorFs::new(&root.0));
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+        let mut file = harness.create("\\a.txt").unwrap();
+        file.set_file_time(
+            FileTimeChange::Unchanged,
+            FileTimeChange::Set(time),
+            FileTimeChange::Set(time),
+        )
+        .unwrap();
+        file.set_file_attributes(FileAttributes::READONLY).unwrap();
+
+        let metadata = std::fs::metadata(root.0.join("a.txt")).unwrap();
+        assert!(metadata.permissions().readonly());
+        assert_eq!(metadata.modified().unwrap(), time);
+
+        file.set_file_attributes(FileAttributes::NORMAL).unwrap();
+        assert!(!std::fs::metadata(root.0.join("a.txt"))
+            .unwrap()
+            .permissions()
+            .readonly());
+    }
+
+    #[test]
+    fn delete_rename() {
+        let root = TempDir::new();
+        let harness = Harness::new(MirrorFs::new(&root.0));
+
+        drop(harness.create_dir("\\dir").unwrap());
+        drop(harness.create("\\dir\\a.txt").unwrap());
+        drop(harness.create("\\b.txt").unwrap());
+        assert_eq!(
+            harness.delete("\\dir").err(),
+            Some(NtStatus::DIRECTORY_NOT_EMPTY)
+        );
+
+        assert_eq!(
+            harness.rename("\\b.txt", "\\dir\\a.txt", false).err(),
+            Some(NtStatus::OBJECT_NAME_COLLISION)
+        );
+        assert_eq!(
+            harness.rename("\\dir", "\\dir\\sub", false).err(),
+            Some(NtStatus::INVALID_PARAMETER)
+        );
+        harness.rename("\\b.txt", "\\dir\\a.txt", true).unwrap();
+        assert!(!root.0.join("b.txt").exists());
+
+        harness.delete("\\dir\\a.txt").unwrap();
+        harness.delete("\\dir").unwrap();
+        assert!(harness.list_dir("\\").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rename_with_other_handles() {
+        let root = TempDir::new();
+        let harness = Harness::new(MirrorFs::new(&root.0));
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+        let mut file = harness.create("\\a.txt").unwrap();
+        let mut other = harness.open("\\a.txt").unwrap();
+        file.move_to("\\b.txt", false).unwrap();
+        other
+            .set_file_time(
+                FileTimeChange::Unchanged,
+                FileTimeChange::Unchanged,
+                FileTimeChange::Set(time),
+            )
+            .unwrap();
+        assert_eq!(
+            std::fs::metadata(root.0.join("b.txt"))
+                .unwrap()
+                .modified()
+                .unwrap(),
+            time
+        );
+        other.move_to("\\c.txt", false).unwrap();
+        drop(other);
+        file.set_file_attributes(FileAttributes::READONLY).unwrap();
+        assert!(std::fs::metadata(root.0.join("c.txt"))
+            .unwrap()
+            .permissions()
+            .readonly());
+        drop(file);
+
+        drop(harness.create_dir("\\dir").unwrap());
+        drop(harness.create("\\dir\\d.txt").unwrap());
+        let request = CreateRequest {
+            desired_access: AccessMask::GENERIC_READ,
+            create_options: CreateOptions::DIRECTORY_FILE,
+            is_directory: true,
+            ..CreateRequest::new()
+        };
+        let mut dir = harness.create_file("\\dir", &request).unwrap();
+        harness.rename("\\dir", "\\renamed", false).unwrap();
+        assert_eq!(file_names(&dir.list_dir().unwrap()), [".", "..", "d.txt"]);
+    }
+
+    // Other hosts do not report disk space, so the filesystem falls back to the default.
+    #[cfg(windows)]
+    #[test]
+    fn disk_space() {
+        let root = TempDir::new();
+        let harness = Harness::new(MirrorFs::new(&root.0));
+
+        let disk_space = harness.disk_space().unwrap();
+        assert!(disk_space.total_bytes > 0);
+        assert!(disk_space.total_free_bytes <= disk_space.total_bytes);
+        assert!(disk_space.free_bytes_available <= disk_space.total_free_bytes);
+    }
+}
@@ -0,0 +1,216 @@
+/// Matches zero or more characters.
+const STAR: u16 = b'*' as u16;
+
+/// Matches exactly one character.
+const QM: u16 = b'?' as u16;
+
+/// Matches zero or more characters, up to but not including the final period in the name.
+const DOS_STAR: u16 = b'<' as u16;
+
+/// Matches one character other than a period, or zero characters before a period or at the end of the name.
+const DOS_QM: u16 = b'>' as u16;
+
+/// Matches a period, or zero characters at the end of the name.
+const DOS_DOT: u16 = b'"' as u16;
+
+const PERIOD: u16 = b'.' as u16;
+
+/// Check whether a name matches a DOS wildcard expression.
+///
+/// This has the semantics of `FsRtlIsNameInExpression`, which `DokanIsNameInExpression` emulates:
+/// * `*` matches zero or more characters.
+/// * `?` matches exactly one character.
+/// * `<` matches zero or more characters, up to but not including the final period in the name.
+/// * `>` matches one character other than a period, or zero characters before a period or at the end of the name.
+/// * `"` matches a period, or zero characters at the end of the name.
+///
+/// Every other character matches itself, ignoring case if `ignore_case` is true.
+pub fn is_name_in_expression(expression: &[u16], name: &[u16], ignore_case: bool) -> bool {
+    let last_period = name.iter().rposition(|&c| c == PERIOD);
+
+    // The expression positions that have matched the name so far.
+    // Position `expression.len()` means the entire expression has matched.
+    let mut states = vec![false; expression.len() + 1];
+    let mut next_states = vec![false; expression.len() + 1];
+    states[0] = true;
+
+    for (i, &c) in name.iter().enumerate() {
+        add_zero_width_matches(expression, &mut states, Some(c));
+
+        next_states.fill(false);
+        for (position, &expression_char) in expression.iter().enumerate() {
+            if !states[position] {
+                continue;
+            }
+
+            match expression_char {
+                STAR => next_states[position] = true,
+                DOS_STAR => {
+                    if Some(i) != last_period {
+                        next_states[position] = true;
+                    }
+                }
+                QM => next_states[position + 1] = true,
+                DOS_QM => {
+                    if c != PERIOD {
+                        next_states[position + 1] = true;
+                    }
+                }
+                DOS_DOT => {
+                    if c == PERIOD {
+                        next_states[position + 1] = true;
+                    }
+                }
+                _ => {
+                    if chars_eq(expression_char, c, ignore_case) {
+                        next_states[position + 1] = true;
+                    }
+                }
+            }
+        }
+
+        std::mem::swap(&mut states, &mut next_states);
+        if !states.contains(&true) {
+            return false;
+        }
+    }
+
+    add_zero_width_matches(expression, &mut states, None);
+    states[expression.len()]
+}
+
+/// Advance past wildcards that can match zero characters before `next`, or the end of the name if `None`.
+fn add_zero_width_matches(expression: &[u16], states: &mut [bool], next: Option<u16>) {
+    // Zero width matches only move forward, so a single pass reaches every position.
+    for (position, &expression_char) in expression.iter().enumerate() {
+        if !states[position] {
+            continue;
+        }
+
+        let zero_width = match expression_char {
+            STAR | DOS_STAR => true,
+            DOS_QM => matches!(next, None | Some(PERIOD)),
+            DOS_DOT => next.is_none(),
+            _ => false,
+        };
+        if zero_width {
+            states[position + 1] = true;
+        }
+    }
+}
+
+/// Compare two UTF-16 code units, optionally ignoring case.
+fn chars_eq(a: u16, b: u16, ignore_case: bool) -> bool {
+    a == b || (ignore_case && to_upper(a) == to_upper(b))
+}
+
+/// Uppercase a UTF-16 code unit, if it has a single code unit uppercase form.
+pub(crate) fn to_upper(c: u16) -> u16 {
+    let Some(c) = char::from_u32(c.into()) else {
+        // Lone surrogates have no case.
+        return c;
+    };
+
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(upper), None) => u16::try_from(u32::from(upper)).unwrap_or(c as u16),
+        _ => c as u16,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Cases of `(expression, name, ignore_case, expected)`.
+    const CASES: &[(&str, &str, bool, bool)] = &[
+        ("*", "", true, true),
+        ("*", "file.txt", true, true),
+        ("*.txt", "file.txt", true, true),
+        ("*.txt", "file.TXT", true, true),
+        ("*.txt", "file.TXT", false, false),
+        ("*.txt", "file.txt.bak", true, false),
+        ("file.txt", "FILE.TXT", true, true),
+        ("file.txt", "FILE.TXT", false, false),
+        ("f?le.txt", "file.txt", true, true),
+        ("f?le.txt", "fle.txt", true, false),
+        ("???", "ab", true, false),
+        ("*a*b*", "xxaxxbxx", true, true),
+        ("*a*b*", "xxbxxaxx", true, false),
+        // `*.*` and `*.` as translated by Win32.
+        ("<\"*", "file", true, true),
+        ("<\"*", "file.txt", true, true),
+        ("<\"*", "archive.tar.gz", true, true),
+        ("<\"", "file", true, true),
+        ("<\"", "file.txt", true, false),
+        ("<", "file", true, true),
+        ("<", "file.txt", true, false),
+        ("<.txt", "a.b.txt", true, true),
+        ("<.gz", "a.tar.gz", true, true),
+        ("<.tar", "a.tar.gz", true, false),
+        // `????????.???` as translated by Win32.
+        (">>>>>>>>\">>>", "file.txt", true, true),
+        (">>>>>>>>\">>>", "file", true, true),
+        (">>>>>>>>\">>>", "longfilename.txt", true, false),
+        (">>>>>>>>\">>>", "file.text", true, false),
+        (">.txt", ".txt", true, true),
+        ("a>", "a.", true, false),
+        ("a\"b", "a.b", true, true),
+        ("a\"b", "ab", true, false),
+        ("a\"", "a", true, true),
+        ("", "", true, true),
+        ("", "a", true, false),
+        ("é*", "ÉTÉ", true, true),
+        ("é*", "ÉTÉ", false, false),
+    ];
+
+    #[test]
+    fn cases() {
+        for &(expression, name, ignore_case, expected) in CASES {
+            let expression: Vec<u16> = expression.encode_utf16().collect();
+            let name: Vec<u16> = name.encode_utf16().collect();
+            assert_eq!(
+                is_name_in_expression(&expression, &name, ignore_case),
+                expected,
+                "expression {:?}, name {:?}, ignore_case {ignore_case}",
+                String::from_utf16_lossy(&expression),
+                String::from_utf16_lossy(&name),
+            );
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn matches_dokan() {
+        use crate::sys;
+
+        for &(expression, name, ignore_case, _expected) in CASES {
+            // Some Dokan releases never match `<` against names without a period.
+            if expression.contains('<') && !name.contains('.') {
+                continue;
+            }
+
+            let expression: Vec<u16> = expression.encode_utf16().chain([0]).collect();
+            let name: Vec<u16> = name.encode_utf16().chain([0]).collect();
+            let dokan = unsafe {
+                sys::DokanIsNameInExpression(
+                    expression.as_ptr(),
+                    name.as_ptr(),
+                    sys::BOOL::from(ignore_case),
+                )
+            };
+
+            assert_eq!(
+                is_name_in_expression(
+                    &expression[..expression.len() - 1],
+                    &name[..name.len() - 1],
+                    ignore_case
+                ),
+                dokan != 0,
+                "expression {:?}, name {:?}, ignore_case {ignore_case}",
+                String::from_utf16_lossy(&expression),
+                String::from_utf16_lossy(&name),
+            );
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use crate::sys;
+
+/// A status code returned from filesystem operations.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct NtStatus(pub sys::NTSTATUS);
+
+impl NtStatus {
+    /// The operation completed successfully.
+    pub const SUCCESS: Self = Self(sys::STATUS_SUCCESS);
+    /// The operation was unsuccessful.
+    pub const UNSUCCESSFUL: Self = Self(sys::STATUS_UNSUCCESSFUL);
+    /// The requested operation is not implemented.
+    pub const NOT_IMPLEMENTED: Self = Self(sys::STATUS_NOT_IMPLEMENTED);
+    /// An internal error occurred.
+    pub const INTERNAL_ERROR: Self = Self(sys::STATUS_INTERNAL_ERROR);
+    /// An invalid parameter was passed to a service or function.
+    pub const INVALID_PARAMETER: Self = Self(sys::STATUS_INVALID_PARAMETER);
+    /// An invalid handle was specified.
+    pub const INVALID_HANDLE: Self = Self(sys::STATUS_INVALID_HANDLE);
+    /// The specified request is not a valid operation for the target device.
+    pub const INVALID_DEVICE_REQUEST: Self = Self(sys::STATUS_INVALID_DEVICE_REQUEST);
+    /// The request is not supported.
+    pub const NOT_SUPPORTED: Self = Self(sys::STATUS_NOT_SUPPORTED);
+    /// A process has requested access to an object but has not been granted those access rights.
+    pub const ACCESS_DENIED: Self = Self(sys::STATUS_ACCESS_DENIED);
+    /// The file does not exist.
+    pub const NO_SUCH_FILE: Self = Self(sys::STATUS_NO_SUCH_FILE);
+    /// The object name is not found.
+    pub const OBJECT_NAME_NOT_FOUND: Self = Self(sys::STATUS_OBJECT_NAME_NOT_FOUND);
+    /// The path does not exist.
+    pub const OBJECT_PATH_NOT_FOUND: Self = Self(sys::STATUS_OBJECT_PATH_NOT_FOUND);
+    /// The object name already exists.
+    pub const OBJECT_NAME_COLLISION: Self = Self(sys::STATUS_OBJECT_NAME_COLLISION);
+    /// The object name is invalid.
+    pub const OBJECT_NAME_INVALID: Self = Self(sys::STATUS_OBJECT_NAME_INVALID);
+    /// The object path component was not a directory object.
+    pub const OBJECT_PATH_INVALID: Self = Self(sys::STATUS_OBJECT_PATH_INVALID);
+    /// The name is too long.
+    pub const NAME_TOO_LONG: Self = Self(sys::STATUS_NAME_TOO_LONG);
+    /// The file that was specified as a target is a directory.
+    pub const FILE_IS_A_DIRECTORY: Self = Self(sys::STATUS_FILE_IS_A_DIRECTORY);
+    /// A requested opened file is not a directory.
+    pub const NOT_A_DIRECTORY: Self = Self(sys::STATUS_NOT_A_DIRECTORY);
+    /// The directory trying to be deleted is not empty.
+    pub const DIRECTORY_NOT_EMPTY: Self = Self(sys::STATUS_DIRECTORY_NOT_EMPTY);
+    /// An attempt has been made to remove a file or directory that cannot be deleted.
+    pub const CANNOT_DELETE: Self = Self(sys::STATUS_CANNOT_DELETE);
+    /// A non-close operation has been requested of a file object that has a delete pending.
+    pub const DELETE_PENDING: Self = Self(sys::STATUS_DELETE_PENDING);
+    /// A file cannot be opened because the share access flags are incompatible.
+    pub const SHARING_VIOLATION: Self = Self(sys::STATUS_SHARING_VIOLATION);
+    /// A requested read/write cannot be granted due to a conflicting file lock.
+    pub const FILE_LOCK_CONFLICT: Self = Self(sys::STATUS_FILE_LOCK_CONFLICT);
+    /// A requested file lock cannot be granted due to other existing locks.
+    pub const LOCK_NOT_GRANTED: Self = Self(sys::STATUS_LOCK_NOT_GRANTED);
+    /// The range specified in an unlock operation was not locked.
+    pub const RANGE_NOT_LOCKED: Self = Self(sys::STATUS_RANGE_NOT_LOCKED);
+    /// The end-of-file marker has been reached.
+    pub const END_OF_FILE: Self = Self(sys::STATUS_END_OF_FILE);
+    /// No more files were found which match the file specification.
+    pub const NO_MORE_FILES: Self = Self(sys::STATUS_NO_MORE_FILES);
+    /// The data was too large to fit into the specified buffer.
+    pub const BUFFER_OVERFLOW: Self = Self(sys::STATUS_BUFFER_OVERFLOW);
+    /// The buffer is too small to contain the entry.
+    pub const BUFFER_TOO_SMALL: Self = Self(sys::STATUS_BUFFER_TOO_SMALL);
+    /// The disk is full.
+    pub const DISK_FULL: Self = Self(sys::STATUS_DISK_FULL);
+    /// The file size exceeds the limit allowed.
+    pub const FILE_TOO_LARGE: Self = Self(sys::STATUS_FILE_TOO_LARGE);
+    /// The media is write protected.
+    pub const MEDIA_WRITE_PROTECTED: Self = Self(sys::STATUS_MEDIA_WRITE_PROTECTED);
+    /// The target file of a rename request is located on a different device than the source of the rename request.
+    pub const NOT_SAME_DEVICE: Self = Self(sys::STATUS_NOT_SAME_DEVICE);
+    /// An attempt was made to create more links on a file than the file system supports.
+    pub const TOO_MANY_LINKS: Self = Self(sys::STATUS_TOO_MANY_LINKS);
+    /// Not enough virtual memory or paging file quota is available to complete the specified operation.
+    pub const NO_MEMORY: Self = Self(sys::STATUS_NO_MEMORY);
+    /// Insufficient system resources exist to complete the API.
+    pub const INSUFFICIENT_RESOURCES: Self = Self(sys::STATUS_INSUFFICIENT_RESOURCES);
+    /// The I/O device reported an I/O error.
+    pub const IO_DEVICE_ERROR: Self = Self(sys::STATUS_IO_DEVICE_ERROR);
+    /// The specified I/O operation was not completed before the time-out period expired.
+    pub const IO_TIMEOUT: Self = Self(sys::STATUS_IO_TIMEOUT);
+    /// An error in reading or writing data occurred.
+    pub const DATA_ERROR: Self = Self(sys::STATUS_DATA_ERROR);
+    /// The I/O request was canceled.
+    pub const CANCELLED: Self = Self(sys::STATUS_CANCELLED);
+    /// An I/O request other than close was attempted using a file object that had already been closed.
+    pub const FILE_CLOSED: Self = Self(sys::STATUS_FILE_CLOSED);
+
+    /// Returns true if this is a success or informational status.
+    ///
+    /// This is the same check as the `NT_SUCCESS` macro.
+    pub fn is_success(self) -> bool {
+        self.0 >= 0
+    }
+
+    /// Get a short description of this status, if it is known.
+    fn description(self) -> Option<&'static str> {
+        let description = match self {
+            Self::SUCCESS => "success",
+            Self::UNSUCCESSFUL => "unsuccessful",
+            Self::NOT_IMPLEMENTED => "not implemented",
+            Self::INTERNAL_ERROR => "internal error",
+            Self::INVALID_PARAMETER => "invalid parameter",
+            Self::INVALID_HANDLE => "invalid handle",
+            Self::INVALID_DEVICE_REQUEST => "invalid device request",
+            Self::NOT_SUPPORTED => "not supported",
+            Self::ACCESS_DENIED => "access denied",
+            Self::NO_SUCH_FILE => "no such file",
+            Self::OBJECT_NAME_NOT_FOUND => "object name not found",
+            Self::OBJECT_PATH_NOT_FOUND => "object path not found",
+            Self::OBJECT_NAME_COLLISION => "object name already exists",
+            Self::OBJECT_NAME_INVALID => "object name invalid",
+            Self::OBJECT_PATH_INVALID => "object path invalid",
+            Self::NAME_TOO_LONG => "name too long",
+            Self::FILE_IS_A_DIRECTORY => "file is a directory",
+            Self::NOT_A_DIRECTORY => "not a directory",
+            Self::DIRECTORY_NOT_EMPTY => "directory not empty",
+            Self::CANNOT_DELETE => "cannot delete",
+            Self::DELETE_PENDING => "delete pending",
+            Self::SHARING_VIOLATION => "sharing violation",
+            Self::FILE_LOCK_CONFLICT => "file lock conflict",
+            Self::LOCK_NOT_GRANTED => "lock not granted",
+            Self::RANGE_NOT_LOCKED => "range not locked",
+            Self::END_OF_FILE => "end of file",
+            Self::NO_MORE_FILES => "no more files",
+            Self::BUFFER_OVERFLOW => "buffer overflow",
+            Self::BUFFER_TOO_SMALL => "buffer too small",
+            Self::DISK_FULL => "disk full",
+            Self::FILE_TOO_LARGE => "file too large",
+            Self::MEDIA_WRITE_PROTECTED => "media write protected",
+            Self::NOT_SAME_DEVICE => "not same device",
+            Self::TOO_MANY_LINKS => "too many links",
+            Self::NO_MEMORY => "no memory",
+            Self::INSUFFICIENT_RESOURCES => "insufficient resources",
+            Self::IO_DEVICE_ERROR => "io device error",
+            Self::IO_TIMEOUT => "io timeout",
+            Self::DATA_ERROR => "data error",
+            Self::CANCELLED => "cancelled",
+            Self::FILE_CLOSED => "file closed",
+            _ => return None,
+        };
+
+        Some(description)
+    }
+}
+
+impl From<NtStatus> for Result<(), NtStatus> {
+    fn from(status: NtStatus) -> Self {
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(status)
+        }
+    }
+}
+
+impl From<NtStatus> for sys::NTSTATUS {
+    fn from(status: NtStatus) -> Self {
+        status.0
+    }
+}
+
+impl From<std::io::Error> for NtStatus {
+    fn from(error: std::io::Error) -> Self {
+        Self::from(error.kind())
+    }
+}
+
+impl From<std::io::ErrorKind> for NtStatus {
+    fn from(kind: std::io::ErrorKind) -> Self {
+        use std::io::ErrorKind;
+
+        match kind {
+            ErrorKind::NotFound => Self::OBJECT_NAME_NOT_FOUND,
+            ErrorKind::PermissionDenied => Self::ACCESS_DENIED,
+            ErrorKind::AlreadyExists => Self::OBJECT_NAME_COLLISION,
+            ErrorKind::InvalidInput => Self::INVALID_PARAMETER,
+            ErrorKind::InvalidData => Self::DATA_ERROR,
+            ErrorKind::UnexpectedEof => Self::END_OF_FILE,
+            ErrorKind::OutOfMemory => Self::NO_MEMORY,
+            ErrorKind::Unsupported => Self::NOT_SUPPORTED,
+            ErrorKind::TimedOut => Self::IO_TIMEOUT,
+            ErrorKind::Interrupted => Self::CANCELLED,
+            ErrorKind::NotADirectory => Self::NOT_A_DIRECTORY,
+            ErrorKind::IsADirectory => Self::FILE_IS_A_DIRECTORY,
+            ErrorKind::DirectoryNotEmpty => Self::DIRECTORY_NOT_EMPTY,
+            ErrorKind::ReadOnlyFilesystem => Self::MEDIA_WRITE_PROTECTED,
+            ErrorKind::StorageFull => Self::DISK_FULL,
+            ErrorKind::FileTooLarge => Self::FILE_TOO_LARGE,
+            ErrorKind::InvalidFilename => Self::OBJECT_NAME_INVALID,
+            ErrorKind::CrossesDevices => Self::NOT_SAME_DEVICE,
+            ErrorKind::TooManyLinks => Self::TOO_MANY_LINKS,
+            ErrorKind::ResourceBusy => Self::SHARING_VIOLATION,
+            _ => Self::UNSUCCESSFUL,
+        }
+    }
+}
+
+impl std::fmt::Display for NtStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.description() {
+            Some(description) => write!(f, "{description} (0x{:08X})", self.0),
+            None => write!(f, "status code 0x{:08X}", self.0),
+        }
+    }
+}
+
+impl Default for NtStatus {
+    fn default() -> Self {
+        Self::SUCCESS
+    }
+}
+
+impl std::error::Error for NtStatus {}
@@ -0,0 +1,19 @@
+/// An error for a wide string with a misplaced NUL.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum NulError {
+    /// The string has a NUL before its end, at the given position.
+    Interior(usize),
+    /// The string is not NUL-terminated.
+    NotTerminated,
+}
+
+impl std::fmt::Display for NulError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Interior(position) => write!(f, "interior NUL at position {position}"),
+            Self::NotTerminated => write!(f, "missing NUL terminator"),
+        }
+    }
+}
+
+impl std::error::Error for NulError {}
@@ -1,59 +1,318 @@
+use crate::file_time::file_time_ptr_to_change;
 use crate::sys;
 use crate::AccessMask;
+use crate::CreateDisposition;
+use crate::CreateFileInfo;
+use crate::CreateFileResult;
+use crate::CreateOptions;
+use crate::DiskSpace;
+use crate::FileAttributes;
+use crate::FileInfo;
+use crate::FileSystem;
 use crate::FillFindData;
+use crate::FillFindStreamData;
 use crate::GlobalContext;
+use crate::NtStatus;
+use crate::SecurityContext;
+use crate::SecurityInformation;
+use crate::ShareAccess;
+use crate::WideCStr;
 use crate::WriteWideCStringCell;
+use std::cell::Cell;
+use std::marker::PhantomData;
 use std::mem::MaybeUninit;
 
-/// Function trampolines
-pub(crate) static OPERATIONS: sys::DOKAN_OPERATIONS = sys::DOKAN_OPERATIONS {
-    ZwCreateFile: Some(create_file_callback),
-    FindFiles: Some(find_files_callback),
-    GetVolumeInformation: Some(get_volume_information_callback),
-    Mounted: Some(mounted_callback),
-    Unmounted: Some(unmounted_callback),
-    ..sys::DOKAN_OPERATIONS::new()
-};
+/// Function trampolines for a filesystem
+pub(crate) struct Operations<FS>(PhantomData<FS>);
+
+impl<FS: FileSystem> Operations<FS> {
+    /// Function trampolines
+    pub(crate) const OPERATIONS: &'static sys::DOKAN_OPERATIONS = &sys::DOKAN_OPERATIONS {
+        ZwCreateFile: Some(create_file_callback::<FS>),
+        Cleanup: Some(cleanup_callback::<FS>),
+        CloseFile: Some(close_file_callback::<FS>),
+        ReadFile: Some(read_file_callback::<FS>),
+        WriteFile: Some(write_file_callback::<FS>),
+        GetFileInformation: Some(get_file_information_callback::<FS>),
+        FindFiles: Some(find_files_callback::<FS>),
+        FindFilesWithPattern: Some(find_files_with_pattern_callback::<FS>),
+        FindStreams: Some(find_streams_callback::<FS>),
+        SetFileAttributes: Some(set_file_attributes_callback::<FS>),
+        SetFileTime: Some(set_file_time_callback::<FS>),
+        DeleteFile: Some(delete_file_callback::<FS>),
+        DeleteDirectory: Some(delete_directory_callback::<FS>),
+        MoveFile: Some(move_file_callback::<FS>),
+        SetEndOfFile: Some(set_end_of_file_callback::<FS>),
+        SetAllocationSize: Some(set_allocation_size_callback::<FS>),
+        LockFile: Some(lock_file_callback::<FS>),
+        UnlockFile: Some(unlock_file_callback::<FS>),
+        GetFileSecurity: Some(get_file_security_callback::<FS>),
+        SetFileSecurity: Some(set_file_security_callback::<FS>),
+        GetDiskFreeSpace: Some(get_disk_free_space_callback::<FS>),
+        GetVolumeInformation: Some(get_volume_information_callback::<FS>),
+        Mounted: Some(mounted_callback::<FS>),
+        Unmounted: Some(unmounted_callback::<FS>),
+        ..sys::DOKAN_OPERATIONS::new()
+    };
+}
 
 unsafe fn slice_from_c_wstr_ptr<'a>(ptr: *const u16) -> &'a [u16] {
-    let len = libc::wcslen(ptr);
-    std::slice::from_raw_parts(ptr, len)
+    WideCStr::from_ptr(ptr).as_slice()
 }
 
-unsafe fn extract_global_context<'a>(
+unsafe fn extract_global_context<'a, FS>(
     dokan_file_info: sys::PDOKAN_FILE_INFO,
-) -> &'a mut GlobalContext {
+) -> &'a GlobalContext<FS> {
     debug_assert!(!dokan_file_info.is_null());
     let options = (*dokan_file_info).DokanOptions;
     debug_assert!(!options.is_null());
     let options = &*options;
     debug_assert!(options.GlobalContext != 0);
-    &mut *(options.GlobalContext as *mut GlobalContext)
+    &*(options.GlobalContext as *const GlobalContext<FS>)
+}
+
+/// Get the per-handle context created by `create_file`.
+///
+/// # Panics
+/// Panics if there is no context for this handle.
+unsafe fn extract_context<'a, FS: FileSystem>(
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> &'a FS::Context {
+    debug_assert!(!dokan_file_info.is_null());
+    let context = (*dokan_file_info).Context;
+    assert!(context != 0, "missing file context");
+    &*(context as *const FS::Context)
 }
 
-unsafe extern "stdcall" fn create_file_callback(
+/// Convert the result of a filesystem operation into a status code.
+fn result_to_status(result: Result<(), NtStatus>) -> sys::NTSTATUS {
+    match result {
+        Ok(()) => sys::STATUS_SUCCESS,
+        Err(status) => status.0,
+    }
+}
+
+/// Convert the result of a directory listing into a status code.
+///
+/// A propagated [`crate::FillDataError::BufferFull`] only means the listing ended early, so it is a success.
+fn find_result_to_status(result: Result<(), NtStatus>, buffer_full: &Cell<bool>) -> sys::NTSTATUS {
+    match result {
+        Err(NtStatus::BUFFER_OVERFLOW) if buffer_full.get() => sys::STATUS_SUCCESS,
+        result => result_to_status(result),
+    }
+}
+
+unsafe extern "system" fn create_file_callback<FS: FileSystem>(
     file_name: sys::LPCWSTR,
-    _security_context: sys::PDOKAN_IO_SECURITY_CONTEXT,
+    security_context: sys::PDOKAN_IO_SECURITY_CONTEXT,
     desired_access: sys::ACCESS_MASK,
-    _file_attributes: sys::ULONG,
-    _share_access: sys::ULONG,
-    _create_disposition: sys::ULONG,
-    _create_options: sys::ULONG,
+    file_attributes: sys::ULONG,
+    share_access: sys::ULONG,
+    create_disposition: sys::ULONG,
+    create_options: sys::ULONG,
     dokan_file_info: sys::PDOKAN_FILE_INFO,
 ) -> sys::NTSTATUS {
     let result = std::panic::catch_unwind(|| {
-        let global_context = extract_global_context(dokan_file_info);
+        let global_context = extract_global_context::<FS>(dokan_file_info);
         let file_name = slice_from_c_wstr_ptr(file_name);
-        let desired_access = AccessMask::from_bits_retain(desired_access);
+        let create_disposition = match CreateDisposition::try_from(create_disposition) {
+            Ok(create_disposition) => create_disposition,
+            Err(_e) => return sys::STATUS_INVALID_PARAMETER,
+        };
+        let create_file_info = CreateFileInfo {
+            security_context: security_context
+                .as_ref()
+                .map(|security_context| SecurityContext::new(security_context)),
+            desired_access: AccessMask::from_bits_retain(desired_access),
+            file_attributes: FileAttributes::from_bits_retain(file_attributes),
+            share_access: ShareAccess::from_bits_retain(share_access),
+            create_disposition,
+            create_options: CreateOptions::from_bits_retain(create_options),
+        };
         let mut is_dir = (*dokan_file_info).IsDirectory != 0;
 
-        let result = global_context
-            .filesystem
-            .create_file(file_name, desired_access, &mut is_dir);
+        let result =
+            global_context
+                .filesystem
+                .create_file(file_name, &create_file_info, &mut is_dir);
 
         (*dokan_file_info).IsDirectory = u8::from(is_dir);
 
-        result
+        // Dokan treats a collision as a success when the disposition may open or create a file.
+        let reports_collision = create_disposition.may_open_or_create();
+        match result {
+            Ok(CreateFileResult {
+                context,
+                opened_existing,
+            }) => {
+                (*dokan_file_info).Context = Box::into_raw(Box::new(context)) as u64;
+                if opened_existing && reports_collision {
+                    sys::STATUS_OBJECT_NAME_COLLISION
+                } else {
+                    sys::STATUS_SUCCESS
+                }
+            }
+            // These would leave the handle open without a context.
+            Err(status)
+                if status.is_success()
+                    || (status == NtStatus::OBJECT_NAME_COLLISION && reports_collision) =>
+            {
+                sys::STATUS_INTERNAL_ERROR
+            }
+            Err(status) => status.0,
+        }
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(_e) => sys::STATUS_INTERNAL_ERROR,
+    }
+}
+
+unsafe extern "system" fn cleanup_callback<FS: FileSystem>(
+    file_name: sys::LPCWSTR,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) {
+    let _result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let file_name = slice_from_c_wstr_ptr(file_name);
+        let context = extract_context::<FS>(dokan_file_info);
+        let file_info = FileInfo::from(&*dokan_file_info);
+
+        global_context
+            .filesystem
+            .cleanup(file_name, context, &file_info);
+    });
+}
+
+unsafe extern "system" fn close_file_callback<FS: FileSystem>(
+    file_name: sys::LPCWSTR,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) {
+    let _result = std::panic::catch_unwind(|| {
+        let context = std::mem::replace(&mut (*dokan_file_info).Context, 0);
+        if context == 0 {
+            return;
+        }
+        let context = Box::from_raw(context as *mut FS::Context);
+
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let file_name = slice_from_c_wstr_ptr(file_name);
+
+        global_context.filesystem.close_file(file_name, *context);
+    });
+}
+
+unsafe extern "system" fn read_file_callback<FS: FileSystem>(
+    file_name: sys::LPCWSTR,
+    buffer: sys::LPVOID,
+    buffer_length: sys::DWORD,
+    read_length: sys::LPDWORD,
+    offset: sys::LONGLONG,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::NTSTATUS {
+    let result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let file_name = slice_from_c_wstr_ptr(file_name);
+        let context = extract_context::<FS>(dokan_file_info);
+        read_length.write(0);
+
+        let offset = match u64::try_from(offset) {
+            Ok(offset) => offset,
+            Err(_e) => return sys::STATUS_INVALID_PARAMETER,
+        };
+        let buffer: &mut [u8] = if buffer_length == 0 {
+            &mut []
+        } else {
+            std::slice::from_raw_parts_mut(buffer.cast(), buffer_length.try_into().unwrap())
+        };
+        let buffer_len = buffer.len();
+
+        match global_context
+            .filesystem
+            .read_file(file_name, context, buffer, offset)
+        {
+            Ok(len) => {
+                assert!(len <= buffer_len);
+                read_length.write(len.try_into().unwrap());
+                sys::STATUS_SUCCESS
+            }
+            Err(status) => status.0,
+        }
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(_e) => sys::STATUS_INTERNAL_ERROR,
+    }
+}
+
+unsafe extern "system" fn write_file_callback<FS: FileSystem>(
+    file_name: sys::LPCWSTR,
+    buffer: sys::LPCVOID,
+    number_of_bytes_to_write: sys::DWORD,
+    number_of_bytes_written: sys::LPDWORD,
+    offset: sys::LONGLONG,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::NTSTATUS {
+    let result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let file_name = slice_from_c_wstr_ptr(file_name);
+        let context = extract_context::<FS>(dokan_file_info);
+        number_of_bytes_written.write(0);
+
+        let write_to_eof = (*dokan_file_info).WriteToEndOfFile != 0;
+        // The offset is meaningless when writing to the end of the file.
+        let offset = match u64::try_from(offset) {
+            Ok(offset) => offset,
+            Err(_e) if write_to_eof => 0,
+            Err(_e) => return sys::STATUS_INVALID_PARAMETER,
+        };
+        let data: &[u8] = if number_of_bytes_to_write == 0 {
+            &[]
+        } else {
+            std::slice::from_raw_parts(buffer.cast(), number_of_bytes_to_write.try_into().unwrap())
+        };
+
+        match global_context
+            .filesystem
+            .write_file(file_name, context, data, offset, write_to_eof)
+        {
+            Ok(len) => {
+                assert!(len <= data.len());
+                number_of_bytes_written.write(len.try_into().unwrap());
+                sys::STATUS_SUCCESS
+            }
+            Err(status) => status.0,
+        }
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(_e) => sys::STATUS_INTERNAL_ERROR,
+    }
+}
+
+unsafe extern "system" fn get_file_information_callback<FS: FileSystem>(
+    file_name: sys::LPCWSTR,
+    buffer: sys::LPBY_HANDLE_FILE_INFORMATION,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::NTSTATUS {
+    let result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let file_name = slice_from_c_wstr_ptr(file_name);
+        let context = extract_context::<FS>(dokan_file_info);
+
+        match global_context
+            .filesystem
+            .get_file_information(file_name, context)
+        {
+            Ok(file_information) => {
+                buffer.write(sys::BY_HANDLE_FILE_INFORMATION::from(file_information));
+                sys::STATUS_SUCCESS
+            }
+            Err(status) => status.0,
+        }
     });
 
     match result {
@@ -62,22 +321,423 @@ unsafe extern "stdcall" fn create_file_callback(
     }
 }
 
-unsafe extern "stdcall" fn find_files_callback(
+unsafe extern "system" fn find_files_callback<FS: FileSystem>(
     file_name: sys::LPCWSTR,
     fill_find_data: sys::PFillFindData,
     dokan_file_info: sys::PDOKAN_FILE_INFO,
 ) -> sys::NTSTATUS {
     let result = std::panic::catch_unwind(|| {
-        let global_context = extract_global_context(dokan_file_info);
+        let global_context = extract_global_context::<FS>(dokan_file_info);
         let file_name = slice_from_c_wstr_ptr(file_name);
+        let context = extract_context::<FS>(dokan_file_info);
+        let buffer_full = Cell::new(false);
         let fill_find_data = FillFindData {
             func: fill_find_data,
             dokan_file_info: &mut *dokan_file_info,
+            buffer_full: &buffer_full,
         };
 
-        global_context
+        let result = global_context
+            .filesystem
+            .find_files(file_name, context, fill_find_data);
+
+        find_result_to_status(result, &buffer_full)
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(_e) => sys::STATUS_INTERNAL_ERROR,
+    }
+}
+
+unsafe extern "system" fn find_files_with_pattern_callback<FS: FileSystem>(
+    path_name: sys::LPCWSTR,
+    search_pattern: sys::LPCWSTR,
+    fill_find_data: sys::PFillFindData,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::NTSTATUS {
+    let result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let path_name = slice_from_c_wstr_ptr(path_name);
+        let search_pattern = slice_from_c_wstr_ptr(search_pattern);
+        let context = extract_context::<FS>(dokan_file_info);
+        let buffer_full = Cell::new(false);
+        let fill_find_data = FillFindData {
+            func: fill_find_data,
+            dokan_file_info: &mut *dokan_file_info,
+            buffer_full: &buffer_full,
+        };
+
+        let result = global_context.filesystem.find_files_with_pattern(
+            path_name,
+            context,
+            search_pattern,
+            fill_find_data,
+        );
+
+        find_result_to_status(result, &buffer_full)
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(_e) => sys::STATUS_INTERNAL_ERROR,
+    }
+}
+
+unsafe extern "system" fn find_streams_callback<FS: FileSystem>(
+    file_name: sys::LPCWSTR,
+    fill_find_stream_data: sys::PFillFindStreamData,
+    find_stream_context: sys::PVOID,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::NTSTATUS {
+    let result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let file_name = slice_from_c_wstr_ptr(file_name);
+        let context = extract_context::<FS>(dokan_file_info);
+        let fill_find_stream_data = FillFindStreamData {
+            func: fill_find_stream_data,
+            find_stream_context,
+            _marker: PhantomData,
+        };
+
+        // Dokan reports a full buffer to the caller, so it is not turned into success.
+        let result =
+            global_context
+                .filesystem
+                .find_streams(file_name, context, fill_find_stream_data);
+
+        result_to_status(result)
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(_e) => sys::STATUS_INTERNAL_ERROR,
+    }
+}
+
+unsafe extern "system" fn set_file_attributes_callback<FS: FileSystem>(
+    file_name: sys::LPCWSTR,
+    file_attributes: sys::DWORD,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::NTSTATUS {
+    let result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let file_name = slice_from_c_wstr_ptr(file_name);
+        let context = extract_context::<FS>(dokan_file_info);
+        let file_attributes = FileAttributes::from_bits_retain(file_attributes);
+
+        let result =
+            global_context
+                .filesystem
+                .set_file_attributes(file_name, context, file_attributes);
+
+        result_to_status(result)
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(_e) => sys::STATUS_INTERNAL_ERROR,
+    }
+}
+
+unsafe extern "system" fn set_file_time_callback<FS: FileSystem>(
+    file_name: sys::LPCWSTR,
+    creation_time: *const sys::FILETIME,
+    last_access_time: *const sys::FILETIME,
+    last_write_time: *const sys::FILETIME,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::NTSTATUS {
+    let result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let file_name = slice_from_c_wstr_ptr(file_name);
+        let context = extract_context::<FS>(dokan_file_info);
+        let (creation_time, last_access_time, last_write_time) = match (
+            file_time_ptr_to_change(creation_time),
+            file_time_ptr_to_change(last_access_time),
+            file_time_ptr_to_change(last_write_time),
+        ) {
+            (Ok(creation_time), Ok(last_access_time), Ok(last_write_time)) => {
+                (creation_time, last_access_time, last_write_time)
+            }
+            _ => return sys::STATUS_INVALID_PARAMETER,
+        };
+
+        let result = global_context.filesystem.set_file_time(
+            file_name,
+            context,
+            creation_time,
+            last_access_time,
+            last_write_time,
+        );
+
+        result_to_status(result)
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(_e) => sys::STATUS_INTERNAL_ERROR,
+    }
+}
+
+unsafe extern "system" fn delete_file_callback<FS: FileSystem>(
+    file_name: sys::LPCWSTR,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::NTSTATUS {
+    let result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let file_name = slice_from_c_wstr_ptr(file_name);
+        let context = extract_context::<FS>(dokan_file_info);
+        let file_info = FileInfo::from(&*dokan_file_info);
+
+        let result = global_context
+            .filesystem
+            .delete_file(file_name, context, &file_info);
+
+        result_to_status(result)
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(_e) => sys::STATUS_INTERNAL_ERROR,
+    }
+}
+
+unsafe extern "system" fn delete_directory_callback<FS: FileSystem>(
+    file_name: sys::LPCWSTR,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::NTSTATUS {
+    let result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let file_name = slice_from_c_wstr_ptr(file_name);
+        let context = extract_context::<FS>(dokan_file_info);
+        let file_info = FileInfo::from(&*dokan_file_info);
+
+        let result = global_context
+            .filesystem
+            .delete_directory(file_name, context, &file_info);
+
+        result_to_status(result)
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(_e) => sys::STATUS_INTERNAL_ERROR,
+    }
+}
+
+unsafe extern "system" fn move_file_callback<FS: FileSystem>(
+    file_name: sys::LPCWSTR,
+    new_file_name: sys::LPCWSTR,
+    replace_if_existing: sys::BOOL,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::NTSTATUS {
+    let result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let file_name = slice_from_c_wstr_ptr(file_name);
+        let new_file_name = slice_from_c_wstr_ptr(new_file_name);
+        let context = extract_context::<FS>(dokan_file_info);
+
+        let result = global_context.filesystem.move_file(
+            file_name,
+            context,
+            new_file_name,
+            replace_if_existing != 0,
+        );
+
+        result_to_status(result)
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(_e) => sys::STATUS_INTERNAL_ERROR,
+    }
+}
+
+unsafe extern "system" fn set_end_of_file_callback<FS: FileSystem>(
+    file_name: sys::LPCWSTR,
+    byte_offset: sys::LONGLONG,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::NTSTATUS {
+    let result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let file_name = slice_from_c_wstr_ptr(file_name);
+        let context = extract_context::<FS>(dokan_file_info);
+
+        let length = match u64::try_from(byte_offset) {
+            Ok(length) => length,
+            Err(_e) => return sys::STATUS_INVALID_PARAMETER,
+        };
+
+        let result = global_context
+            .filesystem
+            .set_end_of_file(file_name, context, length);
+
+        result_to_status(result)
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(_e) => sys::STATUS_INTERNAL_ERROR,
+    }
+}
+
+unsafe extern "system" fn set_allocation_size_callback<FS: FileSystem>(
+    file_name: sys::LPCWSTR,
+    alloc_size: sys::LONGLONG,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::NTSTATUS {
+    let result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let file_name = slice_from_c_wstr_ptr(file_name);
+        let context = extract_context::<FS>(dokan_file_info);
+
+        let length = match u64::try_from(alloc_size) {
+            Ok(length) => length,
+            Err(_e) => return sys::STATUS_INVALID_PARAMETER,
+        };
+
+        let result = global_context
+            .filesystem
+            .set_allocation_size(file_name, context, length);
+
+        result_to_status(result)
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(_e) => sys::STATUS_INTERNAL_ERROR,
+    }
+}
+
+unsafe extern "system" fn lock_file_callback<FS: FileSystem>(
+    file_name: sys::LPCWSTR,
+    byte_offset: sys::LONGLONG,
+    length: sys::LONGLONG,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::NTSTATUS {
+    let result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let file_name = slice_from_c_wstr_ptr(file_name);
+        let context = extract_context::<FS>(dokan_file_info);
+
+        let (offset, length) = match (u64::try_from(byte_offset), u64::try_from(length)) {
+            (Ok(offset), Ok(length)) => (offset, length),
+            _ => return sys::STATUS_INVALID_PARAMETER,
+        };
+
+        let result = global_context
+            .filesystem
+            .lock_file(file_name, context, offset, length);
+
+        result_to_status(result)
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(_e) => sys::STATUS_INTERNAL_ERROR,
+    }
+}
+
+unsafe extern "system" fn unlock_file_callback<FS: FileSystem>(
+    file_name: sys::LPCWSTR,
+    byte_offset: sys::LONGLONG,
+    length: sys::LONGLONG,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::NTSTATUS {
+    let result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let file_name = slice_from_c_wstr_ptr(file_name);
+        let context = extract_context::<FS>(dokan_file_info);
+
+        let (offset, length) = match (u64::try_from(byte_offset), u64::try_from(length)) {
+            (Ok(offset), Ok(length)) => (offset, length),
+            _ => return sys::STATUS_INVALID_PARAMETER,
+        };
+
+        let result = global_context
             .filesystem
-            .find_files(file_name, fill_find_data)
+            .unlock_file(file_name, context, offset, length);
+
+        result_to_status(result)
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(_e) => sys::STATUS_INTERNAL_ERROR,
+    }
+}
+
+unsafe extern "system" fn get_file_security_callback<FS: FileSystem>(
+    file_name: sys::LPCWSTR,
+    security_information: sys::PSECURITY_INFORMATION,
+    security_descriptor: sys::PSECURITY_DESCRIPTOR,
+    buffer_length: sys::ULONG,
+    length_needed: sys::PULONG,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::NTSTATUS {
+    let result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let file_name = slice_from_c_wstr_ptr(file_name);
+        let context = extract_context::<FS>(dokan_file_info);
+        let security_information = SecurityInformation::from_bits_retain(*security_information);
+
+        let result =
+            global_context
+                .filesystem
+                .get_file_security(file_name, context, security_information);
+        let descriptor = match result {
+            Ok(descriptor) => descriptor,
+            Err(status) => return status.0,
+        };
+
+        // Dokan retries with a larger buffer if it is too small.
+        *length_needed = sys::ULONG::try_from(descriptor.len()).unwrap();
+        if *length_needed > buffer_length {
+            return sys::STATUS_BUFFER_OVERFLOW;
+        }
+        std::ptr::copy_nonoverlapping(
+            descriptor.as_ptr(),
+            security_descriptor.cast::<u8>(),
+            descriptor.len(),
+        );
+
+        sys::STATUS_SUCCESS
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(_e) => sys::STATUS_INTERNAL_ERROR,
+    }
+}
+
+unsafe extern "system" fn set_file_security_callback<FS: FileSystem>(
+    file_name: sys::LPCWSTR,
+    security_information: sys::PSECURITY_INFORMATION,
+    security_descriptor: sys::PSECURITY_DESCRIPTOR,
+    buffer_length: sys::ULONG,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::NTSTATUS {
+    let result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+        let file_name = slice_from_c_wstr_ptr(file_name);
+        let context = extract_context::<FS>(dokan_file_info);
+        let security_information = SecurityInformation::from_bits_retain(*security_information);
+        if security_descriptor.is_null() {
+            return sys::STATUS_INVALID_PARAMETER;
+        }
+        let security_descriptor = std::slice::from_raw_parts(
+            security_descriptor.cast::<u8>(),
+            buffer_length.try_into().unwrap(),
+        );
+
+        let result = global_context.filesystem.set_file_security(
+            file_name,
+            context,
+            security_information,
+            security_descriptor,
+        );
+
+        result_to_status(result)
     });
 
     match result {
@@ -86,7 +746,36 @@ unsafe extern "stdcall" fn find_files_callback(
     }
 }
 
-unsafe extern "stdcall" fn get_volume_information_callback(
+unsafe extern "system" fn get_disk_free_space_callback<FS: FileSystem>(
+    free_bytes_available: sys::PULONGLONG,
+    total_number_of_bytes: sys::PULONGLONG,
+    total_number_of_free_bytes: sys::PULONGLONG,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::NTSTATUS {
+    let result = std::panic::catch_unwind(|| {
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+
+        let disk_space = match global_context.filesystem.get_disk_free_space() {
+            Ok(disk_space) => disk_space,
+            Err(NtStatus::NOT_IMPLEMENTED) => DiskSpace::default(),
+            Err(status) => return status.0,
+        };
+        let disk_space = disk_space.clamped();
+
+        *free_bytes_available = disk_space.free_bytes_available;
+        *total_number_of_bytes = disk_space.total_bytes;
+        *total_number_of_free_bytes = disk_space.total_free_bytes;
+
+        sys::STATUS_SUCCESS
+    });
+
+    match result {
+        Ok(code) => code,
+        Err(_e) => sys::STATUS_INTERNAL_ERROR,
+    }
+}
+
+unsafe extern "system" fn get_volume_information_callback<FS: FileSystem>(
     volume_name_buffer: sys::LPWSTR,
     volume_name_size: sys::DWORD,
     volume_serial_number: sys::LPDWORD,
@@ -97,7 +786,7 @@ unsafe extern "stdcall" fn get_volume_information_callback(
     dokan_file_info: sys::PDOKAN_FILE_INFO,
 ) -> sys::NTSTATUS {
     let result = std::panic::catch_unwind(|| {
-        let global_context = extract_global_context(dokan_file_info);
+        let global_context = extract_global_context::<FS>(dokan_file_info);
 
         let volume_name = std::slice::from_raw_parts_mut(
             volume_name_buffer.cast::<MaybeUninit<u16>>(),
@@ -119,13 +808,15 @@ unsafe extern "stdcall" fn get_volume_information_callback(
         file_system_name[0].write(0);
         let file_system_name = WriteWideCStringCell::new(file_system_name);
 
-        global_context.filesystem.get_volume_information(
+        let result = global_context.filesystem.get_volume_information(
             volume_name,
             &mut *volume_serial_number,
             &mut *maximum_component_length,
             &mut *file_system_flags.cast(),
             file_system_name,
-        )
+        );
+
+        result_to_status(result)
     });
 
     match result {
@@ -134,15 +825,17 @@ unsafe extern "stdcall" fn get_volume_information_callback(
     }
 }
 
-unsafe extern "stdcall" fn mounted_callback(
+unsafe extern "system" fn mounted_callback<FS: FileSystem>(
     mount_point: sys::LPCWSTR,
     dokan_file_info: sys::PDOKAN_FILE_INFO,
 ) -> sys::NTSTATUS {
     let result = std::panic::catch_unwind(|| {
-        let global_context = extract_global_context(dokan_file_info);
+        let global_context = extract_global_context::<FS>(dokan_file_info);
         let mount_point = slice_from_c_wstr_ptr(mount_point);
 
-        global_context.filesystem.mounted(mount_point)
+        let result = global_context.filesystem.mounted(mount_point);
+
+        result_to_status(result)
     });
 
     match result {
@@ -151,13 +844,15 @@ unsafe extern "stdcall" fn mounted_callback(
     }
 }
 
-unsafe extern "stdcall" fn unmounted_callback(
+unsafe extern "system" fn unmounted_callback<FS: FileSystem>(
     dokan_file_info: sys::PDOKAN_FILE_INFO,
 ) -> sys::NTSTATUS {
     let result = std::panic::catch_unwind(|| {
-        let global_context = extract_global_context(dokan_file_info);
+        let global_context = extract_global_context::<FS>(dokan_file_info);
+
+        let result = global_context.filesystem.unmounted();
 
-        global_context.filesystem.unmounted()
+        result_to_status(result)
     });
 
     match result {
@@ -1,13 +1,21 @@
 use crate::sys;
 use crate::AsWide;
 use crate::OptionFlags;
+use crate::OptionsError;
+use crate::WideCStr;
+use crate::WideCString;
+use std::time::Duration;
+
+/// The smallest non-default sector or allocation unit size.
+const MIN_UNIT_SIZE: u32 = 512;
 
 /// Options for a filesystem
 pub struct Options {
     /// The ffi options struct
     pub(crate) options: sys::DOKAN_OPTIONS,
 
-    mount_point_buffer: Vec<u16>,
+    mount_point: Option<WideCString>,
+    unc_name: Option<WideCString>,
 }
 
 impl Options {
@@ -16,25 +24,154 @@ impl Options {
         Self {
             options: sys::DOKAN_OPTIONS::new(),
 
-            mount_point_buffer: Vec::new(),
+            mount_point: None,
+            unc_name: None,
         }
     }
 
     /// Set the requested version
-    pub fn set_version(&mut self, version: u16) {
+    pub fn set_version(&mut self, version: u16) -> &mut Self {
         self.options.Version = version;
+        self
+    }
+
+    /// Get the requested version
+    pub fn version(&self) -> u16 {
+        self.options.Version
+    }
+
+    /// Set whether only a single thread should process events.
+    ///
+    /// This is not recommended, as it can easily create a bottleneck.
+    pub fn set_single_thread(&mut self, single_thread: bool) -> &mut Self {
+        self.options.SingleThread = u8::from(single_thread);
+        self
+    }
+
+    /// Get whether only a single thread should process events.
+    pub fn single_thread(&self) -> bool {
+        self.options.SingleThread != 0
     }
 
     /// Set the mount point
-    pub fn set_mount_point(&mut self, mount_point: impl AsWide) {
-        self.mount_point_buffer = mount_point.as_wide().chain(std::iter::once(0)).collect();
+    ///
+    /// The mount point must not have a NUL.
+    pub fn set_mount_point(&mut self, mount_point: impl AsWide) -> Result<&mut Self, OptionsError> {
+        let mount_point = WideCString::new(mount_point).map_err(OptionsError::InvalidMountPoint)?;
+
+        // The heap buffer does not move with the string.
+        self.options.MountPoint = mount_point.as_ptr();
+        self.mount_point = Some(mount_point);
+        Ok(self)
+    }
 
-        self.options.MountPoint = self.mount_point_buffer.as_ptr();
+    /// Get the mount point, if set.
+    pub fn mount_point(&self) -> Option<&[u16]> {
+        self.mount_point.as_deref().map(WideCStr::as_slice)
+    }
+
+    /// Set the UNC name for the network redirector.
+    ///
+    /// The UNC name must not have a NUL.
+    pub fn set_unc_name(&mut self, unc_name: impl AsWide) -> Result<&mut Self, OptionsError> {
+        let unc_name = WideCString::new(unc_name).map_err(OptionsError::InvalidUncName)?;
+
+        // The heap buffer does not move with the string.
+        self.options.UNCName = unc_name.as_ptr();
+        self.unc_name = Some(unc_name);
+        Ok(self)
+    }
+
+    /// Get the UNC name, if set.
+    pub fn unc_name(&self) -> Option<&[u16]> {
+        self.unc_name.as_deref().map(WideCStr::as_slice)
     }
 
     /// Set the option flags
-    pub fn set_option_flags(&mut self, flags: OptionFlags) {
+    pub fn set_option_flags(&mut self, flags: OptionFlags) -> &mut Self {
         self.options.Options = flags.bits();
+        self
+    }
+
+    /// Get the option flags
+    pub fn option_flags(&self) -> OptionFlags {
+        OptionFlags::from_bits_retain(self.options.Options)
+    }
+
+    /// Set the maximum time a request may take before the driver gives up and unmounts.
+    ///
+    /// A zero timeout uses the default of 15 seconds.
+    /// Timeouts too large to fit in milliseconds in a u32 are clamped.
+    pub fn set_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.options.Timeout = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+        self
+    }
+
+    /// Get the request timeout.
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.options.Timeout.into())
+    }
+
+    /// Set the allocation unit size of the volume.
+    ///
+    /// This must be zero to use the default, or a power of two of at least 512.
+    pub fn set_allocation_unit_size(&mut self, size: u32) -> Result<&mut Self, OptionsError> {
+        if !is_valid_unit_size(size) {
+            return Err(OptionsError::InvalidAllocationUnitSize(size));
+        }
+
+        self.options.AllocationUnitSize = size;
+        Ok(self)
+    }
+
+    /// Get the allocation unit size of the volume.
+    pub fn allocation_unit_size(&self) -> u32 {
+        self.options.AllocationUnitSize
+    }
+
+    /// Set the sector size of the volume.
+    ///
+    /// This must be zero to use the default, or a power of two of at least 512.
+    pub fn set_sector_size(&mut self, size: u32) -> Result<&mut Self, OptionsError> {
+        if !is_valid_unit_size(size) {
+            return Err(OptionsError::InvalidSectorSize(size));
+        }
+
+        self.options.SectorSize = size;
+        Ok(self)
+    }
+
+    /// Get the sector size of the volume.
+    pub fn sector_size(&self) -> u32 {
+        self.options.SectorSize
+    }
+
+    /// Set the self-relative security descriptor of the volume.
+    ///
+    /// An empty descriptor disables this option.
+    pub fn set_volume_security_descriptor(
+        &mut self,
+        security_descriptor: &[u8],
+    ) -> Result<&mut Self, OptionsError> {
+        let len = security_descriptor.len();
+        if len > sys::VOLUME_SECURITY_DESCRIPTOR_MAX_SIZE {
+            return Err(OptionsError::SecurityDescriptorTooLarge(len));
+        }
+
+        self.options.VolumeSecurityDescriptor[..len].copy_from_slice(security_descriptor);
+        self.options.VolumeSecurityDescriptor[len..].fill(0);
+        self.options.VolumeSecurityDescriptorLength = len.try_into().unwrap();
+        Ok(self)
+    }
+
+    /// Get the self-relative security descriptor of the volume, if set.
+    pub fn volume_security_descriptor(&self) -> Option<&[u8]> {
+        let len = usize::try_from(self.options.VolumeSecurityDescriptorLength).unwrap();
+        if len == 0 {
+            return None;
+        }
+
+        Some(&self.options.VolumeSecurityDescriptor[..len])
     }
 }
 
@@ -43,3 +180,8 @@ impl Default for Options {
         Self::new()
     }
 }
+
+/// Returns true if `size` can be used as a sector or allocation unit size.
+fn is_valid_unit_size(size: u32) -> bool {
+    size == 0 || (size.is_power_of_two() && size >= MIN_UNIT_SIZE)
+}
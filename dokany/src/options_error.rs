@@ -0,0 +1,37 @@
+use crate::sys;
+use crate::NulError;
+
+/// An error for an invalid option value.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum OptionsError {
+    /// The sector size is not zero or a power of two of at least 512.
+    InvalidSectorSize(u32),
+    /// The allocation unit size is not zero or a power of two of at least 512.
+    InvalidAllocationUnitSize(u32),
+    /// The volume security descriptor is larger than [`sys::VOLUME_SECURITY_DESCRIPTOR_MAX_SIZE`].
+    SecurityDescriptorTooLarge(usize),
+    /// The mount point has a NUL.
+    InvalidMountPoint(NulError),
+    /// The UNC name has a NUL.
+    InvalidUncName(NulError),
+}
+
+impl std::fmt::Display for OptionsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidSectorSize(size) => write!(f, "invalid sector size {size}"),
+            Self::InvalidAllocationUnitSize(size) => {
+                write!(f, "invalid allocation unit size {size}")
+            }
+            Self::SecurityDescriptorTooLarge(len) => write!(
+                f,
+                "volume security descriptor is {len} bytes, but the maximum is {}",
+                sys::VOLUME_SECURITY_DESCRIPTOR_MAX_SIZE
+            ),
+            Self::InvalidMountPoint(error) => write!(f, "invalid mount point: {error}"),
+            Self::InvalidUncName(error) => write!(f, "invalid UNC name: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for OptionsError {}
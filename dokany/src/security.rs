@@ -0,0 +1,40 @@
+//! Security descriptors, without Win32.
+//!
+//! [`SecurityDescriptor`] converts between the self-relative binary format used by Dokan and SDDL strings,
+//! so filesystems can build descriptors on any host, for [`FileSystem::get_file_security`](crate::FileSystem::get_file_security)
+//! or [`Options::set_volume_security_descriptor`](crate::Options::set_volume_security_descriptor).
+
+mod ace;
+mod ace_flags;
+mod ace_type;
+mod acl;
+mod parse_error;
+mod sddl;
+mod security_descriptor;
+mod security_descriptor_control;
+mod sid;
+
+pub use self::ace::Ace;
+pub use self::ace_flags::AceFlags;
+pub use self::ace_type::AceType;
+pub use self::acl::Acl;
+pub use self::parse_error::ParseError;
+pub use self::security_descriptor::SecurityDescriptor;
+pub use self::security_descriptor_control::SecurityDescriptorControl;
+pub use self::sid::Sid;
+
+/// Read a little-endian `u16` at an offset.
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, ParseError> {
+    let bytes = bytes
+        .get(offset..offset + 2)
+        .ok_or(ParseError::UnexpectedEnd)?;
+    Ok(u16::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+/// Read a little-endian `u32` at an offset.
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, ParseError> {
+    let bytes = bytes
+        .get(offset..offset + 4)
+        .ok_or(ParseError::UnexpectedEnd)?;
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
@@ -0,0 +1,71 @@
+use super::AceFlags;
+use super::AceType;
+use super::ParseError;
+use super::Sid;
+use crate::AccessMask;
+
+/// The length of the ACE header and access mask.
+const HEADER_LEN: usize = 8;
+
+/// An access control entry, which allows, denies or audits access by a trustee.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Ace {
+    /// The kind of entry.
+    pub ace_type: AceType,
+    /// The inheritance and auditing flags.
+    pub flags: AceFlags,
+    /// The access rights the entry applies to.
+    ///
+    /// For [`AceType::SystemMandatoryLabel`], this is the label policy instead.
+    pub access_mask: AccessMask,
+    /// The trustee.
+    pub sid: Sid,
+}
+
+impl Ace {
+    /// Make an entry.
+    pub fn new(ace_type: AceType, flags: AceFlags, access_mask: AccessMask, sid: Sid) -> Self {
+        Self {
+            ace_type,
+            flags,
+            access_mask,
+            sid,
+        }
+    }
+
+    /// Get the length of the binary form, in bytes.
+    pub fn binary_len(&self) -> usize {
+        HEADER_LEN + self.sid.binary_len()
+    }
+
+    /// Append the binary form to a buffer.
+    pub(super) fn write(&self, bytes: &mut Vec<u8>) {
+        bytes.push(self.ace_type.into());
+        bytes.push(self.flags.bits());
+        bytes.extend_from_slice(&u16::try_from(self.binary_len()).unwrap().to_le_bytes());
+        bytes.extend_from_slice(&self.access_mask.bits().to_le_bytes());
+        self.sid.write(bytes);
+    }
+
+    /// Parse the binary form at the start of `bytes`.
+    ///
+    /// Returns the entry and its size, which may include padding.
+    pub(super) fn read(bytes: &[u8]) -> Result<(Self, usize), ParseError> {
+        let header = bytes.get(..HEADER_LEN).ok_or(ParseError::UnexpectedEnd)?;
+        let ace_type = AceType::try_from(header[0]).map_err(ParseError::UnsupportedAceType)?;
+        let size = usize::from(super::read_u16(header, 2)?);
+        let bytes = bytes.get(..size).ok_or(ParseError::UnexpectedEnd)?;
+        if size < HEADER_LEN {
+            return Err(ParseError::InvalidSize);
+        }
+
+        let sid = Sid::from_bytes(&bytes[HEADER_LEN..])?;
+        let ace = Self {
+            ace_type,
+            flags: AceFlags::from_bits_retain(header[1]),
+            access_mask: AccessMask::from_bits_retain(super::read_u32(header, 4)?),
+            sid,
+        };
+        Ok((ace, size))
+    }
+}
@@ -0,0 +1,21 @@
+bitflags::bitflags! {
+    /// Inheritance and auditing flags of an access control entry.
+    #[repr(transparent)]
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+    pub struct AceFlags: u8 {
+        /// Files in the directory inherit the ACE.
+        const OBJECT_INHERIT = 0x01;
+        /// Subdirectories of the directory inherit the ACE.
+        const CONTAINER_INHERIT = 0x02;
+        /// Inherited copies of the ACE are not inherited further.
+        const NO_PROPAGATE_INHERIT = 0x04;
+        /// The ACE only applies to children that inherit it, not to the directory itself.
+        const INHERIT_ONLY = 0x08;
+        /// The ACE was inherited from the parent.
+        const INHERITED = 0x10;
+        /// In a SACL, successful access attempts are audited.
+        const SUCCESSFUL_ACCESS = 0x40;
+        /// In a SACL, failed access attempts are audited.
+        const FAILED_ACCESS = 0x80;
+    }
+}
@@ -0,0 +1,43 @@
+/// The kind of an access control entry.
+///
+/// Only ACEs with a plain access mask and SID are supported.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum AceType {
+    /// Allows access to the trustee.
+    AccessAllowed,
+    /// Denies access to the trustee.
+    AccessDenied,
+    /// Logs access attempts by the trustee. This is only valid in a SACL.
+    SystemAudit,
+    /// Raises an alarm on access attempts by the trustee. This is only valid in a SACL.
+    SystemAlarm,
+    /// Sets the integrity level of the object, which is the SID. This is only valid in a SACL.
+    SystemMandatoryLabel,
+}
+
+impl TryFrom<u8> for AceType {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(Self::AccessAllowed),
+            0x01 => Ok(Self::AccessDenied),
+            0x02 => Ok(Self::SystemAudit),
+            0x03 => Ok(Self::SystemAlarm),
+            0x11 => Ok(Self::SystemMandatoryLabel),
+            value => Err(value),
+        }
+    }
+}
+
+impl From<AceType> for u8 {
+    fn from(value: AceType) -> Self {
+        match value {
+            AceType::AccessAllowed => 0x00,
+            AceType::AccessDenied => 0x01,
+            AceType::SystemAudit => 0x02,
+            AceType::SystemAlarm => 0x03,
+            AceType::SystemMandatoryLabel => 0x11,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use super::Ace;
+use super::ParseError;
+
+/// The revision of ACLs with only basic ACEs.
+const ACL_REVISION: u8 = 2;
+
+/// The revision of ACLs that may hold object ACEs.
+const ACL_REVISION_DS: u8 = 4;
+
+/// The length of the ACL header.
+const HEADER_LEN: usize = 8;
+
+/// An access control list.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Acl {
+    /// The entries, in the order they are evaluated.
+    pub aces: Vec<Ace>,
+}
+
+impl Acl {
+    /// Make an empty list, which grants no access when used as a DACL.
+    pub fn new() -> Self {
+        Self { aces: Vec::new() }
+    }
+
+    /// Get the length of the binary form, in bytes.
+    pub fn binary_len(&self) -> usize {
+        HEADER_LEN + self.aces.iter().map(Ace::binary_len).sum::<usize>()
+    }
+
+    /// Append the binary form to a buffer.
+    ///
+    /// # Panics
+    /// Panics if the binary form is larger than 64 KiB.
+    pub(super) fn write(&self, bytes: &mut Vec<u8>) {
+        let size = u16::try_from(self.binary_len()).expect("ACL larger than 64 KiB");
+        let count = u16::try_from(self.aces.len()).unwrap();
+
+        bytes.push(ACL_REVISION);
+        bytes.push(0);
+        bytes.extend_from_slice(&size.to_le_bytes());
+        bytes.extend_from_slice(&count.to_le_bytes());
+        bytes.extend_from_slice(&[0, 0]);
+        for ace in &self.aces {
+            ace.write(bytes);
+        }
+    }
+
+    /// Parse the binary form at the start of `bytes`.
+    pub(super) fn read(bytes: &[u8]) -> Result<Self, ParseError> {
+        let header = bytes.get(..HEADER_LEN).ok_or(ParseError::UnexpectedEnd)?;
+        if header[0] != ACL_REVISION && header[0] != ACL_REVISION_DS {
+            return Err(ParseError::UnsupportedRevision(header[0]));
+        }
+        let size = usize::from(super::read_u16(header, 2)?);
+        let count = super::read_u16(header, 4)?;
+        let bytes = bytes.get(..size).ok_or(ParseError::UnexpectedEnd)?;
+        if size < HEADER_LEN {
+            return Err(ParseError::InvalidSize);
+        }
+
+        let mut offset = HEADER_LEN;
+        let mut aces = Vec::with_capacity(count.into());
+        for _ in 0..count {
+            let (ace, ace_size) = Ace::read(&bytes[offset..])?;
+            aces.push(ace);
+            offset += ace_size;
+        }
+
+        Ok(Self { aces })
+    }
+}
+
+impl Default for Acl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
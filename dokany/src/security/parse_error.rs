@@ -0,0 +1,36 @@
+/// An error from parsing a binary or SDDL security structure.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ParseError {
+    /// The data ends in the middle of a structure.
+    UnexpectedEnd,
+    /// A structure has a size that is too small for its contents.
+    InvalidSize,
+    /// A structure has an unsupported revision.
+    UnsupportedRevision(u8),
+    /// The security descriptor is in absolute format, which holds pointers rather than offsets.
+    NotSelfRelative,
+    /// A SID has more than 15 sub-authorities.
+    TooManySubAuthorities(u8),
+    /// An ACE is of a type not in [`AceType`](super::AceType), such as an object ACE.
+    UnsupportedAceType(u8),
+    /// An SDDL string is invalid at the given byte offset.
+    InvalidSddl(usize),
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "unexpected end of data"),
+            Self::InvalidSize => write!(f, "invalid structure size"),
+            Self::UnsupportedRevision(revision) => write!(f, "unsupported revision {revision}"),
+            Self::NotSelfRelative => write!(f, "the security descriptor is not self-relative"),
+            Self::TooManySubAuthorities(count) => {
+                write!(f, "{count} sub-authorities, but the maximum is 15")
+            }
+            Self::UnsupportedAceType(ace_type) => write!(f, "unsupported ACE type {ace_type:#04x}"),
+            Self::InvalidSddl(position) => write!(f, "invalid SDDL at offset {position}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
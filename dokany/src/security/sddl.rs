@@ -0,0 +1,580 @@
+//! Parsing and formatting of the Security Descriptor Definition Language.
+
+use super::Ace;
+use super::AceFlags;
+use super::AceType;
+use super::Acl;
+use super::ParseError;
+use super::SecurityDescriptor;
+use super::SecurityDescriptorControl;
+use super::Sid;
+use crate::AccessMask;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+/// The maximum number of sub-authorities in a SID.
+const MAX_SUB_AUTHORITIES: usize = 15;
+
+/// SDDL aliases of well-known SIDs, as `(alias, identifier authority, sub-authorities)`.
+///
+/// Aliases relative to a domain or machine are not supported.
+const SID_ALIASES: &[(&str, u64, &[u32])] = &[
+    ("AC", 15, &[2, 1]),
+    ("AN", 5, &[7]),
+    ("AO", 5, &[32, 548]),
+    ("AU", 5, &[11]),
+    ("BA", 5, &[32, 544]),
+    ("BG", 5, &[32, 546]),
+    ("BO", 5, &[32, 551]),
+    ("BU", 5, &[32, 545]),
+    ("CG", 3, &[1]),
+    ("CO", 3, &[0]),
+    ("ER", 5, &[32, 573]),
+    ("HI", 16, &[12288]),
+    ("IU", 5, &[4]),
+    ("LS", 5, &[19]),
+    ("LW", 16, &[4096]),
+    ("ME", 16, &[8192]),
+    ("NO", 5, &[32, 556]),
+    ("NS", 5, &[20]),
+    ("NU", 5, &[2]),
+    ("OW", 3, &[4]),
+    ("PS", 5, &[10]),
+    ("PU", 5, &[32, 547]),
+    ("RC", 5, &[12]),
+    ("RD", 5, &[32, 555]),
+    ("RE", 5, &[32, 552]),
+    ("RU", 5, &[32, 554]),
+    ("SI", 16, &[16384]),
+    ("SO", 5, &[32, 549]),
+    ("SU", 5, &[6]),
+    ("SY", 5, &[18]),
+    ("WD", 1, &[0]),
+    ("WR", 5, &[33]),
+];
+
+/// SDDL strings of ACE types.
+const ACE_TYPES: &[(&str, AceType)] = &[
+    ("A", AceType::AccessAllowed),
+    ("D", AceType::AccessDenied),
+    ("AU", AceType::SystemAudit),
+    ("AL", AceType::SystemAlarm),
+    ("ML", AceType::SystemMandatoryLabel),
+];
+
+/// SDDL strings of ACE flags, in the order they are written.
+const ACE_FLAGS: &[(&str, AceFlags)] = &[
+    ("OI", AceFlags::OBJECT_INHERIT),
+    ("CI", AceFlags::CONTAINER_INHERIT),
+    ("NP", AceFlags::NO_PROPAGATE_INHERIT),
+    ("IO", AceFlags::INHERIT_ONLY),
+    ("ID", AceFlags::INHERITED),
+    ("SA", AceFlags::SUCCESSFUL_ACCESS),
+    ("FA", AceFlags::FAILED_ACCESS),
+];
+
+/// SDDL aliases of combined file and registry rights.
+///
+/// Only the file rights are used when formatting.
+const COMBINED_RIGHTS: &[(&str, u32)] = &[
+    ("FA", 0x001F_01FF),
+    ("FR", 0x0012_0089),
+    ("FW", 0x0012_0116),
+    ("FX", 0x0012_00A0),
+    ("KA", 0x000F_003F),
+    ("KR", 0x0002_0019),
+    ("KW", 0x0002_0006),
+    ("KX", 0x0002_0019),
+];
+
+/// The number of [`COMBINED_RIGHTS`] used when formatting.
+const FILE_COMBINED_RIGHTS: usize = 4;
+
+/// SDDL aliases of single rights, in the order they are written.
+const RIGHTS: &[(&str, u32)] = &[
+    ("GA", 0x1000_0000),
+    ("GR", 0x8000_0000),
+    ("GW", 0x4000_0000),
+    ("GX", 0x2000_0000),
+    ("CC", 0x0000_0001),
+    ("DC", 0x0000_0002),
+    ("LC", 0x0000_0004),
+    ("SW", 0x0000_0008),
+    ("RP", 0x0000_0010),
+    ("WP", 0x0000_0020),
+    ("DT", 0x0000_0040),
+    ("LO", 0x0000_0080),
+    ("CR", 0x0000_0100),
+    ("SD", 0x0001_0000),
+    ("RC", 0x0002_0000),
+    ("WD", 0x0004_0000),
+    ("WO", 0x0008_0000),
+];
+
+/// SDDL aliases of mandatory label policies, which are the rights of [`AceType::SystemMandatoryLabel`] ACEs.
+const LABEL_POLICIES: &[(&str, u32)] = &[("NW", 0x1), ("NR", 0x2), ("NX", 0x4)];
+
+/// Look up a value by its alias.
+fn lookup<T: Copy>(table: &[(&str, T)], alias: &str) -> Option<T> {
+    table
+        .iter()
+        .find(|(name, _)| *name == alias)
+        .map(|&(_, value)| value)
+}
+
+/// A cursor over an SDDL string.
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.rest().is_empty()
+    }
+
+    /// Make an error at the current position.
+    fn error(&self) -> ParseError {
+        ParseError::InvalidSddl(self.position)
+    }
+
+    /// Consume `token` if the rest starts with it.
+    fn eat(&mut self, token: &str) -> bool {
+        if self.rest().starts_with(token) {
+            self.position += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), ParseError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    /// Consume characters while `f` returns true.
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c| !f(c)).unwrap_or(rest.len());
+        self.position += len;
+        &rest[..len]
+    }
+
+    /// Consume a decimal number.
+    fn number(&mut self) -> Result<u64, ParseError> {
+        let start = self.position;
+        self.take_while(|c| c.is_ascii_digit())
+            .parse()
+            .map_err(|_| ParseError::InvalidSddl(start))
+    }
+
+    /// Consume a SID string or alias.
+    fn sid(&mut self) -> Result<Sid, ParseError> {
+        let start = self.position;
+        if !self.eat("S-") {
+            let alias = self.rest().get(..2).ok_or_else(|| self.error())?;
+            let &(_, identifier_authority, sub_authorities) = SID_ALIASES
+                .iter()
+                .find(|(name, _, _)| *name == alias)
+                .ok_or_else(|| self.error())?;
+            self.position += alias.len();
+            return Ok(Sid::new(identifier_authority, sub_authorities));
+        }
+
+        let revision_start = self.position;
+        if self.number()? != 1 {
+            return Err(ParseError::InvalidSddl(revision_start));
+        }
+        self.expect("-")?;
+
+        // Like ConvertStringSidToSid, an authority in hex has exactly 12 digits.
+        let authority_start = self.position;
+        let identifier_authority = if self.eat("0x") || self.eat("0X") {
+            let digits = self.rest().get(..12).ok_or_else(|| self.error())?;
+            self.position += digits.len();
+            u64::from_str_radix(digits, 16).map_err(|_| ParseError::InvalidSddl(authority_start))?
+        } else {
+            self.number()?
+        };
+        if identifier_authority >= 1 << 48 {
+            return Err(ParseError::InvalidSddl(authority_start));
+        }
+
+        let mut sub_authorities = Vec::new();
+        while self.eat("-") {
+            let sub_authority_start = self.position;
+            let sub_authority = u32::try_from(self.number()?)
+                .map_err(|_| ParseError::InvalidSddl(sub_authority_start))?;
+            sub_authorities.push(sub_authority);
+        }
+        if sub_authorities.len() > MAX_SUB_AUTHORITIES {
+            return Err(ParseError::InvalidSddl(start));
+        }
+
+        Ok(Sid::new(identifier_authority, &sub_authorities))
+    }
+
+    /// Consume the flags and ACEs of an ACL.
+    ///
+    /// Returns the control flags for `P`, `AI` and `AR`, and the list, which is `None` if NULL.
+    fn acl(
+        &mut self,
+        protected: SecurityDescriptorControl,
+        auto_inherited: SecurityDescriptorControl,
+        auto_inherit_req: SecurityDescriptorControl,
+    ) -> Result<(SecurityDescriptorControl, Option<Acl>), ParseError> {
+        let mut control = SecurityDescriptorControl::empty();
+        let mut is_null = false;
+        loop {
+            if self.eat("P") {
+                control |= protected;
+            } else if self.eat("AI") {
+                control |= auto_inherited;
+            } else if self.eat("AR") {
+                control |= auto_inherit_req;
+            } else if self.eat("NO_ACCESS_CONTROL") {
+                is_null = true;
+            } else {
+                break;
+            }
+        }
+
+        let mut acl = Acl::new();
+        while self.rest().starts_with('(') {
+            if is_null {
+                return Err(self.error());
+            }
+            self.position += 1;
+            acl.aces.push(self.ace()?);
+            self.expect(")")?;
+        }
+
+        Ok((control, if is_null { None } else { Some(acl) }))
+    }
+
+    /// Consume the fields of an ACE, without the parentheses.
+    fn ace(&mut self) -> Result<Ace, ParseError> {
+        let start = self.position;
+        let ace_type = lookup(ACE_TYPES, self.take_while(|c| c != ';'))
+            .ok_or(ParseError::InvalidSddl(start))?;
+        self.expect(";")?;
+
+        let mut flags = AceFlags::empty();
+        for alias in self.aliases()? {
+            flags |= lookup(ACE_FLAGS, alias).ok_or_else(|| self.alias_error(alias))?;
+        }
+        self.expect(";")?;
+
+        let access_mask = self.rights()?;
+        self.expect(";")?;
+
+        // Object ACEs are not supported, so the object type GUIDs must be empty.
+        self.expect(";")?;
+        self.expect(";")?;
+
+        let sid = self.sid()?;
+
+        Ok(Ace::new(ace_type, flags, access_mask, sid))
+    }
+
+    /// Consume the rights of an ACE, as a number or a sequence of aliases.
+    fn rights(&mut self) -> Result<AccessMask, ParseError> {
+        let start = self.position;
+        let bits = if self.eat("0x") || self.eat("0X") {
+            u32::from_str_radix(self.take_while(|c| c.is_ascii_hexdigit()), 16)
+                .map_err(|_| ParseError::InvalidSddl(start))?
+        } else if self.rest().starts_with(|c: char| c.is_ascii_digit()) {
+            u32::try_from(self.number()?).map_err(|_| ParseError::InvalidSddl(start))?
+        } else {
+            let mut bits = 0;
+            for alias in self.aliases()? {
+                bits |= lookup(COMBINED_RIGHTS, alias)
+                    .or_else(|| lookup(RIGHTS, alias))
+                    .or_else(|| lookup(LABEL_POLICIES, alias))
+                    .ok_or_else(|| self.alias_error(alias))?;
+            }
+            bits
+        };
+        Ok(AccessMask::from_bits_retain(bits))
+    }
+
+    /// Consume a sequence of two letter aliases, up to the next `;`.
+    fn aliases(&mut self) -> Result<Vec<&'a str>, ParseError> {
+        let start = self.position;
+        let field = self.take_while(|c| c != ';' && c != ')');
+        if field.len() % 2 != 0 || !field.is_ascii() {
+            return Err(ParseError::InvalidSddl(start));
+        }
+        Ok((0..field.len())
+            .step_by(2)
+            .map(|i| &field[i..i + 2])
+            .collect())
+    }
+
+    /// Make an error at the position of an alias returned by [`Self::aliases`].
+    fn alias_error(&self, alias: &str) -> ParseError {
+        ParseError::InvalidSddl(alias.as_ptr() as usize - self.input.as_ptr() as usize)
+    }
+}
+
+/// Parse a SID string like `S-1-5-18`, or an alias like `SY`.
+pub(super) fn parse_sid(input: &str) -> Result<Sid, ParseError> {
+    let mut parser = Parser::new(input);
+    let sid = parser.sid()?;
+    if !parser.is_at_end() {
+        return Err(parser.error());
+    }
+    Ok(sid)
+}
+
+/// Parse an SDDL security descriptor string.
+pub(super) fn parse_security_descriptor(input: &str) -> Result<SecurityDescriptor, ParseError> {
+    let mut parser = Parser::new(input);
+    let mut descriptor = SecurityDescriptor::new();
+
+    while !parser.is_at_end() {
+        let start = parser.position;
+        let duplicate = ParseError::InvalidSddl(start);
+        if parser.eat("O:") {
+            if descriptor.owner.is_some() {
+                return Err(duplicate);
+            }
+            descriptor.owner = Some(parser.sid()?);
+        } else if parser.eat("G:") {
+            if descriptor.group.is_some() {
+                return Err(duplicate);
+            }
+            descriptor.group = Some(parser.sid()?);
+        } else if parser.eat("D:") {
+            if descriptor
+                .control
+                .contains(SecurityDescriptorControl::DACL_PRESENT)
+            {
+                return Err(duplicate);
+            }
+            let (control, dacl) = parser.acl(
+                SecurityDescriptorControl::DACL_PROTECTED,
+                SecurityDescriptorControl::DACL_AUTO_INHERITED,
+                SecurityDescriptorControl::DACL_AUTO_INHERIT_REQ,
+            )?;
+            descriptor.control |= control | SecurityDescriptorControl::DACL_PRESENT;
+            descriptor.dacl = dacl;
+        } else if parser.eat("S:") {
+            if descriptor
+                .control
+                .contains(SecurityDescriptorControl::SACL_PRESENT)
+            {
+                return Err(duplicate);
+            }
+            let (control, sacl) = parser.acl(
+                SecurityDescriptorControl::SACL_PROTECTED,
+                SecurityDescriptorControl::SACL_AUTO_INHERITED,
+                SecurityDescriptorControl::SACL_AUTO_INHERIT_REQ,
+            )?;
+            descriptor.control |= control | SecurityDescriptorControl::SACL_PRESENT;
+            descriptor.sacl = sacl;
+        } else {
+            return Err(parser.error());
+        }
+    }
+
+    Ok(descriptor)
+}
+
+/// Write a SID, using its alias if it has one.
+fn write_sid(f: &mut Formatter<'_>, sid: &Sid) -> FmtResult {
+    let alias = SID_ALIASES
+        .iter()
+        .find(|(_, identifier_authority, sub_authorities)| {
+            *identifier_authority == sid.identifier_authority()
+                && *sub_authorities == sid.sub_authorities()
+        });
+    match alias {
+        Some((alias, _, _)) => f.write_str(alias),
+        None => write!(f, "{sid}"),
+    }
+}
+
+/// Write the rights of an ACE, as aliases if they cover every bit, or in hex otherwise.
+fn write_rights(f: &mut Formatter<'_>, ace_type: AceType, access_mask: AccessMask) -> FmtResult {
+    let bits = access_mask.bits();
+    let aliases = if ace_type == AceType::SystemMandatoryLabel {
+        LABEL_POLICIES
+    } else {
+        if let Some((alias, _)) = COMBINED_RIGHTS[..FILE_COMBINED_RIGHTS]
+            .iter()
+            .find(|&&(_, value)| value == bits)
+        {
+            return f.write_str(alias);
+        }
+        RIGHTS
+    };
+
+    let covered = aliases
+        .iter()
+        .filter(|&&(_, value)| bits & value != 0)
+        .fold(0, |covered, &(_, value)| covered | value);
+    if covered != bits {
+        return write!(f, "{bits:#x}");
+    }
+    for (alias, _) in aliases.iter().filter(|&&(_, value)| bits & value != 0) {
+        f.write_str(alias)?;
+    }
+    Ok(())
+}
+
+/// Write an ACE, with its parentheses.
+fn write_ace(f: &mut Formatter<'_>, ace: &Ace) -> FmtResult {
+    let (ace_type, _) = ACE_TYPES
+        .iter()
+        .find(|&&(_, ace_type)| ace_type == ace.ace_type)
+        .unwrap();
+    write!(f, "({ace_type};")?;
+    for (alias, _) in ACE_FLAGS
+        .iter()
+        .filter(|(_, flag)| ace.flags.contains(*flag))
+    {
+        f.write_str(alias)?;
+    }
+    f.write_str(";")?;
+    write_rights(f, ace.ace_type, ace.access_mask)?;
+    f.write_str(";;;")?;
+    write_sid(f, &ace.sid)?;
+    f.write_str(")")
+}
+
+/// Write the flags and ACEs of an ACL.
+fn write_acl(
+    f: &mut Formatter<'_>,
+    control: SecurityDescriptorControl,
+    flags: [(&str, SecurityDescriptorControl); 3],
+    acl: Option<&Acl>,
+) -> FmtResult {
+    for (alias, flag) in flags {
+        if control.contains(flag) {
+            f.write_str(alias)?;
+        }
+    }
+    match acl {
+        Some(acl) => acl.aces.iter().try_for_each(|ace| write_ace(f, ace)),
+        None => f.write_str("NO_ACCESS_CONTROL"),
+    }
+}
+
+/// Write a security descriptor as SDDL.
+pub(super) fn write_security_descriptor(
+    f: &mut Formatter<'_>,
+    descriptor: &SecurityDescriptor,
+) -> FmtResult {
+    let control = descriptor.control;
+    if let Some(owner) = &descriptor.owner {
+        f.write_str("O:")?;
+        write_sid(f, owner)?;
+    }
+    if let Some(group) = &descriptor.group {
+        f.write_str("G:")?;
+        write_sid(f, group)?;
+    }
+    if descriptor.dacl.is_some() || control.contains(SecurityDescriptorControl::DACL_PRESENT) {
+        f.write_str("D:")?;
+        write_acl(
+            f,
+            control,
+            [
+                ("P", SecurityDescriptorControl::DACL_PROTECTED),
+                ("AR", SecurityDescriptorControl::DACL_AUTO_INHERIT_REQ),
+                ("AI", SecurityDescriptorControl::DACL_AUTO_INHERITED),
+            ],
+            descriptor.dacl.as_ref(),
+        )?;
+    }
+    if descriptor.sacl.is_some() || control.contains(SecurityDescriptorControl::SACL_PRESENT) {
+        f.write_str("S:")?;
+        write_acl(
+            f,
+            control,
+            [
+                ("P", SecurityDescriptorControl::SACL_PROTECTED),
+                ("AR", SecurityDescriptorControl::SACL_AUTO_INHERIT_REQ),
+                ("AI", SecurityDescriptorControl::SACL_AUTO_INHERITED),
+            ],
+            descriptor.sacl.as_ref(),
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for sddl in [
+            "O:BAG:SYD:(A;;FA;;;WD)",
+            "O:S-1-5-21-1-2-3-1001G:S-1-5-21-1-2-3-513D:PAI(A;OICIID;FA;;;SY)(D;;FW;;;AN)",
+            "D:(A;CIIO;GAGR;;;CO)(A;;0x1200a9;;;BU)(A;;CCDCRPWPSDRCWDWO;;;AU)",
+            "S:AI(AU;SAFA;FA;;;WD)(ML;;NWNR;;;HI)",
+            "D:",
+            "",
+        ] {
+            let descriptor: SecurityDescriptor = sddl.parse().unwrap();
+            assert_eq!(descriptor.to_string(), sddl);
+            assert_eq!(
+                SecurityDescriptor::from_bytes(&descriptor.to_bytes()).unwrap(),
+                descriptor
+            );
+        }
+    }
+
+    #[test]
+    fn rights() {
+        let descriptor: SecurityDescriptor = "D:(A;;0x1F01FF;;;WD)(A;;2032127;;;WD)(A;;KR;;;WD)"
+            .parse()
+            .unwrap();
+        let aces = &descriptor.dacl.as_ref().unwrap().aces;
+        assert_eq!(aces[0].access_mask, AccessMask::FILE_ALL_ACCESS);
+        assert_eq!(aces[1].access_mask, AccessMask::FILE_ALL_ACCESS);
+        assert_eq!(aces[2].access_mask.bits(), 0x20019);
+        assert_eq!(
+            descriptor.to_string(),
+            "D:(A;;FA;;;WD)(A;;FA;;;WD)(A;;CCSWRPRC;;;WD)"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        for (sddl, position) in [
+            ("X:BA", 0),
+            ("O:XX", 2),
+            ("O:BAO:BA", 4),
+            ("D:(B;;FA;;;WD)", 3),
+            ("D:(A;XX;FA;;;WD)", 5),
+            ("D:(A;;FA;;;WD", 13),
+            ("D:(A;;FAQ;;;WD)", 6),
+            ("D:(A;;FAQQ;;;WD)", 8),
+            ("D:(OA;;FA;guid;;WD)", 3),
+            ("D:(A;;FA;;;WD;attribute)", 13),
+            ("D:NO_ACCESS_CONTROL(A;;FA;;;WD)", 19),
+            ("O:S-1-5-99999999999", 8),
+        ] {
+            assert_eq!(
+                sddl.parse::<SecurityDescriptor>(),
+                Err(ParseError::InvalidSddl(position)),
+                "{sddl}"
+            );
+        }
+    }
+}
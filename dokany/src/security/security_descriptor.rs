@@ -0,0 +1,460 @@
+use super::sddl;
+use super::AceType;
+use super::Acl;
+use super::ParseError;
+use super::SecurityDescriptorControl;
+use super::Sid;
+use crate::SecurityInformation;
+
+/// The only security descriptor revision.
+const SECURITY_DESCRIPTOR_REVISION: u8 = 1;
+
+/// The length of the self-relative header.
+const HEADER_LEN: usize = 20;
+
+/// Control flags that describe the DACL.
+const DACL_CONTROL: SecurityDescriptorControl = SecurityDescriptorControl::DACL_PRESENT
+    .union(SecurityDescriptorControl::DACL_DEFAULTED)
+    .union(SecurityDescriptorControl::DACL_AUTO_INHERIT_REQ)
+    .union(SecurityDescriptorControl::DACL_AUTO_INHERITED)
+    .union(SecurityDescriptorControl::DACL_PROTECTED);
+
+/// Control flags that describe the SACL.
+const SACL_CONTROL: SecurityDescriptorControl = SecurityDescriptorControl::SACL_PRESENT
+    .union(SecurityDescriptorControl::SACL_DEFAULTED)
+    .union(SecurityDescriptorControl::SACL_AUTO_INHERIT_REQ)
+    .union(SecurityDescriptorControl::SACL_AUTO_INHERITED)
+    .union(SecurityDescriptorControl::SACL_PROTECTED);
+
+/// A security descriptor, which holds the owner, group and access control lists of an object.
+///
+/// This displays and parses as SDDL, like `O:BAG:SYD:(A;;FA;;;WD)`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct SecurityDescriptor {
+    /// Flags that qualify the other parts.
+    ///
+    /// [`SecurityDescriptorControl::DACL_PRESENT`] and [`SecurityDescriptorControl::SACL_PRESENT`]
+    /// are implied when the matching list is `Some`.
+    pub control: SecurityDescriptorControl,
+    /// The owner.
+    pub owner: Option<Sid>,
+    /// The primary group.
+    pub group: Option<Sid>,
+    /// The system ACL, which controls auditing.
+    pub sacl: Option<Acl>,
+    /// The discretionary ACL, which controls access.
+    ///
+    /// If this is `None` but [`SecurityDescriptorControl::DACL_PRESENT`] is set,
+    /// the DACL is NULL, which grants all access to everyone.
+    pub dacl: Option<Acl>,
+}
+
+impl SecurityDescriptor {
+    /// Make an empty descriptor.
+    pub fn new() -> Self {
+        Self {
+            control: SecurityDescriptorControl::empty(),
+            owner: None,
+            group: None,
+            sacl: None,
+            dacl: None,
+        }
+    }
+
+    /// Get the control flags as they are written, with the presence flags implied by the lists.
+    fn effective_control(&self) -> SecurityDescriptorControl {
+        let mut control = self.control | SecurityDescriptorControl::SELF_RELATIVE;
+        if self.dacl.is_some() {
+            control |= SecurityDescriptorControl::DACL_PRESENT;
+        }
+        if self.sacl.is_some() {
+            control |= SecurityDescriptorControl::SACL_PRESENT;
+        }
+        control
+    }
+
+    /// Get the self-relative binary form.
+    ///
+    /// # Panics
+    /// Panics if an ACL is larger than 64 KiB.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0; HEADER_LEN];
+        bytes[0] = SECURITY_DESCRIPTOR_REVISION;
+        bytes[2..4].copy_from_slice(&self.effective_control().bits().to_le_bytes());
+
+        // Like Windows, the lists are written first.
+        let mut write_part = |header_offset: usize, write: &dyn Fn(&mut Vec<u8>)| {
+            let offset = u32::try_from(bytes.len()).unwrap();
+            bytes[header_offset..header_offset + 4].copy_from_slice(&offset.to_le_bytes());
+            write(&mut bytes);
+        };
+        if let Some(sacl) = &self.sacl {
+            write_part(12, &|bytes| sacl.write(bytes));
+        }
+        if let Some(dacl) = &self.dacl {
+            write_part(16, &|bytes| dacl.write(bytes));
+        }
+        if let Some(owner) = &self.owner {
+            write_part(4, &|bytes| owner.write(bytes));
+        }
+        if let Some(group) = &self.group {
+            write_part(8, &|bytes| group.write(bytes));
+        }
+
+        bytes
+    }
+
+    /// Parse the self-relative binary form.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
+        let header = bytes.get(..HEADER_LEN).ok_or(ParseError::UnexpectedEnd)?;
+        if header[0] != SECURITY_DESCRIPTOR_REVISION {
+            return Err(ParseError::UnsupportedRevision(header[0]));
+        }
+        let control = SecurityDescriptorControl::from_bits_retain(super::read_u16(header, 2)?);
+        if !control.contains(SecurityDescriptorControl::SELF_RELATIVE) {
+            return Err(ParseError::NotSelfRelative);
+        }
+
+        // An offset of zero means the part is absent, or NULL for a present list.
+        let part = |header_offset: usize| -> Result<Option<&[u8]>, ParseError> {
+            let offset = usize::try_from(super::read_u32(header, header_offset)?).unwrap();
+            if offset == 0 {
+                return Ok(None);
+            }
+            bytes
+                .get(offset..)
+                .map(Some)
+                .ok_or(ParseError::UnexpectedEnd)
+        };
+        let owner = part(4)?.map(Sid::from_bytes).transpose()?;
+        let group = part(8)?.map(Sid::from_bytes).transpose()?;
+        let sacl = match part(12)? {
+            Some(bytes) if control.contains(SecurityDescriptorControl::SACL_PRESENT) => {
+                Some(Acl::read(bytes)?)
+            }
+            _ => None,
+        };
+        let dacl = match part(16)? {
+            Some(bytes) if control.contains(SecurityDescriptorControl::DACL_PRESENT) => {
+                Some(Acl::read(bytes)?)
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            control: control - SecurityDescriptorControl::SELF_RELATIVE,
+            owner,
+            group,
+            sacl,
+            dacl,
+        })
+    }
+
+    /// Get the length of the self-relative descriptor at the start of `bytes`.
+    ///
+    /// Only the header and the sizes of the parts are read, so the parts are not validated.
+    /// If `bytes` is too short to hold every size, a lower bound longer than `bytes` is returned instead,
+    /// so a caller reading from a pointer can retry with that many bytes until the result fits.
+    /// A part that would end beyond the address space is an invalid size.
+    pub fn self_relative_len(bytes: &[u8]) -> Result<usize, ParseError> {
+        // SIDs and ACLs both start with an 8 byte header that holds their size.
+        const PART_HEADER_LEN: usize = 8;
+
+        let Some(header) = bytes.get(..HEADER_LEN) else {
+            return Ok(HEADER_LEN);
+        };
+        let control = SecurityDescriptorControl::from_bits_retain(super::read_u16(header, 2)?);
+        if !control.contains(SecurityDescriptorControl::SELF_RELATIVE) {
+            return Err(ParseError::NotSelfRelative);
+        }
+
+        let mut len = HEADER_LEN;
+        let mut extend = |header_offset: usize,
+                          present: bool,
+                          part_len: fn(&[u8]) -> usize|
+         -> Result<(), ParseError> {
+            let offset = usize::try_from(super::read_u32(header, header_offset)?).unwrap();
+            if !present || offset == 0 {
+                return Ok(());
+            }
+            let part_header_end = offset
+                .checked_add(PART_HEADER_LEN)
+                .ok_or(ParseError::InvalidSize)?;
+            let part_len = bytes
+                .get(offset..part_header_end)
+                .map_or(PART_HEADER_LEN, part_len);
+            let part_end = offset
+                .checked_add(part_len)
+                .ok_or(ParseError::InvalidSize)?;
+            len = len.max(part_end);
+            Ok(())
+        };
+
+        let sid_len = |part: &[u8]| PART_HEADER_LEN + 4 * usize::from(part[1]);
+        let acl_len = |part: &[u8]| usize::from(u16::from_le_bytes([part[2], part[3]]));
+        extend(4, true, sid_len)?;
+        extend(8, true, sid_len)?;
+        extend(
+            12,
+            control.contains(SecurityDescriptorControl::SACL_PRESENT),
+            acl_len,
+        )?;
+        extend(
+            16,
+            control.contains(SecurityDescriptorControl::DACL_PRESENT),
+            acl_len,
+        )?;
+
+        Ok(len)
+    }
+
+    /// Get a copy with only the parts selected by `security_information`, like `GetFileSecurity` returns.
+    ///
+    /// [`SecurityInformation::LABEL`] without [`SecurityInformation::SACL`] selects only the mandatory label ACEs.
+    pub fn filtered(&self, security_information: SecurityInformation) -> Self {
+        let mut filtered = Self::new();
+        if security_information.contains(SecurityInformation::OWNER) {
+            filtered.owner = self.owner.clone();
+            filtered.control |= self.control & SecurityDescriptorControl::OWNER_DEFAULTED;
+        }
+        if security_information.contains(SecurityInformation::GROUP) {
+            filtered.group = self.group.clone();
+            filtered.control |= self.control & SecurityDescriptorControl::GROUP_DEFAULTED;
+        }
+        if security_information.contains(SecurityInformation::DACL) {
+            filtered.dacl = self.dacl.clone();
+            filtered.control |= self.control & DACL_CONTROL;
+        }
+        if security_information.contains(SecurityInformation::SACL) {
+            filtered.sacl = self.sacl.clone();
+            filtered.control |= self.control & SACL_CONTROL;
+        } else if security_information.contains(SecurityInformation::LABEL) {
+            filtered.sacl = Some(Acl {
+                aces: self.label_aces().cloned().collect(),
+            });
+        }
+        filtered
+    }
+
+    /// Replace the parts selected by `security_information` with those of `other`, like `SetFileSecurity` does.
+    ///
+    /// [`SecurityInformation::LABEL`] without [`SecurityInformation::SACL`] replaces only the mandatory label ACEs.
+    pub fn merge(&mut self, other: &Self, security_information: SecurityInformation) {
+        let mut replace_control = |mask: SecurityDescriptorControl| {
+            self.control = (self.control - mask) | (other.control & mask);
+        };
+        if security_information.contains(SecurityInformation::OWNER) {
+            replace_control(SecurityDescriptorControl::OWNER_DEFAULTED);
+            self.owner = other.owner.clone();
+        }
+        if security_information.contains(SecurityInformation::GROUP) {
+            replace_control(SecurityDescriptorControl::GROUP_DEFAULTED);
+            self.group = other.group.clone();
+        }
+        if security_information.contains(SecurityInformation::DACL) {
+            replace_control(DACL_CONTROL);
+            self.dacl = other.dacl.clone();
+        }
+        if security_information.contains(SecurityInformation::SACL) {
+            replace_control(SACL_CONTROL);
+            self.sacl = other.sacl.clone();
+        } else if security_information.contains(SecurityInformation::LABEL) {
+            let labels: Vec<_> = other.label_aces().cloned().collect();
+            let sacl = self.sacl.get_or_insert_with(Acl::new);
+            sacl.aces
+                .retain(|ace| ace.ace_type != AceType::SystemMandatoryLabel);
+            sacl.aces.extend(labels);
+        }
+
+        if security_information.contains(SecurityInformation::PROTECTED_DACL) {
+            self.control |= SecurityDescriptorControl::DACL_PROTECTED;
+        } else if security_information.contains(SecurityInformation::UNPROTECTED_DACL) {
+            self.control -= SecurityDescriptorControl::DACL_PROTECTED;
+        }
+        if security_information.contains(SecurityInformation::PROTECTED_SACL) {
+            self.control |= SecurityDescriptorControl::SACL_PROTECTED;
+        } else if security_information.contains(SecurityInformation::UNPROTECTED_SACL) {
+            self.control -= SecurityDescriptorControl::SACL_PROTECTED;
+        }
+    }
+
+    /// Get the mandatory label ACEs of the SACL.
+    fn label_aces(&self) -> impl Iterator<Item = &super::Ace> {
+        self.sacl
+            .iter()
+            .flat_map(|sacl| &sacl.aces)
+            .filter(|ace| ace.ace_type == AceType::SystemMandatoryLabel)
+    }
+}
+
+impl Default for SecurityDescriptor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for SecurityDescriptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        sddl::write_security_descriptor(f, self)
+    }
+}
+
+impl std::str::FromStr for SecurityDescriptor {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        sddl::parse_security_descriptor(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::security::Ace;
+    use crate::security::AceFlags;
+    use crate::AccessMask;
+
+    /// `O:BAG:SYD:(A;;FA;;;WD)` in self-relative form.
+    const DESCRIPTOR: &[u8] = &[
+        1, 0, 0x04, 0x80, 48, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0, 20, 0, 0, 0, // header
+        2, 0, 28, 0, 1, 0, 0, 0, // DACL header
+        0, 0, 20, 0, 0xFF, 0x01, 0x1F, 0, 1, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, // (A;;FA;;;WD)
+        1, 2, 0, 0, 0, 0, 0, 5, 32, 0, 0, 0, 0x20, 0x02, 0, 0, // BA
+        1, 1, 0, 0, 0, 0, 0, 5, 18, 0, 0, 0, // SY
+    ];
+
+    #[test]
+    fn binary() {
+        let descriptor = SecurityDescriptor {
+            control: SecurityDescriptorControl::empty(),
+            owner: Some(Sid::new(5, &[32, 544])),
+            group: Some(Sid::new(5, &[18])),
+            sacl: None,
+            dacl: Some(Acl {
+                aces: vec![Ace::new(
+                    AceType::AccessAllowed,
+                    AceFlags::empty(),
+                    AccessMask::FILE_ALL_ACCESS,
+                    Sid::new(1, &[0]),
+                )],
+            }),
+        };
+        assert_eq!(descriptor.to_bytes(), DESCRIPTOR);
+
+        let parsed = SecurityDescriptor::from_bytes(DESCRIPTOR).unwrap();
+        assert_eq!(
+            parsed.control,
+            SecurityDescriptorControl::DACL_PRESENT,
+            "the presence flag is kept"
+        );
+        assert_eq!(parsed.to_bytes(), DESCRIPTOR);
+        assert_eq!(parsed.to_string(), "O:BAG:SYD:(A;;FA;;;WD)");
+
+        assert_eq!(
+            SecurityDescriptor::from_bytes(&DESCRIPTOR[..70]),
+            Err(ParseError::UnexpectedEnd)
+        );
+        let mut absolute = DESCRIPTOR.to_vec();
+        absolute[3] = 0;
+        assert_eq!(
+            SecurityDescriptor::from_bytes(&absolute),
+            Err(ParseError::NotSelfRelative)
+        );
+        assert_eq!(
+            SecurityDescriptor::self_relative_len(&absolute),
+            Err(ParseError::NotSelfRelative)
+        );
+    }
+
+    #[test]
+    fn self_relative_len() {
+        let mut bytes = DESCRIPTOR.to_vec();
+        bytes.extend_from_slice(&[0xFF; 16]);
+        assert_eq!(
+            SecurityDescriptor::self_relative_len(&bytes),
+            Ok(DESCRIPTOR.len())
+        );
+
+        // Growing a prefix by the returned bound finds the length without reading past it.
+        let mut len = 0;
+        let mut steps = Vec::new();
+        loop {
+            let needed = SecurityDescriptor::self_relative_len(&DESCRIPTOR[..len]).unwrap();
+            if needed <= len {
+                break;
+            }
+            steps.push(needed);
+            len = needed;
+        }
+        assert_eq!(steps, [20, 72, DESCRIPTOR.len()]);
+
+        // Offsets at the end of the address space are rejected on 32-bit targets, rather than overflowing.
+        let mut header = DESCRIPTOR[..20].to_vec();
+        header[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+        let expected = usize::try_from(u32::MAX)
+            .unwrap()
+            .checked_add(8)
+            .ok_or(ParseError::InvalidSize);
+        assert_eq!(SecurityDescriptor::self_relative_len(&header), expected);
+
+        // A NULL DACL has no size.
+        let null_dacl: SecurityDescriptor = "O:WDD:NO_ACCESS_CONTROL".parse().unwrap();
+        let null_dacl = null_dacl.to_bytes();
+        assert_eq!(
+            SecurityDescriptor::self_relative_len(&null_dacl),
+            Ok(null_dacl.len())
+        );
+    }
+
+    #[test]
+    fn null_dacl() {
+        let descriptor: SecurityDescriptor = "D:NO_ACCESS_CONTROL".parse().unwrap();
+        assert!(descriptor.dacl.is_none());
+        assert!(descriptor
+            .control
+            .contains(SecurityDescriptorControl::DACL_PRESENT));
+
+        let bytes = descriptor.to_bytes();
+        assert_eq!(
+            bytes,
+            [1, 0, 0x04, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(SecurityDescriptor::from_bytes(&bytes).unwrap(), descriptor);
+        assert_eq!(descriptor.to_string(), "D:NO_ACCESS_CONTROL");
+    }
+
+    #[test]
+    fn filtered_and_merge() {
+        let mut descriptor: SecurityDescriptor =
+            "O:BAG:SYD:PAI(A;OICI;FA;;;SY)S:(AU;SA;FW;;;WD)(ML;;NW;;;LW)"
+                .parse()
+                .unwrap();
+
+        assert_eq!(
+            descriptor
+                .filtered(SecurityInformation::OWNER | SecurityInformation::DACL)
+                .to_string(),
+            "O:BAD:PAI(A;OICI;FA;;;SY)"
+        );
+        assert_eq!(
+            descriptor.filtered(SecurityInformation::LABEL).to_string(),
+            "S:(ML;;NW;;;LW)"
+        );
+
+        let other: SecurityDescriptor = "O:SYG:BAD:(A;;FR;;;BU)S:(ML;;NW;;;HI)".parse().unwrap();
+        descriptor.merge(
+            &other,
+            SecurityInformation::DACL | SecurityInformation::LABEL,
+        );
+        assert_eq!(
+            descriptor.to_string(),
+            "O:BAG:SYD:(A;;FR;;;BU)S:(AU;SA;FW;;;WD)(ML;;NW;;;HI)"
+        );
+
+        descriptor.merge(&other, SecurityInformation::PROTECTED_DACL);
+        assert_eq!(
+            descriptor.filtered(SecurityInformation::DACL).to_string(),
+            "D:P(A;;FR;;;BU)"
+        );
+    }
+}
@@ -0,0 +1,33 @@
+bitflags::bitflags! {
+    /// Flags that qualify the parts of a security descriptor.
+    #[repr(transparent)]
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+    pub struct SecurityDescriptorControl: u16 {
+        /// The owner was set by a default mechanism.
+        const OWNER_DEFAULTED = 0x0001;
+        /// The group was set by a default mechanism.
+        const GROUP_DEFAULTED = 0x0002;
+        /// The descriptor has a DACL, which may be NULL.
+        const DACL_PRESENT = 0x0004;
+        /// The DACL was set by a default mechanism.
+        const DACL_DEFAULTED = 0x0008;
+        /// The descriptor has a SACL, which may be NULL.
+        const SACL_PRESENT = 0x0010;
+        /// The SACL was set by a default mechanism.
+        const SACL_DEFAULTED = 0x0020;
+        /// The DACL should be inherited by new children of the object.
+        const DACL_AUTO_INHERIT_REQ = 0x0100;
+        /// The SACL should be inherited by new children of the object.
+        const SACL_AUTO_INHERIT_REQ = 0x0200;
+        /// The DACL was set up to support automatic propagation of inherited ACEs.
+        const DACL_AUTO_INHERITED = 0x0400;
+        /// The SACL was set up to support automatic propagation of inherited ACEs.
+        const SACL_AUTO_INHERITED = 0x0800;
+        /// The DACL cannot be modified by inheritable ACEs.
+        const DACL_PROTECTED = 0x1000;
+        /// The SACL cannot be modified by inheritable ACEs.
+        const SACL_PROTECTED = 0x2000;
+        /// The descriptor is in self-relative format.
+        const SELF_RELATIVE = 0x8000;
+    }
+}
@@ -0,0 +1,163 @@
+use super::sddl;
+use super::ParseError;
+
+/// The only SID revision.
+const SID_REVISION: u8 = 1;
+
+/// The maximum number of sub-authorities in a SID.
+const MAX_SUB_AUTHORITIES: usize = 15;
+
+/// The largest identifier authority, which is 48 bits.
+const MAX_IDENTIFIER_AUTHORITY: u64 = (1 << 48) - 1;
+
+/// A security identifier, which identifies a user, group or other trustee.
+///
+/// This displays and parses as a string like `S-1-5-32-544`.
+/// Parsing also accepts SDDL aliases like `BA`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Sid {
+    identifier_authority: u64,
+    sub_authorities: Vec<u32>,
+}
+
+impl Sid {
+    /// Make a SID from its identifier authority and sub-authorities.
+    ///
+    /// # Panics
+    /// Panics if the identifier authority is larger than 48 bits, or there are more than 15 sub-authorities.
+    pub fn new(identifier_authority: u64, sub_authorities: &[u32]) -> Self {
+        assert!(
+            identifier_authority <= MAX_IDENTIFIER_AUTHORITY,
+            "identifier authority is larger than 48 bits"
+        );
+        assert!(
+            sub_authorities.len() <= MAX_SUB_AUTHORITIES,
+            "too many sub-authorities"
+        );
+
+        Self {
+            identifier_authority,
+            sub_authorities: sub_authorities.to_vec(),
+        }
+    }
+
+    /// Get the identifier authority.
+    pub fn identifier_authority(&self) -> u64 {
+        self.identifier_authority
+    }
+
+    /// Get the sub-authorities, the last of which is the relative identifier.
+    pub fn sub_authorities(&self) -> &[u32] {
+        &self.sub_authorities
+    }
+
+    /// Get the length of the binary form, in bytes.
+    pub fn binary_len(&self) -> usize {
+        8 + 4 * self.sub_authorities.len()
+    }
+
+    /// Get the binary form.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.binary_len());
+        self.write(&mut bytes);
+        bytes
+    }
+
+    /// Parse the binary form at the start of `bytes`.
+    ///
+    /// Like the Win32 functions that take a SID, this ignores anything after it.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
+        let header = bytes.get(..8).ok_or(ParseError::UnexpectedEnd)?;
+        if header[0] != SID_REVISION {
+            return Err(ParseError::UnsupportedRevision(header[0]));
+        }
+        let count = header[1];
+        if usize::from(count) > MAX_SUB_AUTHORITIES {
+            return Err(ParseError::TooManySubAuthorities(count));
+        }
+        let identifier_authority = header[2..8]
+            .iter()
+            .fold(0, |authority, &byte| (authority << 8) | u64::from(byte));
+
+        let sub_authorities = (0..usize::from(count))
+            .map(|i| super::read_u32(bytes, 8 + 4 * i))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            identifier_authority,
+            sub_authorities,
+        })
+    }
+
+    /// Append the binary form to a buffer.
+    pub(super) fn write(&self, bytes: &mut Vec<u8>) {
+        bytes.push(SID_REVISION);
+        bytes.push(self.sub_authorities.len().try_into().unwrap());
+        bytes.extend_from_slice(&self.identifier_authority.to_be_bytes()[2..]);
+        for sub_authority in &self.sub_authorities {
+            bytes.extend_from_slice(&sub_authority.to_le_bytes());
+        }
+    }
+}
+
+impl std::fmt::Display for Sid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Like ConvertSidToStringSid, large authorities are written in hex.
+        if self.identifier_authority > u64::from(u32::MAX) {
+            write!(f, "S-1-{:#014X}", self.identifier_authority)?;
+        } else {
+            write!(f, "S-1-{}", self.identifier_authority)?;
+        }
+        for sub_authority in &self.sub_authorities {
+            write!(f, "-{sub_authority}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Sid {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        sddl::parse_sid(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn binary() {
+        let sid = Sid::new(5, &[32, 544]);
+        let bytes = [1, 2, 0, 0, 0, 0, 0, 5, 32, 0, 0, 0, 0x20, 0x02, 0, 0];
+        assert_eq!(sid.to_bytes(), bytes);
+        assert_eq!(sid.binary_len(), bytes.len());
+        assert_eq!(Sid::from_bytes(&bytes).unwrap(), sid);
+
+        assert_eq!(
+            Sid::from_bytes(&bytes[..12]),
+            Err(ParseError::UnexpectedEnd)
+        );
+        assert_eq!(
+            Sid::from_bytes(&[2, 0, 0, 0, 0, 0, 0, 5]),
+            Err(ParseError::UnsupportedRevision(2))
+        );
+    }
+
+    #[test]
+    fn string() {
+        let sid: Sid = "S-1-5-32-544".parse().unwrap();
+        assert_eq!(sid, Sid::new(5, &[32, 544]));
+        assert_eq!(sid.to_string(), "S-1-5-32-544");
+        assert_eq!("BA".parse::<Sid>().unwrap(), sid);
+
+        let sid = Sid::new(0x1234_5678_9ABC, &[]);
+        assert_eq!(sid.to_string(), "S-1-0x123456789ABC");
+        assert_eq!(sid.to_string().parse::<Sid>().unwrap(), sid);
+
+        assert_eq!("S-2-5".parse::<Sid>(), Err(ParseError::InvalidSddl(2)));
+        assert_eq!("S-1-5-".parse::<Sid>(), Err(ParseError::InvalidSddl(6)));
+        assert_eq!("XX".parse::<Sid>(), Err(ParseError::InvalidSddl(0)));
+    }
+}
@@ -0,0 +1,33 @@
+use crate::sys;
+
+bitflags::bitflags! {
+    /// The parts of a security descriptor that are queried or changed.
+    #[repr(transparent)]
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+    pub struct SecurityInformation: sys::SECURITY_INFORMATION {
+        /// The owner.
+        const OWNER = sys::OWNER_SECURITY_INFORMATION;
+        /// The primary group.
+        const GROUP = sys::GROUP_SECURITY_INFORMATION;
+        /// The discretionary access control list.
+        const DACL = sys::DACL_SECURITY_INFORMATION;
+        /// The system access control list.
+        const SACL = sys::SACL_SECURITY_INFORMATION;
+        /// The mandatory integrity label, which is stored in the SACL.
+        const LABEL = sys::LABEL_SECURITY_INFORMATION;
+        /// The resource attributes, which are stored in the SACL.
+        const ATTRIBUTE = sys::ATTRIBUTE_SECURITY_INFORMATION;
+        /// The central access policy identifier, which is stored in the SACL.
+        const SCOPE = sys::SCOPE_SECURITY_INFORMATION;
+        /// Everything a backup application needs.
+        const BACKUP = sys::BACKUP_SECURITY_INFORMATION;
+        /// The DACL cannot inherit entries from the parent.
+        const PROTECTED_DACL = sys::PROTECTED_DACL_SECURITY_INFORMATION;
+        /// The SACL cannot inherit entries from the parent.
+        const PROTECTED_SACL = sys::PROTECTED_SACL_SECURITY_INFORMATION;
+        /// The DACL inherits entries from the parent.
+        const UNPROTECTED_DACL = sys::UNPROTECTED_DACL_SECURITY_INFORMATION;
+        /// The SACL inherits entries from the parent.
+        const UNPROTECTED_SACL = sys::UNPROTECTED_SACL_SECURITY_INFORMATION;
+    }
+}
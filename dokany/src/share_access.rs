@@ -0,0 +1,15 @@
+use crate::sys;
+
+bitflags::bitflags! {
+    /// The type of access other handles may have to an opened file.
+    #[repr(transparent)]
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+    pub struct ShareAccess: sys::FILE_SHARE_MODE {
+        /// Other handles may read from the file.
+        const READ = sys::FILE_SHARE_READ;
+        /// Other handles may write to the file.
+        const WRITE = sys::FILE_SHARE_WRITE;
+        /// Other handles may delete the file.
+        const DELETE = sys::FILE_SHARE_DELETE;
+    }
+}
@@ -0,0 +1,1271 @@
+//! Drive a [`FileSystem`] in-process, without the Dokan driver.
+//!
+//! The [`Harness`] builds fake Dokan structures and calls the same trampolines that Dokan calls,
+//! so tests exercise the full ffi layer of a filesystem implementation.
+
+use crate::file_time::file_time_change_to_file_time;
+use crate::is_name_in_expression;
+use crate::sys;
+use crate::AccessMask;
+use crate::AsWide;
+use crate::CreateDisposition;
+use crate::CreateOptions;
+use crate::DiskSpace;
+use crate::FileAttributes;
+use crate::FileInformation;
+use crate::FileSystem;
+use crate::FileSystemFlags;
+use crate::FileTimeChange;
+use crate::FindData;
+use crate::FindStreamData;
+use crate::GlobalContext;
+use crate::NtStatus;
+use crate::Operations;
+use crate::SecurityInformation;
+use crate::ShareAccess;
+use crate::WideStr;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::rc::Weak;
+
+/// The size of the name buffers passed to `GetVolumeInformation`, in wide chars.
+///
+/// This matches the size used by the Dokan library.
+const VOLUME_NAME_BUFFER_LEN: usize = sys::MAX_PATH as usize + 1;
+
+/// The parameters of a create request made through a [`Harness`].
+#[derive(Debug, Copy, Clone)]
+pub struct CreateRequest {
+    /// The requested access rights.
+    pub desired_access: AccessMask,
+    /// The attributes to apply to a newly created file.
+    pub file_attributes: FileAttributes,
+    /// The type of access other handles may have to the file.
+    pub share_access: ShareAccess,
+    /// The action to take if the file does or does not exist.
+    pub create_disposition: CreateDisposition,
+    /// The options to apply when creating or opening the file.
+    pub create_options: CreateOptions,
+    /// Whether the file is already known to be a directory.
+    pub is_directory: bool,
+}
+
+impl CreateRequest {
+    /// Make a request to open an existing file for reading and writing.
+    pub fn new() -> Self {
+        Self {
+            desired_access: AccessMask::GENERIC_READ | AccessMask::GENERIC_WRITE,
+            file_attributes: FileAttributes::NORMAL,
+            share_access: ShareAccess::READ | ShareAccess::WRITE,
+            create_disposition: CreateDisposition::Open,
+            create_options: CreateOptions::empty(),
+            is_directory: false,
+        }
+    }
+}
+
+impl Default for CreateRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The result of a `GetVolumeInformation` call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VolumeInfo {
+    /// The volume name.
+    pub volume_name: String,
+    /// The volume serial number.
+    pub volume_serial_number: u32,
+    /// The maximum length of a file name component.
+    pub maximum_component_length: u32,
+    /// The capability flags of the filesystem.
+    pub file_system_flags: FileSystemFlags,
+    /// The filesystem name.
+    pub file_system_name: String,
+}
+
+/// An in-process driver for a [`FileSystem`].
+pub struct Harness<FS: FileSystem> {
+    /// The fake options. This is boxed as each handle keeps a pointer to it.
+    options: Box<sys::DOKAN_OPTIONS>,
+    /// The names of the open handles, so a rename can update every handle to the file.
+    file_names: RefCell<Vec<Weak<RefCell<Vec<u16>>>>>,
+    _filesystem: PhantomData<FS>,
+}
+
+impl<FS: FileSystem> Harness<FS> {
+    /// Make a new harness for a filesystem.
+    pub fn new(filesystem: FS) -> Self {
+        let context = Box::new(GlobalContext { filesystem });
+
+        let mut options = Box::new(sys::DOKAN_OPTIONS::new());
+        options.GlobalContext = Box::into_raw(context) as u64;
+
+        Self {
+            options,
+            file_names: RefCell::new(Vec::new()),
+            _filesystem: PhantomData,
+        }
+    }
+
+    /// Get the filesystem being driven.
+    pub fn filesystem(&self) -> &FS {
+        let context = self.options.GlobalContext as *const GlobalContext<FS>;
+        unsafe { &(*context).filesystem }
+    }
+
+    /// Make a file info that is not associated with an open handle.
+    fn file_info(&self) -> Box<sys::DOKAN_FILE_INFO> {
+        let mut file_info: Box<sys::DOKAN_FILE_INFO> = Box::new(unsafe { std::mem::zeroed() });
+        file_info.DokanOptions = &*self.options as *const sys::DOKAN_OPTIONS as *mut _;
+        file_info.ProcessId = std::process::id();
+        file_info
+    }
+
+    /// Create or open a file.
+    ///
+    /// Like Dokan, a collision is a success if the disposition may open or create a file.
+    pub fn create_file(
+        &self,
+        file_name: impl AsWide,
+        request: &CreateRequest,
+    ) -> Result<Handle<'_, FS>, NtStatus> {
+        let file_name: Vec<u16> = file_name.as_wide().chain(std::iter::once(0)).collect();
+        let mut file_info = self.file_info();
+        file_info.IsDirectory = u8::from(request.is_directory);
+
+        let mut security_context: sys::DOKAN_IO_SECURITY_CONTEXT = unsafe { std::mem::zeroed() };
+        security_context.DesiredAccess = request.desired_access.bits();
+        security_context.AccessState.OriginalDesiredAccess = request.desired_access.bits();
+        security_context.AccessState.RemainingDesiredAccess = request.desired_access.bits();
+
+        let func = Operations::<FS>::OPERATIONS.ZwCreateFile.unwrap();
+        let status = NtStatus(unsafe {
+            (func)(
+                file_name.as_ptr(),
+                &mut security_context,
+                request.desired_access.bits(),
+                request.file_attributes.bits(),
+                request.share_access.bits(),
+                request.create_disposition.into(),
+                request.create_options.bits(),
+                &mut *file_info,
+            )
+        });
+        let collided = status == NtStatus::OBJECT_NAME_COLLISION
+            && request.create_disposition.may_open_or_create();
+        if !collided {
+            Result::from(status)?;
+        }
+
+        assert!(
+            file_info.Context != 0,
+            "a successful create must set a context"
+        );
+        // Like the driver, report the delete-on-close option at cleanup.
+        file_info.DeleteOnClose = u8::from(
+            request
+                .create_options
+                .contains(CreateOptions::DELETE_ON_CLOSE),
+        );
+
+        let file_name = Rc::new(RefCell::new(file_name));
+        self.file_names.borrow_mut().push(Rc::downgrade(&file_name));
+
+        Ok(Handle {
+            file_name,
+            file_info,
+            opened_existing: collided || !request.create_disposition.may_create(),
+            harness: self,
+        })
+    }
+
+    /// Give every open handle named `file_name` the name `new_file_name`.
+    ///
+    /// Names are NUL-terminated and compared ignoring case, like Dokan's file control blocks.
+    fn rename_handles(&self, file_name: &[u16], new_file_name: &[u16]) {
+        let file_name = WideStr::from_slice(file_name);
+        self.file_names.borrow_mut().retain(|name| {
+            let Some(name) = name.upgrade() else {
+                return false;
+            };
+            let mut name = name.borrow_mut();
+            if WideStr::from_slice(&name).eq_ignore_case(file_name) {
+                *name = new_file_name.to_vec();
+            }
+            true
+        });
+    }
+
+    /// Open an existing file for reading and writing.
+    pub fn open(&self, file_name: impl AsWide) -> Result<Handle<'_, FS>, NtStatus> {
+        self.create_file(file_name, &CreateRequest::new())
+    }
+
+    /// Create a new file, failing if it already exists.
+    pub fn create(&self, file_name: impl AsWide) -> Result<Handle<'_, FS>, NtStatus> {
+        let request = CreateRequest {
+            create_disposition: CreateDisposition::Create,
+            ..CreateRequest::new()
+        };
+        self.create_file(file_name, &request)
+    }
+
+    /// Create a new directory, failing if it already exists.
+    pub fn create_dir(&self, file_name: impl AsWide) -> Result<Handle<'_, FS>, NtStatus> {
+        let request = CreateRequest {
+            desired_access: AccessMask::GENERIC_READ,
+            file_attributes: FileAttributes::DIRECTORY,
+            create_disposition: CreateDisposition::Create,
+            create_options: CreateOptions::DIRECTORY_FILE,
+            is_directory: true,
+            ..CreateRequest::new()
+        };
+        self.create_file(file_name, &request)
+    }
+
+    /// Open a file and read it to the end.
+    pub fn read(&self, file_name: impl AsWide) -> Result<Vec<u8>, NtStatus> {
+        self.open(file_name)?.read_to_end()
+    }
+
+    /// Open a directory and list its entries.
+    pub fn list_dir(&self, file_name: impl AsWide) -> Result<Vec<FindData>, NtStatus> {
+        let request = CreateRequest {
+            desired_access: AccessMask::GENERIC_READ,
+            create_options: CreateOptions::DIRECTORY_FILE,
+            is_directory: true,
+            ..CreateRequest::new()
+        };
+        self.create_file(file_name, &request)?.list_dir()
+    }
+
+    /// Open a file or directory and list its streams.
+    pub fn list_streams(&self, file_name: impl AsWide) -> Result<Vec<FindStreamData>, NtStatus> {
+        self.open(file_name)?.list_streams()
+    }
+
+    /// Delete a file or an empty directory.
+    pub fn delete(&self, file_name: impl AsWide) -> Result<(), NtStatus> {
+        let request = CreateRequest {
+            desired_access: AccessMask::DELETE,
+            ..CreateRequest::new()
+        };
+        self.create_file(file_name, &request)?
+            .set_delete_on_close(true)
+    }
+
+    /// Move or rename a file or directory.
+    pub fn rename(
+        &self,
+        file_name: impl AsWide,
+        new_file_name: impl AsWide,
+        replace_if_existing: bool,
+    ) -> Result<(), NtStatus> {
+        let request = CreateRequest {
+            desired_access: AccessMask::DELETE,
+            ..CreateRequest::new()
+        };
+        self.create_file(file_name, &request)?
+            .move_to(new_file_name, replace_if_existing)
+    }
+
+    /// Get the capacity of the volume.
+    pub fn disk_space(&self) -> Result<DiskSpace, NtStatus> {
+        let mut file_info = self.file_info();
+        let mut free_bytes_available = 0;
+        let mut total_bytes = 0;
+        let mut total_free_bytes = 0;
+
+        let func = Operations::<FS>::OPERATIONS.GetDiskFreeSpace.unwrap();
+        let status = NtStatus(unsafe {
+            (func)(
+                &mut free_bytes_available,
+                &mut total_bytes,
+                &mut total_free_bytes,
+                &mut *file_info,
+            )
+        });
+        Result::from(status)?;
+
+        Ok(DiskSpace {
+            free_bytes_available,
+            total_bytes,
+            total_free_bytes,
+        })
+    }
+
+    /// Get information about the volume.
+    pub fn volume_info(&self) -> Result<VolumeInfo, NtStatus> {
+        let mut file_info = self.file_info();
+        let mut volume_name = [0; VOLUME_NAME_BUFFER_LEN];
+        let mut volume_serial_number = 0;
+        let mut maximum_component_length = 0;
+        let mut file_system_flags = 0;
+        let mut file_system_name = [0; VOLUME_NAME_BUFFER_LEN];
+
+        let func = Operations::<FS>::OPERATIONS.GetVolumeInformation.unwrap();
+        let status = NtStatus(unsafe {
+            (func)(
+                volume_name.as_mut_ptr(),
+                volume_name.len().try_into().unwrap(),
+                &mut volume_serial_number,
+                &mut maximum_component_length,
+                &mut file_system_flags,
+                file_system_name.as_mut_ptr(),
+                file_system_name.len().try_into().unwrap(),
+                &mut *file_info,
+            )
+        });
+        Result::from(status)?;
+
+        Ok(VolumeInfo {
+            volume_name: string_from_wide_buffer(&volume_name),
+            volume_serial_number,
+            maximum_component_length,
+            file_system_flags: FileSystemFlags::from_bits_retain(file_system_flags),
+            file_system_name: string_from_wide_buffer(&file_system_name),
+        })
+    }
+
+    /// Notify the filesystem that it was mounted.
+    pub fn mounted(&self, mount_point: impl AsWide) -> Result<(), NtStatus> {
+        let mount_point: Vec<u16> = mount_point.as_wide().chain(std::iter::once(0)).collect();
+        let mut file_info = self.file_info();
+
+        let func = Operations::<FS>::OPERATIONS.Mounted.unwrap();
+        NtStatus(unsafe { (func)(mount_point.as_ptr(), &mut *file_info) }).into()
+    }
+
+    /// Notify the filesystem that it was unmounted.
+    pub fn unmounted(&self) -> Result<(), NtStatus> {
+        let mut file_info = self.file_info();
+
+        let func = Operations::<FS>::OPERATIONS.Unmounted.unwrap();
+        NtStatus(unsafe { (func)(&mut *file_info) }).into()
+    }
+}
+
+impl<FS: FileSystem> Drop for Harness<FS> {
+    fn drop(&mut self) {
+        let context = self.options.GlobalContext as *mut GlobalContext<FS>;
+        drop(unsafe { Box::from_raw(context) });
+    }
+}
+
+/// A file handle opened through a [`Harness`].
+///
+/// The handle is cleaned up and closed when this is dropped.
+pub struct Handle<'a, FS: FileSystem> {
+    /// The NUL-terminated file name. This is shared with the harness, which updates it on renames.
+    file_name: Rc<RefCell<Vec<u16>>>,
+    /// The file info. This is boxed as the filesystem may hold on to its address.
+    file_info: Box<sys::DOKAN_FILE_INFO>,
+    /// Whether an existing file was opened, rather than a new one created.
+    opened_existing: bool,
+    harness: &'a Harness<FS>,
+}
+
+impl<FS: FileSystem> Handle<'_, FS> {
+    /// Get the per-handle context created by the filesystem.
+    pub fn context(&self) -> &FS::Context {
+        unsafe { &*(self.file_info.Context as *const FS::Context) }
+    }
+
+    /// Returns true if the filesystem reported this handle as a directory.
+    pub fn is_directory(&self) -> bool {
+        self.file_info.IsDirectory != 0
+    }
+
+    /// Returns true if an existing file was opened, rather than a new one created.
+    pub fn opened_existing(&self) -> bool {
+        self.opened_existing
+    }
+
+    /// Get information about the open file.
+    pub fn file_information(&mut self) -> Result<FileInformation, NtStatus> {
+        let mut buffer: sys::BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+
+        let func = Operations::<FS>::OPERATIONS.GetFileInformation.unwrap();
+        let status = NtStatus(unsafe {
+            (func)(
+                self.file_name.borrow().as_ptr(),
+                &mut buffer,
+                &mut *self.file_info,
+            )
+        });
+        Result::from(status)?;
+
+        Ok(buffer.into())
+    }
+
+    /// Read data at the given offset into a buffer.
+    ///
+    /// # Returns
+    /// Returns the number of bytes read.
+    pub fn read_at(&mut self, buffer: &mut [u8], offset: u64) -> Result<usize, NtStatus> {
+        let mut read_length = 0;
+
+        let func = Operations::<FS>::OPERATIONS.ReadFile.unwrap();
+        let status = NtStatus(unsafe {
+            (func)(
+                self.file_name.borrow().as_ptr(),
+                buffer.as_mut_ptr().cast(),
+                buffer.len().try_into().unwrap(),
+                &mut read_length,
+                offset.try_into().unwrap(),
+                &mut *self.file_info,
+            )
+        });
+        Result::from(status)?;
+
+        let read_length = usize::try_from(read_length).unwrap();
+        assert!(read_length <= buffer.len());
+        Ok(read_length)
+    }
+
+    /// Read from the start of the file until a read returns no data.
+    pub fn read_to_end(&mut self) -> Result<Vec<u8>, NtStatus> {
+        let mut data = Vec::new();
+        let mut buffer = [0; 4096];
+        loop {
+            let len = match self.read_at(&mut buffer, data.len().try_into().unwrap()) {
+                Ok(len) => len,
+                Err(NtStatus::END_OF_FILE) => 0,
+                Err(status) => return Err(status),
+            };
+            if len == 0 {
+                return Ok(data);
+            }
+            data.extend_from_slice(&buffer[..len]);
+        }
+    }
+
+    /// Write data at the given offset.
+    ///
+    /// # Returns
+    /// Returns the number of bytes written.
+    pub fn write_at(&mut self, data: &[u8], offset: u64) -> Result<usize, NtStatus> {
+        self.write(data, offset.try_into().unwrap(), false)
+    }
+
+    /// Write data to the end of the file.
+    ///
+    /// # Returns
+    /// Returns the number of bytes written.
+    pub fn append(&mut self, data: &[u8]) -> Result<usize, NtStatus> {
+        self.write(data, -1, true)
+    }
+
+    fn write(&mut self, data: &[u8], offset: i64, write_to_eof: bool) -> Result<usize, NtStatus> {
+        let mut written = 0;
+        self.file_info.WriteToEndOfFile = u8::from(write_to_eof);
+
+        let func = Operations::<FS>::OPERATIONS.WriteFile.unwrap();
+        let status = NtStatus(unsafe {
+            (func)(
+                self.file_name.borrow().as_ptr(),
+                data.as_ptr().cast(),
+                data.len().try_into().unwrap(),
+                &mut written,
+                offset,
+                &mut *self.file_info,
+            )
+        });
+        self.file_info.WriteToEndOfFile = 0;
+        Result::from(status)?;
+
+        let written = usize::try_from(written).unwrap();
+        assert!(written <= data.len());
+        Ok(written)
+    }
+
+    /// Set the size of the file.
+    pub fn set_end_of_file(&mut self, length: u64) -> Result<(), NtStatus> {
+        let length = sys::LONGLONG::try_from(length).map_err(|_| NtStatus::INVALID_PARAMETER)?;
+
+        let func = Operations::<FS>::OPERATIONS.SetEndOfFile.unwrap();
+        let status = NtStatus(unsafe {
+            (func)(
+                self.file_name.borrow().as_ptr(),
+                length,
+                &mut *self.file_info,
+            )
+        });
+        Result::from(status)
+    }
+
+    /// Set the space allocated for the file.
+    pub fn set_allocation_size(&mut self, length: u64) -> Result<(), NtStatus> {
+        let length = sys::LONGLONG::try_from(length).map_err(|_| NtStatus::INVALID_PARAMETER)?;
+
+        let func = Operations::<FS>::OPERATIONS.SetAllocationSize.unwrap();
+        let status = NtStatus(unsafe {
+            (func)(
+                self.file_name.borrow().as_ptr(),
+                length,
+                &mut *self.file_info,
+            )
+        });
+        Result::from(status)
+    }
+
+    /// Set the attributes of the file.
+    pub fn set_file_attributes(&mut self, file_attributes: FileAttributes) -> Result<(), NtStatus> {
+        let func = Operations::<FS>::OPERATIONS.SetFileAttributes.unwrap();
+        let status = NtStatus(unsafe {
+            (func)(
+                self.file_name.borrow().as_ptr(),
+                file_attributes.bits(),
+                &mut *self.file_info,
+            )
+        });
+        Result::from(status)
+    }
+
+    /// Get the parts of the security descriptor of the file selected by `security_information`.
+    ///
+    /// Like Dokan, this starts with an empty buffer and retries with the length the filesystem needs.
+    pub fn file_security(
+        &mut self,
+        security_information: SecurityInformation,
+    ) -> Result<Vec<u8>, NtStatus> {
+        let func = Operations::<FS>::OPERATIONS.GetFileSecurity.unwrap();
+        let mut buffer: Vec<u8> = Vec::new();
+        loop {
+            let mut security_information = security_information.bits();
+            let mut length_needed = 0;
+            let status = NtStatus(unsafe {
+                (func)(
+                    self.file_name.borrow().as_ptr(),
+                    &mut security_information,
+                    buffer.as_mut_ptr().cast(),
+                    buffer.len().try_into().unwrap(),
+                    &mut length_needed,
+                    &mut *self.file_info,
+                )
+            });
+
+            let length_needed = usize::try_from(length_needed).unwrap();
+            match status {
+                NtStatus::BUFFER_OVERFLOW => {
+                    assert!(
+                        length_needed > buffer.len(),
+                        "an overflow must report a larger length"
+                    );
+                    buffer.resize(length_needed, 0);
+                }
+                status => {
+                    Result::from(status)?;
+                    buffer.truncate(length_needed);
+                    return Ok(buffer);
+                }
+            }
+        }
+    }
+
+    /// Change the parts of the security descriptor of the file selected by `security_information`.
+    pub fn set_file_security(
+        &mut self,
+        security_information: SecurityInformation,
+        security_descriptor: &[u8],
+    ) -> Result<(), NtStatus> {
+        let func = Operations::<FS>::OPERATIONS.SetFileSecurity.unwrap();
+        let mut security_information = security_information.bits();
+        let status = NtStatus(unsafe {
+            (func)(
+                self.file_name.borrow().as_ptr(),
+                &mut security_information,
+                security_descriptor.as_ptr().cast_mut().cast(),
+                security_descriptor.len().try_into().unwrap(),
+                &mut *self.file_info,
+            )
+        });
+        Result::from(status)
+    }
+
+    /// Change the times of the file.
+    ///
+    /// Unchanged times are passed as null.
+    pub fn set_file_time(
+        &mut self,
+        creation_time: FileTimeChange,
+        last_access_time: FileTimeChange,
+        last_write_time: FileTimeChange,
+    ) -> Result<(), NtStatus> {
+        let creation_time = file_time_change_to_file_time(creation_time);
+        let last_access_time = file_time_change_to_file_time(last_access_time);
+        let last_write_time = file_time_change_to_file_time(last_write_time);
+
+        let func = Operations::<FS>::OPERATIONS.SetFileTime.unwrap();
+        let status = NtStatus(unsafe {
+            (func)(
+                self.file_name.borrow().as_ptr(),
+                file_time_ptr(&creation_time),
+                file_time_ptr(&last_access_time),
+                file_time_ptr(&last_write_time),
+                &mut *self.file_info,
+            )
+        });
+        Result::from(status)
+    }
+
+    /// Lock a range of the file.
+    pub fn lock(&mut self, offset: u64, length: u64) -> Result<(), NtStatus> {
+        let offset = sys::LONGLONG::try_from(offset).map_err(|_| NtStatus::INVALID_PARAMETER)?;
+        let length = sys::LONGLONG::try_from(length).map_err(|_| NtStatus::INVALID_PARAMETER)?;
+
+        let func = Operations::<FS>::OPERATIONS.LockFile.unwrap();
+        let status = NtStatus(unsafe {
+            (func)(
+                self.file_name.borrow().as_ptr(),
+                offset,
+                length,
+                &mut *self.file_info,
+            )
+        });
+        Result::from(status)
+    }
+
+    /// Unlock a range of the file.
+    pub fn unlock(&mut self, offset: u64, length: u64) -> Result<(), NtStatus> {
+        let offset = sys::LONGLONG::try_from(offset).map_err(|_| NtStatus::INVALID_PARAMETER)?;
+        let length = sys::LONGLONG::try_from(length).map_err(|_| NtStatus::INVALID_PARAMETER)?;
+
+        let func = Operations::<FS>::OPERATIONS.UnlockFile.unwrap();
+        let status = NtStatus(unsafe {
+            (func)(
+                self.file_name.borrow().as_ptr(),
+                offset,
+                length,
+                &mut *self.file_info,
+            )
+        });
+        Result::from(status)
+    }
+
+    /// Request or cancel the deletion of the file when this handle is cleaned up.
+    ///
+    /// Like the driver, this first asks the filesystem whether the file can be deleted.
+    pub fn set_delete_on_close(&mut self, delete_on_close: bool) -> Result<(), NtStatus> {
+        let previous = self.file_info.DeleteOnClose;
+        self.file_info.DeleteOnClose = u8::from(delete_on_close);
+
+        let operations = Operations::<FS>::OPERATIONS;
+        let func = if self.is_directory() {
+            operations.DeleteDirectory.unwrap()
+        } else {
+            operations.DeleteFile.unwrap()
+        };
+        let result = Result::from(NtStatus(unsafe {
+            (func)(self.file_name.borrow().as_ptr(), &mut *self.file_info)
+        }));
+        if result.is_err() {
+            self.file_info.DeleteOnClose = previous;
+        }
+
+        result
+    }
+
+    /// Move or rename the file of this handle.
+    ///
+    /// On success, later operations on this handle use the new name.
+    /// Like Dokan, so do those on every other open handle to the file.
+    pub fn move_to(
+        &mut self,
+        new_file_name: impl AsWide,
+        replace_if_existing: bool,
+    ) -> Result<(), NtStatus> {
+        let new_file_name: Vec<u16> = new_file_name.as_wide().chain(std::iter::once(0)).collect();
+
+        let func = Operations::<FS>::OPERATIONS.MoveFile.unwrap();
+        let status = NtStatus(unsafe {
+            (func)(
+                self.file_name.borrow().as_ptr(),
+                new_file_name.as_ptr(),
+                sys::BOOL::from(replace_if_existing),
+                &mut *self.file_info,
+            )
+        });
+        Result::from(status)?;
+
+        let file_name = self.file_name.borrow().clone();
+        self.harness.rename_handles(&file_name, &new_file_name);
+        Ok(())
+    }
+
+    /// List the entries of this directory.
+    pub fn list_dir(&mut self) -> Result<Vec<FindData>, NtStatus> {
+        self.list_dir_with_capacity(usize::MAX)
+    }
+
+    /// List the entries of this directory, reporting a full buffer after `capacity` entries.
+    pub fn list_dir_with_capacity(&mut self, capacity: usize) -> Result<Vec<FindData>, NtStatus> {
+        let mut buffer = FindDataBuffer::new(capacity);
+        Result::from(self.find_files(&mut buffer, None))?;
+
+        Ok(buffer.entries)
+    }
+
+    /// List the entries of this directory that match a DOS wildcard expression.
+    ///
+    /// Like Dokan, if `find_files_with_pattern` is not implemented,
+    /// this falls back to `find_files` and filters the entries itself.
+    pub fn list_dir_with_pattern(
+        &mut self,
+        search_pattern: impl AsWide,
+    ) -> Result<Vec<FindData>, NtStatus> {
+        let search_pattern: Vec<u16> = search_pattern.as_wide().chain(std::iter::once(0)).collect();
+
+        let mut buffer = FindDataBuffer::new(usize::MAX);
+        let mut status = self.find_files(&mut buffer, Some(&search_pattern));
+        if status == NtStatus::NOT_IMPLEMENTED {
+            buffer = FindDataBuffer::new(usize::MAX);
+            buffer.search_pattern = Some(search_pattern[..search_pattern.len() - 1].to_vec());
+            status = self.find_files(&mut buffer, None);
+        }
+        Result::from(status)?;
+
+        Ok(buffer.entries)
+    }
+
+    /// List the streams of this file.
+    pub fn list_streams(&mut self) -> Result<Vec<FindStreamData>, NtStatus> {
+        self.list_streams_with_capacity(usize::MAX)
+    }
+
+    /// List the streams of this file, reporting a full buffer after `capacity` entries.
+    ///
+    /// Like Dokan, this fails with the status of the listing if the buffer fills up.
+    pub fn list_streams_with_capacity(
+        &mut self,
+        capacity: usize,
+    ) -> Result<Vec<FindStreamData>, NtStatus> {
+        let mut buffer = FindStreamDataBuffer {
+            entries: Vec::new(),
+            capacity,
+        };
+
+        let func = Operations::<FS>::OPERATIONS.FindStreams.unwrap();
+        let status = NtStatus(unsafe {
+            (func)(
+                self.file_name.borrow().as_ptr(),
+                Some(fill_find_stream_data_callback),
+                (&mut buffer as *mut FindStreamDataBuffer).cast(),
+                &mut *self.file_info,
+            )
+        });
+        Result::from(status)?;
+
+        Ok(buffer.entries)
+    }
+
+    /// Call `FindFiles`, or `FindFilesWithPattern` if given a NUL-terminated search pattern.
+    fn find_files(
+        &mut self,
+        buffer: &mut FindDataBuffer,
+        search_pattern: Option<&[u16]>,
+    ) -> NtStatus {
+        // Dokan reserves this field for its own use, so it is free for the fake fill function.
+        self.file_info.ProcessingContext = (buffer as *mut FindDataBuffer).cast();
+
+        let operations = Operations::<FS>::OPERATIONS;
+        let status = NtStatus(unsafe {
+            match search_pattern {
+                Some(search_pattern) => (operations.FindFilesWithPattern.unwrap())(
+                    self.file_name.borrow().as_ptr(),
+                    search_pattern.as_ptr(),
+                    Some(fill_find_data_callback),
+                    &mut *self.file_info,
+                ),
+                None => (operations.FindFiles.unwrap())(
+                    self.file_name.borrow().as_ptr(),
+                    Some(fill_find_data_callback),
+                    &mut *self.file_info,
+                ),
+            }
+        });
+        self.file_info.ProcessingContext = std::ptr::null_mut();
+
+        status
+    }
+}
+
+impl<FS: FileSystem> Drop for Handle<'_, FS> {
+    fn drop(&mut self) {
+        let operations = Operations::<FS>::OPERATIONS;
+        unsafe {
+            (operations.Cleanup.unwrap())(self.file_name.borrow().as_ptr(), &mut *self.file_info);
+            (operations.CloseFile.unwrap())(self.file_name.borrow().as_ptr(), &mut *self.file_info);
+        }
+    }
+}
+
+/// The entries collected by a fake `PFillFindData`.
+struct FindDataBuffer {
+    entries: Vec<FindData>,
+    capacity: usize,
+    /// If set, entries that do not match this expression are skipped.
+    search_pattern: Option<Vec<u16>>,
+}
+
+impl FindDataBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            capacity,
+            search_pattern: None,
+        }
+    }
+}
+
+/// A fake `PFillFindData` that collects entries into the buffer in the file info's processing context.
+///
+/// Like Dokan, this returns 1 once the buffer is full.
+unsafe extern "system" fn fill_find_data_callback(
+    find_data: sys::PWIN32_FIND_DATAW,
+    dokan_file_info: sys::PDOKAN_FILE_INFO,
+) -> sys::c_int {
+    let buffer = &mut *((*dokan_file_info).ProcessingContext as *mut FindDataBuffer);
+    let find_data = FindData {
+        find_data: *find_data,
+    };
+
+    if let Some(search_pattern) = &buffer.search_pattern {
+        if !is_name_in_expression(search_pattern, find_data.file_name(), true) {
+            return 0;
+        }
+    }
+
+    if buffer.entries.len() >= buffer.capacity {
+        return 1;
+    }
+
+    buffer.entries.push(find_data);
+    0
+}
+
+/// The entries collected by a fake `PFillFindStreamData`.
+struct FindStreamDataBuffer {
+    entries: Vec<FindStreamData>,
+    capacity: usize,
+}
+
+/// A fake `PFillFindStreamData` that collects entries into the buffer passed as its context.
+///
+/// Like Dokan, this returns `FALSE` once the buffer is full.
+unsafe extern "system" fn fill_find_stream_data_callback(
+    find_stream_data: sys::PWIN32_FIND_STREAM_DATA,
+    find_stream_context: sys::PVOID,
+) -> sys::BOOL {
+    let buffer = &mut *(find_stream_context as *mut FindStreamDataBuffer);
+    if buffer.entries.len() >= buffer.capacity {
+        return sys::FALSE;
+    }
+
+    buffer.entries.push(FindStreamData {
+        find_stream_data: *find_stream_data,
+    });
+    sys::TRUE
+}
+
+/// Get a pointer to an optional file time, or null if it is `None`.
+fn file_time_ptr(file_time: &Option<sys::FILETIME>) -> *const sys::FILETIME {
+    match file_time {
+        Some(file_time) => file_time,
+        None => std::ptr::null(),
+    }
+}
+
+/// Convert a NUL-terminated wide string buffer into a string.
+fn string_from_wide_buffer(buffer: &[u16]) -> String {
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    String::from_utf16_lossy(&buffer[..len])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::CreateFileInfo;
+    use crate::CreateFileResult;
+    use crate::FillFindData;
+    use crate::FillFindStreamData;
+    use crate::WriteWideCStringCell;
+    use std::sync::Mutex;
+    use std::time::SystemTime;
+
+    /// A read-only filesystem with one directory and one file.
+    struct StaticFileSystem;
+
+    const HELLO: &[u8] = b"Hello World!";
+
+    impl FileSystem for StaticFileSystem {
+        type Context = String;
+
+        fn create_file(
+            &self,
+            file_name: &[u16],
+            create_file_info: &CreateFileInfo<'_>,
+            is_dir: &mut bool,
+        ) -> Result<CreateFileResult<Self::Context>, NtStatus> {
+            let file_name = String::from_utf16_lossy(file_name);
+            if create_file_info.create_disposition() != CreateDisposition::Open {
+                return Err(NtStatus::MEDIA_WRITE_PROTECTED);
+            }
+
+            match file_name.as_str() {
+                "\\" => *is_dir = true,
+                "\\hello.txt" => *is_dir = false,
+                _ => return Err(NtStatus::OBJECT_NAME_NOT_FOUND),
+            }
+
+            Ok(CreateFileResult::opened(file_name))
+        }
+
+        fn read_file(
+            &self,
+            _file_name: &[u16],
+            context: &Self::Context,
+            buffer: &mut [u8],
+            offset: u64,
+        ) -> Result<usize, NtStatus> {
+            if context != "\\hello.txt" {
+                return Err(NtStatus::FILE_IS_A_DIRECTORY);
+            }
+
+            let data = HELLO.get(offset as usize..).unwrap_or_default();
+            let len = data.len().min(buffer.len());
+            buffer[..len].copy_from_slice(&data[..len]);
+            Ok(len)
+        }
+
+        fn find_files(
+            &self,
+            _file_name: &[u16],
+            context: &Self::Context,
+            mut fill_find_data: FillFindData<'_>,
+        ) -> Result<(), NtStatus> {
+            if context != "\\" {
+                return Err(NtStatus::NOT_A_DIRECTORY);
+            }
+
+            let mut find_data = FindData::new();
+            find_data.set_file_name("hello.txt");
+            find_data.set_size(HELLO.len() as u64);
+            fill_find_data.fill(&find_data)?;
+
+            Ok(())
+        }
+
+        fn find_streams(
+            &self,
+            _file_name: &[u16],
+            context: &Self::Context,
+            mut fill_find_stream_data: FillFindStreamData<'_>,
+        ) -> Result<(), NtStatus> {
+            if context != "\\hello.txt" {
+                return Ok(());
+            }
+
+            let mut find_stream_data = FindStreamData::new();
+            find_stream_data.set_stream_name("::$DATA");
+            find_stream_data.set_size(HELLO.len() as u64);
+            fill_find_stream_data.fill(&find_stream_data)?;
+
+            find_stream_data.set_stream_name(":Zone.Identifier:$DATA");
+            find_stream_data.set_size(0);
+            fill_find_stream_data.fill(&find_stream_data)?;
+
+            Ok(())
+        }
+
+        fn get_volume_information(
+            &self,
+            mut volume_name: WriteWideCStringCell<'_>,
+            volume_serial_number: &mut u32,
+            _maximum_component_length: &mut u32,
+            file_system_flags: &mut FileSystemFlags,
+            mut file_system_name: WriteWideCStringCell<'_>,
+        ) -> Result<(), NtStatus> {
+            volume_name.write("Static");
+            *volume_serial_number = 42;
+            *file_system_flags = FileSystemFlags::READ_ONLY_VOLUME;
+            file_system_name.write("NTFS");
+            Ok(())
+        }
+    }
+
+    /// A filesystem that records the time changes made through it.
+    #[derive(Default)]
+    struct TimeFileSystem {
+        changes: Mutex<Vec<[FileTimeChange; 3]>>,
+    }
+
+    impl FileSystem for TimeFileSystem {
+        type Context = ();
+
+        fn create_file(
+            &self,
+            _file_name: &[u16],
+            _create_file_info: &CreateFileInfo<'_>,
+            _is_dir: &mut bool,
+        ) -> Result<CreateFileResult<Self::Context>, NtStatus> {
+            Ok(CreateFileResult::opened(()))
+        }
+
+        fn set_file_time(
+            &self,
+            _file_name: &[u16],
+            _context: &Self::Context,
+            creation_time: FileTimeChange,
+            last_access_time: FileTimeChange,
+            last_write_time: FileTimeChange,
+        ) -> Result<(), NtStatus> {
+            self.changes
+                .lock()
+                .unwrap()
+                .push([creation_time, last_access_time, last_write_time]);
+            Ok(())
+        }
+    }
+
+    /// A filesystem that returns the create result named by the file name.
+    struct CreateResultFileSystem;
+
+    impl FileSystem for CreateResultFileSystem {
+        type Context = ();
+
+        fn create_file(
+            &self,
+            file_name: &[u16],
+            _create_file_info: &CreateFileInfo<'_>,
+            _is_dir: &mut bool,
+        ) -> Result<CreateFileResult<Self::Context>, NtStatus> {
+            match String::from_utf16_lossy(file_name).as_str() {
+                "\\created" => Ok(CreateFileResult::created(())),
+                "\\opened" => Ok(CreateFileResult::opened(())),
+                "\\success" => Err(NtStatus::SUCCESS),
+                "\\collision" => Err(NtStatus::OBJECT_NAME_COLLISION),
+                _ => Err(NtStatus::OBJECT_NAME_NOT_FOUND),
+            }
+        }
+    }
+
+    #[test]
+    fn open() {
+        let harness = Harness::new(StaticFileSystem);
+
+        let root = harness.open("\\").unwrap();
+        assert!(root.is_directory());
+        assert_eq!(root.context(), "\\");
+
+        let file = harness.open("\\hello.txt").unwrap();
+        assert!(!file.is_directory());
+
+        assert_eq!(
+            harness.open("\\missing.txt").err(),
+            Some(NtStatus::OBJECT_NAME_NOT_FOUND)
+        );
+        assert_eq!(
+            harness.create("\\new.txt").err(),
+            Some(NtStatus::MEDIA_WRITE_PROTECTED)
+        );
+    }
+
+    #[test]
+    fn read() {
+        let harness = Harness::new(StaticFileSystem);
+
+        assert_eq!(harness.read("\\hello.txt").unwrap(), HELLO);
+
+        let mut file = harness.open("\\hello.txt").unwrap();
+        let mut buffer = [0; 5];
+        assert_eq!(file.read_at(&mut buffer, 6).unwrap(), 5);
+        assert_eq!(&buffer, b"World");
+
+        assert_eq!(
+            harness.read("\\").err(),
+            Some(NtStatus::FILE_IS_A_DIRECTORY)
+        );
+    }
+
+    #[test]
+    fn write_not_implemented() {
+        let harness = Harness::new(StaticFileSystem);
+
+        let mut file = harness.open("\\hello.txt").unwrap();
+        assert_eq!(
+            file.write_at(b"data", 0).err(),
+            Some(NtStatus::NOT_IMPLEMENTED)
+        );
+    }
+
+    #[test]
+    fn list_dir() {
+        let harness = Harness::new(StaticFileSystem);
+
+        let entries = harness.list_dir("\\").unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entries[0].file_name(),
+            "hello.txt".encode_utf16().collect::<Vec<_>>()
+        );
+        assert_eq!(entries[0].size(), HELLO.len() as u64);
+
+        // A full buffer ends the listing early, but successfully.
+        let mut root = harness.open("\\").unwrap();
+        assert!(root.list_dir_with_capacity(0).unwrap().is_empty());
+
+        // Without find_files_with_pattern, entries are filtered like Dokan does.
+        assert_eq!(root.list_dir_with_pattern("*.TXT").unwrap().len(), 1);
+        assert!(root.list_dir_with_pattern("*.md").unwrap().is_empty());
+
+        assert_eq!(
+            harness.list_dir("\\hello.txt").err(),
+            Some(NtStatus::NOT_A_DIRECTORY)
+        );
+    }
+
+    #[test]
+    fn list_streams() {
+        let harness = Harness::new(StaticFileSystem);
+
+        let streams = harness.list_streams("\\hello.txt").unwrap();
+        let names: Vec<String> = streams
+            .iter()
+            .map(|stream| String::from_utf16_lossy(stream.stream_name()))
+            .collect();
+        assert_eq!(names, ["::$DATA", ":Zone.Identifier:$DATA"]);
+        assert_eq!(streams[0].size(), HELLO.len() as u64);
+
+        // Unlike directory listings, a full buffer is reported to the caller.
+        let mut file = harness.open("\\hello.txt").unwrap();
+        assert_eq!(
+            file.list_streams_with_capacity(1).err(),
+            Some(NtStatus::BUFFER_OVERFLOW)
+        );
+
+        assert!(harness.list_streams("\\").unwrap().is_empty());
+    }
+
+    #[test]
+    fn volume_info() {
+        let harness = Harness::new(StaticFileSystem);
+
+        let volume_info = harness.volume_info().unwrap();
+        assert_eq!(
+            volume_info,
+            VolumeInfo {
+                volume_name: "Static".into(),
+                volume_serial_number: 42,
+                maximum_component_length: 255,
+                file_system_flags: FileSystemFlags::READ_ONLY_VOLUME,
+                file_system_name: "NTFS".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn disk_space() {
+        let harness = Harness::new(StaticFileSystem);
+
+        // Without get_disk_free_space, the Dokan defaults are reported.
+        assert_eq!(harness.disk_space().unwrap(), DiskSpace::default());
+    }
+
+    #[test]
+    fn set_file_time() {
+        let harness = Harness::new(TimeFileSystem::default());
+        let mut file = harness.open("\\a.txt").unwrap();
+
+        let file_time = |intervals: i64| sys::FILETIME {
+            dwLowDateTime: intervals as u32,
+            dwHighDateTime: (intervals >> 32) as u32,
+        };
+        let set_file_time = |file: &mut Handle<'_, TimeFileSystem>,
+                             creation_time: *const sys::FILETIME,
+                             last_access_time: *const sys::FILETIME,
+                             last_write_time: *const sys::FILETIME| {
+            let func = Operations::<TimeFileSystem>::OPERATIONS
+                .SetFileTime
+                .unwrap();
+            NtStatus(unsafe {
+                (func)(
+                    file.file_name.borrow().as_ptr(),
+                    creation_time,
+                    last_access_time,
+                    last_write_time,
+                    &mut *file.file_info,
+                )
+            })
+        };
+
+        // Null and zero leave a time unchanged, while -1 and -2 stop and resume updating it.
+        let status = set_file_time(&mut file, std::ptr::null(), &file_time(0), &file_time(-1));
+        assert_eq!(status, NtStatus::SUCCESS);
+        let status = set_file_time(
+            &mut file,
+            &file_time(-2),
+            &file_time(116_444_736_000_000_000),
+            std::ptr::null(),
+        );
+        assert_eq!(status, NtStatus::SUCCESS);
+
+        // Other negative times are rejected before reaching the filesystem.
+        let status = set_file_time(
+            &mut file,
+            std::ptr::null(),
+            &file_time(-3),
+            std::ptr::null(),
+        );
+        assert_eq!(status, NtStatus::INVALID_PARAMETER);
+
+        assert_eq!(
+            *harness.filesystem().changes.lock().unwrap(),
+            [
+                [
+                    FileTimeChange::Unchanged,
+                    FileTimeChange::Unchanged,
+                    FileTimeChange::StopUpdating,
+                ],
+                [
+                    FileTimeChange::ResumeUpdating,
+                    FileTimeChange::Set(SystemTime::UNIX_EPOCH),
+                    FileTimeChange::Unchanged,
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn create_results() {
+        let harness = Harness::new(CreateResultFileSystem);
+        let open_if = CreateRequest {
+            create_disposition: CreateDisposition::OpenIf,
+            ..CreateRequest::new()
+        };
+
+        // An opened existing file is reported as a collision, which is still a success.
+        let file = harness.create_file("\\opened", &open_if).unwrap();
+        assert!(file.opened_existing());
+        let file = harness.create_file("\\created", &open_if).unwrap();
+        assert!(!file.opened_existing());
+        assert!(harness.open("\\opened").unwrap().opened_existing());
+
+        // Errors that Dokan would treat as a success without a context are internal errors.
+        assert_eq!(
+            harness.create_file("\\success", &open_if).err(),
+            Some(NtStatus::INTERNAL_ERROR)
+        );
+        assert_eq!(
+            harness.create_file("\\collision", &open_if).err(),
+            Some(NtStatus::INTERNAL_ERROR)
+        );
+        assert_eq!(
+            harness.create("\\collision").err(),
+            Some(NtStatus::OBJECT_NAME_COLLISION)
+        );
+    }
+}
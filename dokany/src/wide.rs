@@ -1,5 +1,10 @@
+use crate::WideCStr;
+use crate::WideCString;
+use crate::WideStr;
+use crate::WideString;
 use std::ffi::OsStr;
 use std::ffi::OsString;
+#[cfg(windows)]
 use std::os::windows::ffi::OsStrExt;
 use std::path::Path;
 use std::path::PathBuf;
@@ -47,6 +52,41 @@ impl<'a> AsWide for &'a Vec<u16> {
     }
 }
 
+impl<'a> AsWide for &'a WideStr {
+    type Iter = std::iter::Copied<std::slice::Iter<'a, u16>>;
+
+    fn as_wide(&self) -> Self::Iter {
+        self.as_slice().iter().copied()
+    }
+}
+
+impl<'a> AsWide for &'a WideString {
+    type Iter = std::iter::Copied<std::slice::Iter<'a, u16>>;
+
+    fn as_wide(&self) -> Self::Iter {
+        self.as_slice().iter().copied()
+    }
+}
+
+/// The NUL terminator is not included.
+impl<'a> AsWide for &'a WideCStr {
+    type Iter = std::iter::Copied<std::slice::Iter<'a, u16>>;
+
+    fn as_wide(&self) -> Self::Iter {
+        self.as_slice().iter().copied()
+    }
+}
+
+/// The NUL terminator is not included.
+impl<'a> AsWide for &'a WideCString {
+    type Iter = std::iter::Copied<std::slice::Iter<'a, u16>>;
+
+    fn as_wide(&self) -> Self::Iter {
+        self.as_slice().iter().copied()
+    }
+}
+
+#[cfg(windows)]
 impl<'a> AsWide for &'a OsStr {
     type Iter = std::os::windows::ffi::EncodeWide<'a>;
 
@@ -55,6 +95,7 @@ impl<'a> AsWide for &'a OsStr {
     }
 }
 
+#[cfg(windows)]
 impl<'a> AsWide for &'a OsString {
     type Iter = std::os::windows::ffi::EncodeWide<'a>;
 
@@ -63,6 +104,7 @@ impl<'a> AsWide for &'a OsString {
     }
 }
 
+#[cfg(windows)]
 impl<'a> AsWide for &'a Path {
     type Iter = std::os::windows::ffi::EncodeWide<'a>;
 
@@ -71,6 +113,7 @@ impl<'a> AsWide for &'a Path {
     }
 }
 
+#[cfg(windows)]
 impl<'a> AsWide for &'a PathBuf {
     type Iter = std::os::windows::ffi::EncodeWide<'a>;
 
@@ -78,3 +121,128 @@ impl<'a> AsWide for &'a PathBuf {
         self.as_os_str().encode_wide()
     }
 }
+
+/// Encode an `OsStr` as UTF-16.
+///
+/// Outside of Windows, `OsStr`s are not UTF-16.
+/// On Unix, unpaired surrogates encoded as WTF-8 by [`os_string_from_wide`] are decoded,
+/// and other invalid unicode is replaced.
+#[cfg(not(windows))]
+fn encode_os_str(value: &OsStr) -> std::vec::IntoIter<u16> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        let mut wide = Vec::with_capacity(value.len());
+        let mut bytes = value.as_bytes();
+        while !bytes.is_empty() {
+            let error = match std::str::from_utf8(bytes) {
+                Ok(valid) => {
+                    wide.extend(valid.encode_utf16());
+                    break;
+                }
+                Err(error) => error,
+            };
+
+            let (valid, rest) = bytes.split_at(error.valid_up_to());
+            wide.extend(std::str::from_utf8(valid).unwrap().encode_utf16());
+            bytes = match *rest {
+                // The 3 byte form of a surrogate, which is invalid UTF-8.
+                [0xED, b1 @ 0xA0..=0xBF, b2 @ 0x80..=0xBF, ..] => {
+                    wide.push(0xD000 | (u16::from(b1 & 0x3F) << 6) | u16::from(b2 & 0x3F));
+                    &rest[3..]
+                }
+                _ => {
+                    wide.push(char::REPLACEMENT_CHARACTER as u16);
+                    &rest[error.error_len().unwrap_or(rest.len())..]
+                }
+            };
+        }
+        wide.into_iter()
+    }
+
+    #[cfg(not(unix))]
+    {
+        value
+            .to_string_lossy()
+            .encode_utf16()
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+#[cfg(not(windows))]
+impl AsWide for &OsStr {
+    type Iter = std::vec::IntoIter<u16>;
+
+    fn as_wide(&self) -> Self::Iter {
+        encode_os_str(self)
+    }
+}
+
+#[cfg(not(windows))]
+impl AsWide for &OsString {
+    type Iter = std::vec::IntoIter<u16>;
+
+    fn as_wide(&self) -> Self::Iter {
+        encode_os_str(self)
+    }
+}
+
+#[cfg(not(windows))]
+impl AsWide for &Path {
+    type Iter = std::vec::IntoIter<u16>;
+
+    fn as_wide(&self) -> Self::Iter {
+        encode_os_str(self.as_os_str())
+    }
+}
+
+#[cfg(not(windows))]
+impl AsWide for &PathBuf {
+    type Iter = std::vec::IntoIter<u16>;
+
+    fn as_wide(&self) -> Self::Iter {
+        encode_os_str(self.as_os_str())
+    }
+}
+
+/// Convert wide chars to an `OsString`, keeping unpaired surrogates.
+#[cfg(windows)]
+pub(crate) fn os_string_from_wide(wide: &[u16]) -> OsString {
+    std::os::windows::ffi::OsStringExt::from_wide(wide)
+}
+
+/// Convert wide chars to an `OsString`, keeping unpaired surrogates.
+///
+/// On Unix, the result is WTF-8, which encodes unpaired surrogates like other chars,
+/// so it is valid UTF-8 whenever the wide chars are valid UTF-16.
+/// Other hosts cannot hold unpaired surrogates, so they are replaced.
+#[cfg(not(windows))]
+pub(crate) fn os_string_from_wide(wide: &[u16]) -> OsString {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+
+        let mut bytes = Vec::with_capacity(wide.len());
+        for c in char::decode_utf16(wide.iter().copied()) {
+            match c {
+                Ok(c) => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+                Err(error) => {
+                    let surrogate = error.unpaired_surrogate();
+                    bytes.extend_from_slice(&[
+                        0xE0 | (surrogate >> 12) as u8,
+                        0x80 | ((surrogate >> 6) & 0x3F) as u8,
+                        0x80 | (surrogate & 0x3F) as u8,
+                    ]);
+                }
+            }
+        }
+        OsString::from_vec(bytes)
+    }
+
+    #[cfg(not(unix))]
+    {
+        OsString::from(String::from_utf16_lossy(wide))
+    }
+}
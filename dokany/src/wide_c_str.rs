@@ -0,0 +1,111 @@
+use crate::NulError;
+use crate::WideCString;
+use crate::WideStr;
+use std::ops::Deref;
+
+/// A borrowed NUL-terminated wide string, with no interior NULs.
+///
+/// This derefs to a [`WideStr`] without the NUL terminator.
+#[repr(transparent)]
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct WideCStr {
+    /// The wide chars, including the NUL terminator.
+    inner: [u16],
+}
+
+impl WideCStr {
+    /// Wrap a NUL-terminated string from a pointer.
+    ///
+    /// # Safety
+    /// `ptr` must point to a NUL-terminated wide string that is valid and unchanged for `'a`.
+    pub unsafe fn from_ptr<'a>(ptr: *const u16) -> &'a Self {
+        let mut len = 0;
+        while *ptr.add(len) != 0 {
+            len += 1;
+        }
+        Self::from_slice_with_nul_unchecked(std::slice::from_raw_parts(ptr, len + 1))
+    }
+
+    /// Wrap a slice that ends with its only NUL.
+    ///
+    /// # Errors
+    /// Returns an error if the slice has an interior NUL or does not end with a NUL.
+    pub fn from_slice_with_nul(slice: &[u16]) -> Result<&Self, NulError> {
+        match slice.iter().position(|&c| c == 0) {
+            Some(position) if position + 1 == slice.len() => {
+                Ok(unsafe { Self::from_slice_with_nul_unchecked(slice) })
+            }
+            Some(position) => Err(NulError::Interior(position)),
+            None => Err(NulError::NotTerminated),
+        }
+    }
+
+    /// Wrap a slice that ends with its only NUL, without checking.
+    ///
+    /// # Safety
+    /// The slice must end with a NUL and have no other NULs.
+    pub unsafe fn from_slice_with_nul_unchecked(slice: &[u16]) -> &Self {
+        // WideCStr is a transparent wrapper around [u16].
+        &*(slice as *const [u16] as *const Self)
+    }
+
+    /// Get a pointer to the NUL-terminated wide chars.
+    pub fn as_ptr(&self) -> *const u16 {
+        self.inner.as_ptr()
+    }
+
+    /// Get the wide chars, without the NUL terminator.
+    pub fn as_slice(&self) -> &[u16] {
+        &self.inner[..self.inner.len() - 1]
+    }
+
+    /// Get the wide chars, including the NUL terminator.
+    pub fn as_slice_with_nul(&self) -> &[u16] {
+        &self.inner
+    }
+
+    /// Borrow as a [`WideStr`], without the NUL terminator.
+    pub fn as_wide_str(&self) -> &WideStr {
+        WideStr::from_slice(self.as_slice())
+    }
+}
+
+impl Deref for WideCStr {
+    type Target = WideStr;
+
+    fn deref(&self) -> &WideStr {
+        self.as_wide_str()
+    }
+}
+
+impl ToOwned for WideCStr {
+    type Owned = WideCString;
+
+    fn to_owned(&self) -> WideCString {
+        WideCString::from(self)
+    }
+}
+
+impl AsRef<WideCStr> for WideCStr {
+    fn as_ref(&self) -> &WideCStr {
+        self
+    }
+}
+
+impl AsRef<WideStr> for WideCStr {
+    fn as_ref(&self) -> &WideStr {
+        self.as_wide_str()
+    }
+}
+
+impl std::fmt::Display for WideCStr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.as_wide_str(), f)
+    }
+}
+
+impl std::fmt::Debug for WideCStr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self.as_wide_str(), f)
+    }
+}
@@ -0,0 +1,152 @@
+use crate::AsWide;
+use crate::NulError;
+use crate::WideCStr;
+use crate::WideStr;
+use crate::WideString;
+use std::borrow::Borrow;
+use std::ops::Deref;
+
+/// An owned NUL-terminated wide string, with no interior NULs.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct WideCString {
+    /// The wide chars, including the NUL terminator.
+    inner: Vec<u16>,
+}
+
+impl WideCString {
+    /// Make a NUL-terminated copy of a wide string.
+    ///
+    /// # Errors
+    /// Returns an error if the string has a NUL.
+    pub fn new(value: impl AsWide) -> Result<Self, NulError> {
+        Self::from_vec(value.as_wide().collect::<Vec<_>>())
+    }
+
+    /// Add a NUL terminator to a vector of wide chars.
+    ///
+    /// # Errors
+    /// Returns an error if the vector has a NUL.
+    pub fn from_vec(vec: impl Into<Vec<u16>>) -> Result<Self, NulError> {
+        let mut inner = vec.into();
+        if let Some(position) = inner.iter().position(|&c| c == 0) {
+            return Err(NulError::Interior(position));
+        }
+        inner.push(0);
+
+        Ok(Self { inner })
+    }
+
+    /// Get the wide chars, without the NUL terminator.
+    pub fn into_vec(mut self) -> Vec<u16> {
+        self.inner.pop();
+        self.inner
+    }
+
+    /// Get the wide chars, including the NUL terminator.
+    pub fn into_vec_with_nul(self) -> Vec<u16> {
+        self.inner
+    }
+
+    /// Borrow as a [`WideCStr`].
+    pub fn as_wide_c_str(&self) -> &WideCStr {
+        unsafe { WideCStr::from_slice_with_nul_unchecked(&self.inner) }
+    }
+}
+
+impl Default for WideCString {
+    fn default() -> Self {
+        Self { inner: vec![0] }
+    }
+}
+
+impl Deref for WideCString {
+    type Target = WideCStr;
+
+    fn deref(&self) -> &WideCStr {
+        self.as_wide_c_str()
+    }
+}
+
+impl Borrow<WideCStr> for WideCString {
+    fn borrow(&self) -> &WideCStr {
+        self.as_wide_c_str()
+    }
+}
+
+impl AsRef<WideCStr> for WideCString {
+    fn as_ref(&self) -> &WideCStr {
+        self.as_wide_c_str()
+    }
+}
+
+impl AsRef<WideStr> for WideCString {
+    fn as_ref(&self) -> &WideStr {
+        self.as_wide_str()
+    }
+}
+
+impl From<&WideCStr> for WideCString {
+    fn from(value: &WideCStr) -> Self {
+        Self {
+            inner: value.as_slice_with_nul().to_vec(),
+        }
+    }
+}
+
+impl TryFrom<WideString> for WideCString {
+    type Error = NulError;
+
+    fn try_from(value: WideString) -> Result<Self, Self::Error> {
+        Self::from_vec(value.into_vec())
+    }
+}
+
+impl From<WideCString> for WideString {
+    fn from(value: WideCString) -> Self {
+        WideString::from_vec(value.into_vec())
+    }
+}
+
+impl std::fmt::Display for WideCString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.as_wide_str(), f)
+    }
+}
+
+impl std::fmt::Debug for WideCString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self.as_wide_str(), f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nul() {
+        let wide = WideCString::new("mount").unwrap();
+        assert_eq!(wide.as_slice_with_nul().last(), Some(&0));
+        assert_eq!(*wide.as_wide_str(), "mount");
+        assert_eq!(wide.to_string(), "mount");
+        assert_eq!(
+            unsafe { WideCStr::from_ptr(wide.as_ptr()) },
+            wide.as_wide_c_str()
+        );
+        assert_eq!(
+            wide.clone().into_vec(),
+            WideString::from("mount").into_vec()
+        );
+
+        assert_eq!(WideCString::new("a\0b"), Err(NulError::Interior(1)));
+        assert_eq!(
+            WideCStr::from_slice_with_nul(&[1, 0, 2, 0]),
+            Err(NulError::Interior(1))
+        );
+        assert_eq!(
+            WideCStr::from_slice_with_nul(&[1, 2]),
+            Err(NulError::NotTerminated)
+        );
+        assert!(WideCString::default().is_empty());
+    }
+}
@@ -0,0 +1,179 @@
+use crate::name_in_expression::to_upper;
+use crate::wide::os_string_from_wide;
+use crate::WideString;
+use std::ffi::OsString;
+use std::fmt::Write;
+use std::string::FromUtf16Error;
+
+/// A borrowed wide string.
+///
+/// Like file names on Windows, this may hold unpaired surrogates.
+#[repr(transparent)]
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct WideStr {
+    inner: [u16],
+}
+
+impl WideStr {
+    /// Wrap a slice of wide chars.
+    pub fn from_slice(slice: &[u16]) -> &Self {
+        // WideStr is a transparent wrapper around [u16].
+        unsafe { &*(slice as *const [u16] as *const Self) }
+    }
+
+    /// Get the wide chars.
+    pub fn as_slice(&self) -> &[u16] {
+        &self.inner
+    }
+
+    /// Get the length, in wide chars.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Check whether this is empty.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Convert to an `OsString`.
+    ///
+    /// On Windows, this is lossless.
+    /// On Unix, the result is UTF-8, with unpaired surrogates encoded as WTF-8,
+    /// which [`WideString::from`] decodes again.
+    pub fn to_os_string(&self) -> OsString {
+        os_string_from_wide(&self.inner)
+    }
+
+    /// Convert to a string, replacing unpaired surrogates with U+FFFD.
+    ///
+    /// Use [`String::try_from`] to reject unpaired surrogates instead.
+    pub fn to_string_lossy(&self) -> String {
+        String::from_utf16_lossy(&self.inner)
+    }
+
+    /// Compare with another string, ignoring case like Windows file names.
+    ///
+    /// Wide chars are compared one by one, so chars whose uppercase form has a different length only match themselves.
+    pub fn eq_ignore_case(&self, other: &WideStr) -> bool {
+        self.len() == other.len()
+            && self
+                .inner
+                .iter()
+                .zip(&other.inner)
+                .all(|(&a, &b)| a == b || to_upper(a) == to_upper(b))
+    }
+}
+
+impl ToOwned for WideStr {
+    type Owned = WideString;
+
+    fn to_owned(&self) -> WideString {
+        WideString::from_vec(self.inner.to_vec())
+    }
+}
+
+impl AsRef<[u16]> for WideStr {
+    fn as_ref(&self) -> &[u16] {
+        &self.inner
+    }
+}
+
+impl AsRef<WideStr> for WideStr {
+    fn as_ref(&self) -> &WideStr {
+        self
+    }
+}
+
+impl<'a> From<&'a [u16]> for &'a WideStr {
+    fn from(slice: &'a [u16]) -> Self {
+        WideStr::from_slice(slice)
+    }
+}
+
+impl PartialEq<str> for WideStr {
+    fn eq(&self, other: &str) -> bool {
+        self.inner.iter().copied().eq(other.encode_utf16())
+    }
+}
+
+impl PartialEq<&str> for WideStr {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+/// Fails if the string has unpaired surrogates.
+impl TryFrom<&WideStr> for String {
+    type Error = FromUtf16Error;
+
+    fn try_from(value: &WideStr) -> Result<Self, Self::Error> {
+        String::from_utf16(&value.inner)
+    }
+}
+
+/// Unpaired surrogates are displayed as U+FFFD.
+impl std::fmt::Display for WideStr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for c in char::decode_utf16(self.inner.iter().copied()) {
+            f.write_char(c.unwrap_or(char::REPLACEMENT_CHARACTER))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for WideStr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&self.to_string_lossy(), f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn case() {
+        let a = WideString::from("Straße.TXT");
+        let b = WideString::from("STRAßE.txt");
+        assert!(a.eq_ignore_case(&b));
+        assert!(!a.eq_ignore_case(&WideString::from("STRASSE.txt")));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn conversion() {
+        let wide = WideString::from("file.txt");
+        assert_eq!(wide, "file.txt");
+        assert_eq!(wide.to_string(), "file.txt");
+        assert_eq!(wide.to_os_string(), "file.txt");
+        assert_eq!(WideString::from(wide.to_os_string()), wide);
+
+        // Unpaired surrogates are kept until converted to a string.
+        let wide = [u16::from(b'a'), 0xDC00];
+        let wide = WideStr::from_slice(&wide);
+        assert!(String::try_from(wide).is_err());
+        assert_eq!(wide.to_string(), "a\u{FFFD}");
+        assert_eq!(format!("{wide:?}"), "\"a\u{FFFD}\"");
+        assert_eq!(wide.to_owned().as_slice(), wide.as_slice());
+
+        // Converting to an OsString and back is lossless, even next to a surrogate pair.
+        let mixed = [0xD83D, 0xDE00, 0xD800, u16::from(b'b'), 0xDBFF];
+        #[cfg(any(windows, unix))]
+        for wide in [wide.as_slice(), &mixed] {
+            let wide = WideStr::from_slice(wide);
+            assert_eq!(
+                WideString::from(wide.to_os_string()).as_slice(),
+                wide.as_slice()
+            );
+        }
+
+        // Other invalid unicode is replaced.
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            let os_str = std::ffi::OsStr::from_bytes(b"a\xFF\xED\xA0");
+            assert_eq!(WideString::from(os_str), "a\u{FFFD}\u{FFFD}\u{FFFD}");
+        }
+    }
+}
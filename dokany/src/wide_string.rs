@@ -0,0 +1,136 @@
+use crate::AsWide;
+use crate::WideStr;
+use std::borrow::Borrow;
+use std::ffi::OsStr;
+use std::ffi::OsString;
+use std::ops::Deref;
+
+/// An owned wide string.
+///
+/// Like file names on Windows, this may hold unpaired surrogates.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct WideString {
+    inner: Vec<u16>,
+}
+
+impl WideString {
+    /// Make an empty string.
+    pub fn new() -> Self {
+        Self { inner: Vec::new() }
+    }
+
+    /// Wrap a vector of wide chars.
+    pub fn from_vec(vec: impl Into<Vec<u16>>) -> Self {
+        Self { inner: vec.into() }
+    }
+
+    /// Get the wide chars.
+    pub fn into_vec(self) -> Vec<u16> {
+        self.inner
+    }
+
+    /// Borrow as a [`WideStr`].
+    pub fn as_wide_str(&self) -> &WideStr {
+        WideStr::from_slice(&self.inner)
+    }
+
+    /// Append wide chars to the end.
+    pub fn push(&mut self, value: impl AsWide) {
+        self.inner.extend(value.as_wide());
+    }
+}
+
+impl Default for WideString {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for WideString {
+    type Target = WideStr;
+
+    fn deref(&self) -> &WideStr {
+        self.as_wide_str()
+    }
+}
+
+impl Borrow<WideStr> for WideString {
+    fn borrow(&self) -> &WideStr {
+        self.as_wide_str()
+    }
+}
+
+impl AsRef<WideStr> for WideString {
+    fn as_ref(&self) -> &WideStr {
+        self.as_wide_str()
+    }
+}
+
+impl AsRef<[u16]> for WideString {
+    fn as_ref(&self) -> &[u16] {
+        &self.inner
+    }
+}
+
+impl From<Vec<u16>> for WideString {
+    fn from(vec: Vec<u16>) -> Self {
+        Self::from_vec(vec)
+    }
+}
+
+impl From<&WideStr> for WideString {
+    fn from(value: &WideStr) -> Self {
+        value.to_owned()
+    }
+}
+
+impl From<&str> for WideString {
+    fn from(value: &str) -> Self {
+        Self::from_vec(value.encode_utf16().collect::<Vec<_>>())
+    }
+}
+
+impl From<String> for WideString {
+    fn from(value: String) -> Self {
+        Self::from(value.as_str())
+    }
+}
+
+/// On Windows, this is lossless.
+/// Elsewhere, `OsStr`s are not UTF-16, so invalid unicode is replaced,
+/// except for unpaired surrogates encoded as WTF-8 on Unix.
+impl From<&OsStr> for WideString {
+    fn from(value: &OsStr) -> Self {
+        Self::from_vec(value.as_wide().collect::<Vec<_>>())
+    }
+}
+
+impl From<OsString> for WideString {
+    fn from(value: OsString) -> Self {
+        Self::from(value.as_os_str())
+    }
+}
+
+impl PartialEq<str> for WideString {
+    fn eq(&self, other: &str) -> bool {
+        *self.as_wide_str() == *other
+    }
+}
+
+impl PartialEq<&str> for WideString {
+    fn eq(&self, other: &&str) -> bool {
+        *self.as_wide_str() == **other
+    }
+}
+
+impl std::fmt::Display for WideString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.as_wide_str(), f)
+    }
+}
+
+impl std::fmt::Debug for WideString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self.as_wide_str(), f)
+    }
+}